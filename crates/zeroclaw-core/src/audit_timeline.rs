@@ -0,0 +1,282 @@
+use crate::control_plane::{ActionReceipt, ControlPlaneStore, ReceiptResult};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditSource {
+    Receipt,
+    WrapperAudit,
+}
+
+/// One row of the merged timeline. Wrapper events carry the `receipt_id`
+/// they reference; receipts list the wrapper audit lines that point at them.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuditTimelineEntry {
+    pub timestamp: String,
+    pub source: AuditSource,
+    pub actor_id: Option<String>,
+    pub action: Option<String>,
+    pub receipt_id: Option<String>,
+    /// 1-based line in the wrapper audit log, for wrapper events.
+    #[serde(default)]
+    pub audit_line: Option<usize>,
+    #[serde(default)]
+    pub linked_audit_lines: Vec<usize>,
+    pub body: Value,
+}
+
+/// Merges control-plane receipts with the wrapper's JSONL audit log into one
+/// chronological timeline, newest first. Lines that are not JSON objects or
+/// have no parseable timestamp are skipped rather than failing the view.
+pub fn unified_audit_view(
+    control_plane: &ControlPlaneStore,
+    wrapper_audit_log: &Path,
+    limit: usize,
+) -> Result<Vec<AuditTimelineEntry>> {
+    let mut entries = timeline_entries(control_plane, wrapper_audit_log)?;
+    entries.truncate(limit.clamp(1, 10_000));
+    Ok(entries)
+}
+
+/// The full merged timeline, newest first, without the view's row cap.
+fn timeline_entries(
+    control_plane: &ControlPlaneStore,
+    wrapper_audit_log: &Path,
+) -> Result<Vec<AuditTimelineEntry>> {
+    let mut entries = Vec::new();
+    let mut links: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+
+    if wrapper_audit_log.exists() {
+        let body = fs::read_to_string(wrapper_audit_log)
+            .with_context(|| format!("failed to read {}", wrapper_audit_log.display()))?;
+        for (index, line) in body.lines().enumerate() {
+            let Ok(event) = serde_json::from_str::<Value>(line) else {
+                continue;
+            };
+            let Some(timestamp) = first_str(&event, &["timestamp", "ts", "at"]).and_then(parse)
+            else {
+                continue;
+            };
+            let receipt_id = first_str(&event, &["receipt_id"]).map(str::to_string);
+            if let Some(receipt_id) = &receipt_id {
+                links.entry(receipt_id.clone()).or_default().push(index + 1);
+            }
+            entries.push(AuditTimelineEntry {
+                timestamp: timestamp.to_rfc3339(),
+                source: AuditSource::WrapperAudit,
+                actor_id: first_str(&event, &["actor_id", "actor"]).map(str::to_string),
+                action: first_str(&event, &["action", "event", "kind"]).map(str::to_string),
+                receipt_id,
+                audit_line: Some(index + 1),
+                linked_audit_lines: Vec::new(),
+                body: event,
+            });
+        }
+    }
+
+    for receipt in control_plane.load()?.receipts {
+        entries.push(receipt_entry(receipt, &mut links));
+    }
+
+    entries.sort_by(|a, b| {
+        parse(&b.timestamp)
+            .cmp(&parse(&a.timestamp))
+            .then(b.audit_line.cmp(&a.audit_line))
+    });
+    Ok(entries)
+}
+
+/// What one actor did, across receipts and wrapper audit events, for
+/// reviewing an operator before changing their role.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ActorActivity {
+    pub actor_id: String,
+    pub receipts: u64,
+    pub wrapper_events: u64,
+    pub denied: u64,
+    pub pending_approval: u64,
+    pub by_action: BTreeMap<String, u64>,
+    pub first_seen: Option<String>,
+    pub last_seen: Option<String>,
+}
+
+pub fn actor_activity(
+    control_plane: &ControlPlaneStore,
+    wrapper_audit_log: &Path,
+    actor_id: &str,
+) -> Result<ActorActivity> {
+    let mut activity = ActorActivity {
+        actor_id: actor_id.to_string(),
+        ..ActorActivity::default()
+    };
+    let entries = timeline_entries(control_plane, wrapper_audit_log)?;
+    for entry in entries
+        .into_iter()
+        .filter(|entry| entry.actor_id.as_deref() == Some(actor_id))
+    {
+        match entry.source {
+            AuditSource::Receipt => {
+                activity.receipts += 1;
+                match serde_json::from_value(entry.body["result"].clone()) {
+                    Ok(ReceiptResult::Denied) => activity.denied += 1,
+                    Ok(ReceiptResult::PendingApproval) => activity.pending_approval += 1,
+                    _ => {}
+                }
+            }
+            AuditSource::WrapperAudit => activity.wrapper_events += 1,
+        }
+        if let Some(action) = entry.action {
+            *activity.by_action.entry(action).or_default() += 1;
+        }
+        if activity.last_seen.is_none() {
+            activity.last_seen = Some(entry.timestamp.clone());
+        }
+        activity.first_seen = Some(entry.timestamp);
+    }
+    Ok(activity)
+}
+
+fn receipt_entry(
+    receipt: ActionReceipt,
+    links: &mut BTreeMap<String, Vec<usize>>,
+) -> AuditTimelineEntry {
+    AuditTimelineEntry {
+        timestamp: receipt.timestamp.clone(),
+        source: AuditSource::Receipt,
+        actor_id: Some(receipt.actor_id.clone()),
+        action: Some(receipt.action.clone()),
+        receipt_id: Some(receipt.id.clone()),
+        audit_line: None,
+        linked_audit_lines: links.remove(&receipt.id).unwrap_or_default(),
+        body: serde_json::to_value(&receipt).unwrap_or(Value::Null),
+    }
+}
+
+fn first_str<'a>(event: &'a Value, keys: &[&str]) -> Option<&'a str> {
+    keys.iter()
+        .find_map(|key| event.get(*key).and_then(Value::as_str))
+}
+
+fn parse(raw: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(raw)
+        .ok()
+        .map(|value| value.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control_plane::tests::request;
+    use crate::control_plane::ActionPolicyRequest;
+    use tempfile::TempDir;
+
+    #[test]
+    fn merges_and_cross_links_both_trails() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
+        let decision = store
+            .evaluate_action(request("operator", "runtime.start", "runtime", "local"))
+            .unwrap();
+
+        let log = tmp.path().join(".right-hand-audit.jsonl");
+        let later = (Utc::now() + chrono::Duration::seconds(5)).to_rfc3339();
+        fs::write(
+            &log,
+            format!(
+                "{}\nnot json\n{}\n",
+                serde_json::json!({
+                    "timestamp": "2020-01-01T00:00:00Z",
+                    "action": "app.opened",
+                    "actor_id": "operator-a"
+                }),
+                serde_json::json!({
+                    "timestamp": later,
+                    "action": "runtime_start",
+                    "receipt_id": decision.receipt_id
+                })
+            ),
+        )
+        .unwrap();
+
+        let timeline = unified_audit_view(&store, &log, 10).unwrap();
+        assert_eq!(timeline.len(), 3);
+        assert_eq!(timeline[0].source, AuditSource::WrapperAudit);
+        assert_eq!(timeline[0].audit_line, Some(3));
+        assert_eq!(
+            timeline[0].receipt_id.as_deref(),
+            Some(decision.receipt_id.as_str())
+        );
+        assert_eq!(timeline[1].source, AuditSource::Receipt);
+        assert_eq!(timeline[1].linked_audit_lines, vec![3]);
+        assert_eq!(timeline[2].action.as_deref(), Some("app.opened"));
+    }
+
+    #[test]
+    fn actor_activity_counts_one_actor_across_both_trails() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
+        for role in ["viewer", "viewer", "owner"] {
+            store
+                .evaluate_action(ActionPolicyRequest {
+                    actor_id: "dana".into(),
+                    ..request(role, "channels.add", "telegram", "local")
+                })
+                .unwrap();
+        }
+        let log = tmp.path().join(".right-hand-audit.jsonl");
+        fs::write(
+            &log,
+            format!(
+                "{}\n{}\n",
+                serde_json::json!({
+                    "timestamp": "2020-01-01T00:00:00Z",
+                    "action": "app.opened",
+                    "actor_id": "dana"
+                }),
+                serde_json::json!({
+                    "timestamp": "2020-01-02T00:00:00Z",
+                    "action": "app.opened",
+                    "actor_id": "someone-else"
+                })
+            ),
+        )
+        .unwrap();
+
+        let activity = actor_activity(&store, &log, "dana").unwrap();
+        assert_eq!(activity.receipts, 3);
+        assert_eq!(activity.wrapper_events, 1);
+        assert_eq!(activity.denied, 2);
+        assert_eq!(activity.by_action["channels.add"], 3);
+        assert_eq!(
+            activity.first_seen.as_deref(),
+            Some("2020-01-01T00:00:00+00:00")
+        );
+    }
+
+    #[test]
+    fn actor_activity_is_not_capped_by_the_view_limit() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
+        let log = tmp.path().join(".right-hand-audit.jsonl");
+        let line = serde_json::json!({
+            "timestamp": "2020-01-01T00:00:00Z",
+            "action": "app.opened",
+            "actor_id": "dana"
+        })
+        .to_string();
+        fs::write(&log, format!("{line}\n").repeat(10_001)).unwrap();
+
+        assert_eq!(
+            unified_audit_view(&store, &log, usize::MAX).unwrap().len(),
+            10_000
+        );
+        let activity = actor_activity(&store, &log, "dana").unwrap();
+        assert_eq!(activity.wrapper_events, 10_001);
+    }
+}
@@ -1,12 +1,33 @@
+use crate::events::{EventBus, RuntimeEvent, RuntimeEventKind};
+use crate::integrity::IntegrityMonitor;
+use crate::protocol::CORE_PROTOCOL_VERSION;
+use crate::provider_regions::{provider_endpoint_key, provider_request_denial};
+use crate::recovery::{backup_path, quarantine_corrupt};
+use crate::workspace_crypto::{file_label, WorkspaceCipher};
 use anyhow::{Context, Result};
-use chrono::{DateTime, Duration, Utc};
+use base64::Engine;
+use chrono::{DateTime, Duration, Timelike, Utc};
+use parking_lot::Mutex;
+use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::BTreeMap;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
+use std::net::IpAddr;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
 
 const CONTROL_PLANE_FILE: &str = "control_plane.json";
+/// Extension of the append-only receipt journal kept beside a state file.
+pub(crate) const RECEIPT_JOURNAL_EXTENSION: &str = "receipts.jsonl";
+/// After the trial ends the personal view stays usable this long, giving the
+/// user time to convert before the workspace locks.
+const TRIAL_GRACE_HOURS: i64 = 72;
+/// Above this many receipts the JSON backend stops pretty-printing; the
+/// indentation alone roughly doubles the file size of large stores.
+const PRETTY_JSON_MAX_RECEIPTS: usize = 500;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -30,6 +51,27 @@ pub struct AccessState {
     pub trial_started_at: Option<String>,
     pub trial_expires_at: Option<String>,
     pub updated_at: String,
+    /// Set once the lapsed trial has been downgraded to the personal view.
+    #[serde(default)]
+    pub trial_downgraded_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TrialPhase {
+    NotOnTrial,
+    Active,
+    Grace,
+    Expired,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TrialStatus {
+    pub phase: TrialPhase,
+    pub expires_at: Option<String>,
+    pub grace_ends_at: Option<String>,
+    /// Seconds until the current phase ends; zero once expired.
+    pub remaining_secs: i64,
 }
 
 impl Default for AccessState {
@@ -40,6 +82,7 @@ impl Default for AccessState {
             trial_started_at: None,
             trial_expires_at: None,
             updated_at: Utc::now().to_rfc3339(),
+            trial_downgraded_at: None,
         }
     }
 }
@@ -52,6 +95,29 @@ impl AccessState {
         self.trial_started_at = Some(now.to_rfc3339());
         self.trial_expires_at = Some((now + Duration::hours(24)).to_rfc3339());
         self.updated_at = now.to_rfc3339();
+        self.trial_downgraded_at = None;
+    }
+
+    pub fn trial_status(&self, now: DateTime<Utc>) -> TrialStatus {
+        let expires = self.trial_expires_at.as_deref().and_then(parse_rfc3339);
+        let grace_ends = expires.map(|expires| expires + Duration::hours(TRIAL_GRACE_HOURS));
+        let (phase, until) = match (&self.plan, expires, grace_ends) {
+            (AccessPlan::Trial, Some(expires), _) if now < expires => {
+                (TrialPhase::Active, Some(expires))
+            }
+            (AccessPlan::Trial, _, Some(grace_ends)) if now < grace_ends => {
+                (TrialPhase::Grace, Some(grace_ends))
+            }
+            (AccessPlan::Trial, _, _) => (TrialPhase::Expired, None),
+            _ => (TrialPhase::NotOnTrial, None),
+        };
+
+        TrialStatus {
+            phase,
+            expires_at: expires.map(|at| at.to_rfc3339()),
+            grace_ends_at: grace_ends.map(|at| at.to_rfc3339()),
+            remaining_secs: until.map_or(0, |until| (until - now).num_seconds().max(0)),
+        }
     }
 
     pub fn set_paid_plan(&mut self, plan: AccessPlan) -> Result<()> {
@@ -84,7 +150,11 @@ impl AccessState {
 
     pub fn can_access_view(&self, view: &WorkspaceView) -> bool {
         match self.plan {
-            AccessPlan::Trial => self.is_trial_active(),
+            AccessPlan::Trial => {
+                self.is_trial_active()
+                    || (matches!(view, WorkspaceView::Personal)
+                        && self.trial_status(Utc::now()).phase == TrialPhase::Grace)
+            }
             AccessPlan::Personal => matches!(view, WorkspaceView::Personal),
             AccessPlan::Org => matches!(view, WorkspaceView::Org),
         }
@@ -109,10 +179,18 @@ impl WorkspaceView {
     }
 }
 
+/// Days to keep each class of workspace data. Receipts, approvals and
+/// diagnostics logs are purged by the core; the shell applies the remaining
+/// classes to the stores it owns.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
 pub struct RetentionPolicy {
     pub receipts_days: u32,
     pub approvals_days: u32,
+    pub audit_events_days: u32,
+    pub outcomes_days: u32,
+    pub completed_tasks_days: u32,
+    pub diagnostics_logs_days: u32,
 }
 
 impl Default for RetentionPolicy {
@@ -120,10 +198,106 @@ impl Default for RetentionPolicy {
         Self {
             receipts_days: 30,
             approvals_days: 90,
+            audit_events_days: 365,
+            outcomes_days: 180,
+            completed_tasks_days: 90,
+            diagnostics_logs_days: 14,
+        }
+    }
+}
+
+/// Sensitive action classes (secrets, rollout, RBAC, billing) only pass the
+/// policy gate when the caller re-authenticated within the freshness window.
+/// Off until a workspace opts in through `set_reauth_policy`, so existing
+/// callers that never send `authenticated_at` keep working.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReauthPolicy {
+    pub enabled: bool,
+    pub freshness_secs: u64,
+    pub sensitive_action_classes: Vec<String>,
+}
+
+impl Default for ReauthPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            freshness_secs: 300,
+            sensitive_action_classes: vec![
+                "secrets".into(),
+                "rollout".into(),
+                "rbac".into(),
+                "billing".into(),
+            ],
+        }
+    }
+}
+
+impl ReauthPolicy {
+    pub fn is_sensitive(&self, action: &str) -> bool {
+        self.enabled
+            && self.sensitive_action_classes.iter().any(|class| {
+                action == class
+                    || action
+                        .strip_prefix(class.as_str())
+                        .is_some_and(|rest| rest.starts_with('.'))
+            })
+    }
+
+    pub fn is_fresh(&self, authenticated_at: Option<&str>, now: DateTime<Utc>) -> bool {
+        let window = Duration::seconds(i64::try_from(self.freshness_secs).unwrap_or(i64::MAX));
+        authenticated_at
+            .and_then(parse_rfc3339)
+            .is_some_and(|authenticated| {
+                authenticated <= now + Duration::seconds(30) && now - authenticated <= window
+            })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditVerbosity {
+    #[default]
+    Always,
+    /// Keeps one in `sample_every` allowed receipts.
+    Sampled,
+    DeniedOnly,
+}
+
+/// Which allowed receipts are kept per action. Policy evaluation is not
+/// affected: denials and approval requests are always recorded, and so is
+/// every action under a rate-limited rule, since the limit counts receipts.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AuditVerbosityPolicy {
+    /// Keyed by exact action or by action class (`workflow` covers
+    /// `workflow.move`); the exact action wins.
+    #[serde(default)]
+    pub actions: BTreeMap<String, AuditVerbosity>,
+    pub sample_every: u32,
+}
+
+impl Default for AuditVerbosityPolicy {
+    fn default() -> Self {
+        Self {
+            actions: BTreeMap::new(),
+            sample_every: 10,
         }
     }
 }
 
+impl AuditVerbosityPolicy {
+    pub fn level_for(&self, action: &str) -> AuditVerbosity {
+        self.actions
+            .get(action)
+            .or_else(|| {
+                action
+                    .split_once('.')
+                    .and_then(|(class, _)| self.actions.get(class))
+            })
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct PolicyRule {
     pub id: String,
@@ -133,6 +307,14 @@ pub struct PolicyRule {
     pub destinations: Vec<String>,
     pub require_approval: bool,
     pub enabled: bool,
+    #[serde(default)]
+    pub allowed_hours: Option<AllowedHours>,
+    #[serde(default)]
+    pub max_invocations_per_hour: Option<u32>,
+    /// Higher priority wins when several rules match; ties fall back to the
+    /// order of the rule list.
+    #[serde(default)]
+    pub priority: i32,
 }
 
 impl PolicyRule {
@@ -143,6 +325,67 @@ impl PolicyRule {
             && matches_filter(&self.resources, &request.resource)
             && matches_filter(&self.destinations, &request.destination)
     }
+
+    /// Checks the rule's time-window and rate-limit conditions, returning the
+    /// denial reason when one of them blocks the request.
+    fn condition_violation(
+        &self,
+        receipts: &[ActionReceipt],
+        request: &ActionPolicyRequest,
+        now: DateTime<Utc>,
+    ) -> Option<String> {
+        if let Some(window) = &self.allowed_hours {
+            if !window.contains(now) {
+                return Some(format!(
+                    "rule '{}' only allows this action between {:02}:00 and {:02}:00",
+                    self.id, window.start_hour, window.end_hour
+                ));
+            }
+        }
+
+        if let Some(limit) = self.max_invocations_per_hour {
+            let since = now - Duration::hours(1);
+            let recent = receipts
+                .iter()
+                .filter(|receipt| {
+                    matches!(receipt.result, ReceiptResult::Allowed)
+                        && receipt.actor_id == request.actor_id
+                        && receipt.action == request.action
+                        && parse_rfc3339(&receipt.timestamp).is_some_and(|at| at > since)
+                })
+                .count();
+            if recent >= limit as usize {
+                return Some(format!(
+                    "rule '{}' allows at most {limit} invocations per hour",
+                    self.id
+                ));
+            }
+        }
+
+        None
+    }
+}
+
+/// Daily window, in workspace local time, during which a rule applies.
+/// `start_hour > end_hour` wraps past midnight (e.g. 22 to 6).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AllowedHours {
+    pub start_hour: u8,
+    pub end_hour: u8,
+    #[serde(default)]
+    pub utc_offset_minutes: i32,
+}
+
+impl AllowedHours {
+    fn contains(&self, now: DateTime<Utc>) -> bool {
+        let local = now + Duration::minutes(i64::from(self.utc_offset_minutes));
+        let hour = u8::try_from(local.hour()).unwrap_or_default();
+        match self.start_hour.cmp(&self.end_hour) {
+            std::cmp::Ordering::Less => hour >= self.start_hour && hour < self.end_hour,
+            std::cmp::Ordering::Greater => hour >= self.start_hour || hour < self.end_hour,
+            std::cmp::Ordering::Equal => true,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -154,8 +397,16 @@ pub struct ActionPolicyRequest {
     pub destination: String,
     #[serde(default)]
     pub approval_id: Option<String>,
+    /// When the caller says the action happened. Recorded on the receipt
+    /// only; policy is always enforced against the store's clock.
     #[serde(default)]
     pub occurred_at: Option<String>,
+    /// When the shell last verified the operator (OS biometric or passphrase).
+    #[serde(default)]
+    pub authenticated_at: Option<String>,
+    /// User the actor is acting for; requires an active impersonation grant.
+    #[serde(default)]
+    pub on_behalf_of: Option<String>,
     #[serde(default)]
     pub context: BTreeMap<String, Value>,
 }
@@ -164,6 +415,8 @@ pub struct ActionPolicyRequest {
 pub struct ActionPolicyDecision {
     pub allowed: bool,
     pub requires_approval: bool,
+    #[serde(default)]
+    pub requires_reauth: bool,
     pub reason: String,
     pub approval_id: Option<String>,
     pub receipt_id: String,
@@ -189,9 +442,52 @@ pub struct ActionReceipt {
     pub result: ReceiptResult,
     pub reason: String,
     #[serde(default)]
+    pub on_behalf_of: Option<String>,
+    #[serde(default)]
     pub context: BTreeMap<String, Value>,
+    /// Inverse of this action, present on allowed receipts for reversible
+    /// actions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub undo: Option<UndoPlan>,
+    /// Policy revision whose rules produced this decision.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy_revision: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UndoPlan {
+    pub action: String,
+    pub resource: String,
+    pub destination: String,
+    pub description: String,
+}
+
+impl UndoPlan {
+    /// Builds the inverse for actions that have one, e.g. `cron.add` ->
+    /// `cron.remove` on the same resource.
+    pub fn for_action(action: &str, resource: &str, destination: &str) -> Option<Self> {
+        let inverse = UNDOABLE_ACTIONS
+            .iter()
+            .find(|(forward, _)| *forward == action)
+            .map(|(_, inverse)| *inverse)?;
+        Some(Self {
+            action: inverse.to_string(),
+            resource: resource.to_string(),
+            destination: destination.to_string(),
+            description: format!("run {inverse} on {resource} to revert {action}"),
+        })
+    }
 }
 
+const UNDOABLE_ACTIONS: &[(&str, &str)] = &[
+    ("channels.add", "channels.remove"),
+    ("cron.add", "cron.remove"),
+    ("integration.enable", "integration.disable"),
+    ("skills.enable", "skills.disable"),
+    ("mcp.enable", "mcp.disable"),
+    ("background.enable", "background.disable"),
+];
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum ApprovalStatus {
@@ -200,6 +496,13 @@ pub enum ApprovalStatus {
     Rejected,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ApproverIdentity {
+    pub user_id: String,
+    pub role: String,
+    pub active: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ApprovalRequest {
     pub id: String,
@@ -211,16 +514,278 @@ pub struct ApprovalRequest {
     pub destination: String,
     pub status: ApprovalStatus,
     pub decided_by: Option<String>,
+    #[serde(default)]
+    pub decided_by_user_id: Option<String>,
     pub decided_at: Option<String>,
+    /// Set when the approval was opened as part of a batch evaluation.
+    #[serde(default)]
+    pub group_id: Option<String>,
     pub reason: Option<String>,
     #[serde(default)]
     pub context: BTreeMap<String, Value>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BatchPolicyDecision {
+    /// One decision per planned action, in request order.
+    pub decisions: Vec<ActionPolicyDecision>,
+    /// Covers every approval the batch opened; resolve it with
+    /// [`ControlPlaneStore::resolve_approval_group`].
+    pub group_approval_id: Option<String>,
+}
+
+/// Outcome of a dry-run evaluation. Nothing is persisted: no receipt is
+/// written and no approval request is opened.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PolicySimulation {
+    pub allowed: bool,
+    pub requires_approval: bool,
+    pub requires_reauth: bool,
+    pub reason: String,
+    pub matched_rule: Option<PolicyRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CompactionReport {
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    pub removed_duplicate_receipts: usize,
+    pub removed_duplicate_approvals: usize,
+    pub removed_resolved_approvals: usize,
+    pub removed_closed_break_glass: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct PurgeSummary {
     pub removed_receipts: usize,
     pub removed_approvals: usize,
+    #[serde(default)]
+    pub removed_log_files: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakGlassStatus {
+    Requested,
+    Active,
+    Expired,
+    Revoked,
+}
+
+/// Emergency elevation of an operator to admin for a bounded window.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BreakGlassGrant {
+    pub id: String,
+    pub actor_id: String,
+    pub original_role: String,
+    pub reason: String,
+    pub duration_minutes: u32,
+    pub requested_at: String,
+    pub activated_at: Option<String>,
+    pub expires_at: Option<String>,
+    pub status: BreakGlassStatus,
+    #[serde(default)]
+    pub revoked_by: Option<String>,
+    #[serde(default)]
+    pub revoked_at: Option<String>,
+}
+
+impl BreakGlassGrant {
+    fn is_active(&self, now: DateTime<Utc>) -> bool {
+        matches!(self.status, BreakGlassStatus::Active)
+            && self
+                .expires_at
+                .as_deref()
+                .and_then(parse_rfc3339)
+                .is_some_and(|expires| now < expires)
+    }
+}
+
+/// Just-in-time elevation of a user to `role`, granted by an owner/admin for
+/// a bounded number of hours.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RoleGrant {
+    pub id: String,
+    pub actor_id: String,
+    pub role: String,
+    pub granted_by: String,
+    pub reason: String,
+    pub granted_at: String,
+    pub expires_at: String,
+    #[serde(default)]
+    pub revoked_at: Option<String>,
+    /// Set by the expiry sweep once the window has closed.
+    #[serde(default)]
+    pub expired_at: Option<String>,
+}
+
+impl RoleGrant {
+    fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none()
+            && self.expired_at.is_none()
+            && parse_rfc3339(&self.expires_at).is_some_and(|expires| now < expires)
+    }
+}
+
+/// Admin-approved permission for one user to act on behalf of another.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ImpersonationGrant {
+    pub id: String,
+    pub actor_id: String,
+    pub subject_id: String,
+    pub granted_by: String,
+    pub reason: String,
+    pub created_at: String,
+    pub expires_at: String,
+    #[serde(default)]
+    pub revoked_at: Option<String>,
+}
+
+impl ImpersonationGrant {
+    fn permits(&self, actor_id: &str, subject_id: &str, now: DateTime<Utc>) -> bool {
+        self.actor_id == actor_id
+            && self.subject_id == subject_id
+            && self.revoked_at.is_none()
+            && parse_rfc3339(&self.expires_at).is_some_and(|expires| now < expires)
+    }
+}
+
+/// Credential for scripts and services that call the host without a human
+/// behind them. Only the SHA-256 of the secret is stored.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MachineToken {
+    pub id: String,
+    pub name: String,
+    pub token_sha256: String,
+    /// Action patterns (`*` and `?` wildcards) this token may perform.
+    pub allowed_actions: Vec<String>,
+    pub created_by: String,
+    pub created_at: String,
+    #[serde(default)]
+    pub expires_at: Option<String>,
+    #[serde(default)]
+    pub last_used_at: Option<String>,
+    #[serde(default)]
+    pub revoked_at: Option<String>,
+}
+
+impl MachineToken {
+    fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none()
+            && self
+                .expires_at
+                .as_deref()
+                .is_none_or(|expires| parse_rfc3339(expires).is_some_and(|expires| now < expires))
+    }
+
+    pub fn actor_id(&self) -> String {
+        format!("machine:{}", self.id)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OffboardSummary {
+    pub actor_id: String,
+    pub cancelled_approvals: Vec<String>,
+    pub revoked_machine_tokens: Vec<String>,
+    pub revoked_role_grants: Vec<String>,
+    /// Grants where the user either impersonates or is impersonated.
+    pub revoked_impersonation_grants: Vec<String>,
+    /// Active and not-yet-activated break-glass grants.
+    pub revoked_break_glass: Vec<String>,
+    pub receipt_id: String,
+}
+
+/// A newly created machine token. `secret` is shown once and never stored.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IssuedMachineToken {
+    pub token: MachineToken,
+    pub secret: String,
+}
+
+/// A rule set as it stood from `effective_at` until the next revision.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PolicyRevision {
+    pub revision: u32,
+    pub effective_at: String,
+    pub rules: Vec<PolicyRule>,
+}
+
+/// A rule set scheduled to replace the live rules from `effective_from`, and
+/// to hand them back at `effective_until` when one is set.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StagedPolicy {
+    pub id: String,
+    pub name: String,
+    pub rules: Vec<PolicyRule>,
+    pub effective_from: String,
+    #[serde(default)]
+    pub effective_until: Option<String>,
+    pub staged_by: String,
+    pub staged_at: String,
+    #[serde(default)]
+    pub activated_at: Option<String>,
+    #[serde(default)]
+    pub activated_revision: Option<u32>,
+    /// Rules that were live before activation, restored at `effective_until`.
+    #[serde(default)]
+    pub replaced_rules: Option<Vec<PolicyRule>>,
+    #[serde(default)]
+    pub ended_at: Option<String>,
+}
+
+/// A recent decision that a staged rule set would decide differently.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StagedPolicyImpact {
+    pub receipt_id: String,
+    pub actor_id: String,
+    pub action: String,
+    pub resource: String,
+    pub current: ReceiptResult,
+    pub staged: ReceiptResult,
+}
+
+/// Control-plane state reconstructed as of a past moment.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ControlPlaneStateAt {
+    pub as_of: String,
+    pub policy_revision: Option<u32>,
+    pub policy_rules: Vec<PolicyRule>,
+    /// Approvals that existed at `as_of`, with decisions made later undone.
+    pub approvals: Vec<ApprovalRequest>,
+    pub recent_receipts: Vec<ActionReceipt>,
+    pub active_break_glass: Vec<BreakGlassGrant>,
+}
+
+/// Describes the runtime that produced a receipt, so exported receipts can
+/// be read without cross-referencing other files.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReceiptMetadata {
+    pub app_version: String,
+    pub protocol_version: String,
+    pub deployment_mode: String,
+    pub profile_id: String,
+    pub hostname: String,
+}
+
+impl ReceiptMetadata {
+    /// Fills in the protocol version and hostname of the current process.
+    pub fn detect(
+        app_version: impl Into<String>,
+        deployment_mode: impl Into<String>,
+        profile_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            app_version: app_version.into(),
+            protocol_version: CORE_PROTOCOL_VERSION.to_string(),
+            deployment_mode: deployment_mode.into(),
+            profile_id: profile_id.into(),
+            hostname: hostname::get()
+                .ok()
+                .and_then(|name| name.into_string().ok())
+                .unwrap_or_else(|| "unknown".into()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -229,8 +794,48 @@ pub struct ControlPlaneState {
     pub access_state: AccessState,
     pub policy_rules: Vec<PolicyRule>,
     pub retention: RetentionPolicy,
+    #[serde(default)]
+    pub reauth: ReauthPolicy,
     pub receipts: Vec<ActionReceipt>,
     pub approvals: Vec<ApprovalRequest>,
+    #[serde(default)]
+    pub break_glass: Vec<BreakGlassGrant>,
+    /// Domains (matching subdomains too) and IP ranges in CIDR notation that
+    /// no action may reach, whatever the policy rules allow.
+    #[serde(default)]
+    pub blocked_destinations: Vec<String>,
+    /// Data-residency allowlist in the same format: when set, network
+    /// destinations outside it are denied. Empty means unrestricted.
+    #[serde(default)]
+    pub residency_destinations: Vec<String>,
+    #[serde(default)]
+    pub policy_history: Vec<PolicyRevision>,
+    #[serde(default)]
+    pub impersonation_grants: Vec<ImpersonationGrant>,
+    #[serde(default)]
+    pub role_grants: Vec<RoleGrant>,
+    #[serde(default)]
+    pub machine_tokens: Vec<MachineToken>,
+    #[serde(default)]
+    pub staged_policies: Vec<StagedPolicy>,
+    /// Provider regions that provider-bound actions may use; empty means
+    /// unrestricted.
+    #[serde(default)]
+    pub allowed_provider_regions: Vec<String>,
+    /// Region each provider endpoint (API host, or provider id when no custom
+    /// URL is configured) serves from. Provider-bound actions take their
+    /// region from here rather than from the caller's claim.
+    #[serde(default)]
+    pub provider_endpoint_regions: BTreeMap<String, String>,
+    #[serde(default)]
+    pub audit_verbosity: AuditVerbosityPolicy,
+    /// Allowed evaluations seen per sampled action.
+    #[serde(default)]
+    pub audit_sample_counts: BTreeMap<String, u64>,
+    /// Runtime metadata stamped onto new receipts; supplied by the store, not
+    /// persisted with the state.
+    #[serde(skip)]
+    pub receipt_metadata: Option<ReceiptMetadata>,
 }
 
 impl Default for ControlPlaneState {
@@ -240,274 +845,894 @@ impl Default for ControlPlaneState {
             access_state: AccessState::default(),
             policy_rules: default_policy_rules(),
             retention: RetentionPolicy::default(),
+            reauth: ReauthPolicy::default(),
             receipts: Vec::new(),
             approvals: Vec::new(),
+            break_glass: Vec::new(),
+            blocked_destinations: Vec::new(),
+            residency_destinations: Vec::new(),
+            policy_history: Vec::new(),
+            impersonation_grants: Vec::new(),
+            role_grants: Vec::new(),
+            machine_tokens: Vec::new(),
+            staged_policies: Vec::new(),
+            allowed_provider_regions: Vec::new(),
+            provider_endpoint_regions: BTreeMap::new(),
+            audit_verbosity: AuditVerbosityPolicy::default(),
+            audit_sample_counts: BTreeMap::new(),
+            receipt_metadata: None,
         }
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct ControlPlaneStore {
-    path: PathBuf,
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReceiptQuery {
+    #[serde(default)]
+    pub actor_id: Option<String>,
+    #[serde(default)]
+    pub action: Option<String>,
+    #[serde(default)]
+    pub result: Option<ReceiptResult>,
+    #[serde(default)]
+    pub limit: usize,
 }
 
-impl ControlPlaneStore {
-    pub fn for_workspace(workspace_dir: &Path) -> Self {
-        Self {
-            path: workspace_dir.join(CONTROL_PLANE_FILE),
-        }
+impl ReceiptQuery {
+    pub fn capped_limit(&self) -> usize {
+        self.limit.clamp(1, 1000)
     }
 
-    pub fn load(&self) -> Result<ControlPlaneState> {
-        if !self.path.exists() {
-            let mut state = ControlPlaneState::default();
-            state.access_state.start_trial();
-            self.save(&state)?;
-            return Ok(state);
-        }
-
-        let body = fs::read_to_string(&self.path)
-            .with_context(|| format!("failed to read {}", self.path.display()))?;
-        let mut state: ControlPlaneState =
-            serde_json::from_str(&body).context("failed to parse control plane state")?;
-        self.normalize(&mut state);
-        Ok(state)
+    pub fn matches(&self, receipt: &ActionReceipt) -> bool {
+        self.actor_id
+            .as_ref()
+            .is_none_or(|actor_id| &receipt.actor_id == actor_id)
+            && self
+                .action
+                .as_ref()
+                .is_none_or(|action| &receipt.action == action)
+            && self
+                .result
+                .as_ref()
+                .is_none_or(|result| &receipt.result == result)
     }
+}
 
-    pub fn save(&self, state: &ControlPlaneState) -> Result<()> {
-        if let Some(parent) = self.path.parent() {
-            fs::create_dir_all(parent)
-                .with_context(|| format!("failed to create {}", parent.display()))?;
-        }
+/// Persistence backend behind [`ControlPlaneStore`]. Backends only move state
+/// in and out of storage; locking, defaults, and policy evaluation stay in the
+/// store so every backend behaves identically.
+pub trait ControlPlaneBackend: Send + Sync + std::fmt::Debug {
+    fn backend_name(&self) -> &'static str;
+    /// Storage location; stores opened on the same location share one lock.
+    fn location(&self) -> &Path;
+    fn read_state(&self) -> Result<Option<ControlPlaneState>>;
+    fn write_state(&self, state: &ControlPlaneState) -> Result<()>;
 
-        let body = serde_json::to_string_pretty(state)
-            .context("failed to serialize control plane state")?;
-        let tmp = self.path.with_extension("json.tmp");
-        fs::write(&tmp, body).with_context(|| format!("failed to write {}", tmp.display()))?;
-        fs::rename(&tmp, &self.path)
-            .with_context(|| format!("failed to replace {}", self.path.display()))?;
-        Ok(())
+    /// State holding only the receipts written after `since`, which is all
+    /// policy evaluation looks at. Backends with indexed receipts should
+    /// override this together with [`Self::write_recent_state`].
+    fn read_recent_state(&self, _since: DateTime<Utc>) -> Result<Option<ControlPlaneState>> {
+        self.read_state()
     }
 
-    pub fn get_state(&self) -> Result<ControlPlaneState> {
-        self.load()
+    /// Writes a state loaded by [`Self::read_recent_state`]. Receipts older
+    /// than its window were never loaded and must be kept.
+    fn write_recent_state(&self, state: &ControlPlaneState) -> Result<()> {
+        self.write_state(state)
     }
 
-    pub fn start_trial(&self) -> Result<AccessState> {
-        let mut state = self.load()?;
-        state.access_state.start_trial();
-        self.save(&state)?;
-        Ok(state.access_state)
+    /// Durably records `receipts` (oldest first) without touching the rest
+    /// of the state. Backends should override this with a cheap append.
+    fn append_receipts(&self, receipts: &[ActionReceipt]) -> Result<()> {
+        let mut state = self
+            .read_state()?
+            .context("control plane state is not initialized")?;
+        prepend_receipts(&mut state, receipts.to_vec());
+        self.write_state(&state)
     }
 
-    pub fn set_paid_plan(&self, plan: AccessPlan) -> Result<AccessState> {
-        let mut state = self.load()?;
-        state.access_state.set_paid_plan(plan)?;
-        self.save(&state)?;
-        Ok(state.access_state)
+    /// Receipts matching `query`, newest first.
+    fn query_receipts(&self, query: &ReceiptQuery) -> Result<Vec<ActionReceipt>> {
+        let receipts = self
+            .read_state()?
+            .map(|state| state.receipts)
+            .unwrap_or_default();
+        Ok(receipts
+            .into_iter()
+            .filter(|receipt| query.matches(receipt))
+            .take(query.capped_limit())
+            .collect())
     }
+}
 
-    pub fn set_active_view(&self, view: WorkspaceView) -> Result<AccessState> {
-        let mut state = self.load()?;
-        state.access_state.set_active_view(view)?;
-        self.save(&state)?;
-        Ok(state.access_state)
-    }
+/// Default backend: the whole state as one pretty-printed JSON document.
+#[derive(Debug, Clone)]
+pub struct JsonFileControlPlaneBackend {
+    path: PathBuf,
+    cipher: Option<Arc<WorkspaceCipher>>,
+}
 
-    pub fn evaluate_action(&self, request: ActionPolicyRequest) -> Result<ActionPolicyDecision> {
-        let mut state = self.load()?;
-        let now = request
-            .occurred_at
-            .as_deref()
-            .and_then(parse_rfc3339)
-            .unwrap_or_else(Utc::now);
+impl JsonFileControlPlaneBackend {
+    pub fn for_workspace(workspace_dir: &Path) -> Self {
+        Self {
+            path: workspace_dir.join(CONTROL_PLANE_FILE),
+            cipher: None,
+        }
+    }
 
-        let decision = if !state
-            .access_state
-            .can_access_view(&state.access_state.active_view)
-        {
-            let receipt = push_receipt(
-                &mut state,
-                &request,
-                ReceiptResult::Denied,
-                "access plan does not permit the current workspace view",
+    /// Encrypts the state file at rest. A plaintext state file is rejected
+    /// unless `cipher` accepts plaintext, in which case it is sealed on the
+    /// next write.
+    #[must_use]
+    pub fn with_cipher(mut self, cipher: Arc<WorkspaceCipher>) -> Self {
+        self.cipher = Some(cipher);
+        self
+    }
+
+    /// Receipts appended since the last full write, one per line.
+    fn journal_path(&self) -> PathBuf {
+        self.path.with_extension(RECEIPT_JOURNAL_EXTENSION)
+    }
+
+    /// Parses the state file. One that decrypts but no longer parses is
+    /// quarantined and replaced by its `.bak` copy, or by fresh defaults when
+    /// the backup is unusable too.
+    fn read_file(&self) -> Result<Option<ControlPlaneState>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+
+        let body = match &self.cipher {
+            Some(cipher) => cipher.read_to_string(&self.path)?,
+            None => fs::read_to_string(&self.path)
+                .with_context(|| format!("failed to read {}", self.path.display()))?,
+        };
+        if self.cipher.is_none() && WorkspaceCipher::is_sealed(&body) {
+            anyhow::bail!(
+                "{} is encrypted; open it with the workspace key",
+                self.path.display()
             );
-            ActionPolicyDecision {
-                allowed: false,
-                requires_approval: false,
-                reason: "access plan does not permit the current workspace view".into(),
-                approval_id: None,
-                receipt_id: receipt,
+        }
+        let error = match serde_json::from_str(&body) {
+            Ok(state) => return Ok(Some(state)),
+            Err(error) => error,
+        };
+
+        let backup = quarantine_corrupt(&self.path, "control plane state", &error, |backup| {
+            let body = fs::read_to_string(backup).ok()?;
+            let body = match &self.cipher {
+                Some(cipher) => {
+                    String::from_utf8(cipher.open(&file_label(&self.path), &body).ok()?).ok()?
+                }
+                None => body,
+            };
+            serde_json::from_str::<ControlPlaneState>(&body).ok()
+        })?;
+        if let Some(state) = &backup {
+            self.write_file(state)?;
+        }
+        Ok(backup)
+    }
+
+    /// Replaces the state file, keeping the previous one as `.bak`.
+    fn write_file(&self, state: &ControlPlaneState) -> Result<()> {
+        let body = if state.receipts.len() > PRETTY_JSON_MAX_RECEIPTS {
+            serde_json::to_string(state)
+        } else {
+            serde_json::to_string_pretty(state)
+        }
+        .context("failed to serialize control plane state")?;
+        if self.path.exists() {
+            fs::copy(&self.path, backup_path(&self.path))
+                .with_context(|| format!("failed to back up {}", self.path.display()))?;
+        }
+        if let Some(cipher) = &self.cipher {
+            cipher.write(&self.path, &body)
+        } else {
+            let tmp = self.path.with_extension("json.tmp");
+            fs::write(&tmp, body).with_context(|| format!("failed to write {}", tmp.display()))?;
+            fs::rename(&tmp, &self.path)
+                .with_context(|| format!("failed to replace {}", self.path.display()))
+        }
+    }
+
+    fn read_journal(&self) -> Result<Vec<ActionReceipt>> {
+        let path = self.journal_path();
+        let body = match fs::read_to_string(&path) {
+            Ok(body) => body,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => {
+                return Err(error).with_context(|| format!("failed to read {}", path.display()))
             }
-        } else if let Some(rule) = state
-            .policy_rules
-            .iter()
-            .find(|rule| rule.matches(&request))
-        {
-            if rule.require_approval {
-                if let Some(existing_approval_id) = request.approval_id.as_deref() {
-                    if let Some(approval) = state
-                        .approvals
-                        .iter()
-                        .find(|approval| approval.id == existing_approval_id)
-                    {
-                        let matches_request = approval.actor_id == request.actor_id
-                            && approval.actor_role == request.actor_role
-                            && approval.action == request.action
-                            && approval.resource == request.resource
-                            && approval.destination == request.destination;
-
-                        if !matches_request {
-                            let receipt = push_receipt(
-                                &mut state,
-                                &request,
-                                ReceiptResult::Denied,
-                                "approval does not match action request",
-                            );
-                            self.save(&state)?;
-                            return Ok(ActionPolicyDecision {
-                                allowed: false,
-                                requires_approval: false,
-                                reason: "approval does not match action request".into(),
-                                approval_id: Some(existing_approval_id.to_string()),
-                                receipt_id: receipt,
-                            });
-                        }
-
-                        match approval.status {
-                            ApprovalStatus::Approved => {
-                                let receipt = push_receipt(
-                                    &mut state,
-                                    &request,
-                                    ReceiptResult::Allowed,
-                                    "approved action",
-                                );
-                                ActionPolicyDecision {
-                                    allowed: true,
-                                    requires_approval: false,
-                                    reason: "approved action".into(),
-                                    approval_id: Some(existing_approval_id.to_string()),
-                                    receipt_id: receipt,
-                                }
-                            }
-                            ApprovalStatus::Rejected => {
-                                let receipt = push_receipt(
-                                    &mut state,
-                                    &request,
-                                    ReceiptResult::Denied,
-                                    "approval rejected",
-                                );
-                                ActionPolicyDecision {
-                                    allowed: false,
-                                    requires_approval: false,
-                                    reason: "approval rejected".into(),
-                                    approval_id: Some(existing_approval_id.to_string()),
-                                    receipt_id: receipt,
-                                }
-                            }
-                            ApprovalStatus::Pending => {
-                                let receipt = push_receipt(
-                                    &mut state,
-                                    &request,
-                                    ReceiptResult::PendingApproval,
-                                    "approval is still pending",
-                                );
-                                ActionPolicyDecision {
-                                    allowed: false,
-                                    requires_approval: true,
-                                    reason: "approval is still pending".into(),
-                                    approval_id: Some(existing_approval_id.to_string()),
-                                    receipt_id: receipt,
-                                }
-                            }
-                        }
-                    } else {
-                        let receipt = push_receipt(
-                            &mut state,
-                            &request,
-                            ReceiptResult::Denied,
-                            "approval not found",
-                        );
-                        ActionPolicyDecision {
-                            allowed: false,
-                            requires_approval: false,
-                            reason: "approval not found".into(),
-                            approval_id: Some(existing_approval_id.to_string()),
-                            receipt_id: receipt,
-                        }
-                    }
-                } else {
-                    let approval_id = uuid::Uuid::new_v4().to_string();
-                    state.approvals.push(ApprovalRequest {
-                        id: approval_id.clone(),
-                        created_at: now.to_rfc3339(),
-                        actor_id: request.actor_id.clone(),
-                        actor_role: request.actor_role.clone(),
-                        action: request.action.clone(),
-                        resource: request.resource.clone(),
-                        destination: request.destination.clone(),
-                        status: ApprovalStatus::Pending,
-                        decided_by: None,
-                        decided_at: None,
-                        reason: None,
-                        context: request.context.clone(),
-                    });
-                    let receipt = push_receipt(
-                        &mut state,
-                        &request,
-                        ReceiptResult::PendingApproval,
-                        "action requires approval",
-                    );
-                    ActionPolicyDecision {
-                        allowed: false,
-                        requires_approval: true,
-                        reason: "action requires approval".into(),
-                        approval_id: Some(approval_id),
-                        receipt_id: receipt,
-                    }
+        };
+        let label = file_label(&path);
+        let lines: Vec<(usize, &str)> = body
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .collect();
+        let mut receipts = Vec::new();
+        for (position, (index, line)) in lines.iter().enumerate() {
+            let parsed: Result<ActionReceipt> = match &self.cipher {
+                Some(cipher) => cipher
+                    .open(&label, line)
+                    .and_then(|plain| serde_json::from_slice(&plain).map_err(Into::into)),
+                None => serde_json::from_str(line).map_err(Into::into),
+            };
+            match parsed {
+                Ok(receipt) => receipts.push(receipt),
+                // A crash mid-append leaves a torn last line; anything else
+                // would be dropped for good by the next full write.
+                Err(error) if position + 1 == lines.len() => {
+                    tracing::warn!("skipping torn last receipt journal line: {error}");
                 }
-            } else {
-                let receipt = push_receipt(
-                    &mut state,
-                    &request,
-                    ReceiptResult::Allowed,
-                    "policy allowed",
-                );
-                ActionPolicyDecision {
-                    allowed: true,
-                    requires_approval: false,
-                    reason: "policy allowed".into(),
-                    approval_id: None,
-                    receipt_id: receipt,
+                Err(error) => {
+                    return Err(error).with_context(|| {
+                        format!("{} line {} is unreadable", path.display(), index + 1)
+                    })
                 }
             }
+        }
+        Ok(receipts)
+    }
+}
+
+impl ControlPlaneBackend for JsonFileControlPlaneBackend {
+    fn backend_name(&self) -> &'static str {
+        "json-file"
+    }
+
+    fn location(&self) -> &Path {
+        &self.path
+    }
+
+    fn read_state(&self) -> Result<Option<ControlPlaneState>> {
+        let Some(mut state) = self.read_file()? else {
+            return Ok(None);
+        };
+        prepend_receipts(&mut state, self.read_journal()?);
+        Ok(Some(state))
+    }
+
+    fn write_state(&self, state: &ControlPlaneState) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+
+        // Journal entries the caller never loaded are folded in, not lost.
+        let unseen: Vec<ActionReceipt> = self
+            .read_journal()?
+            .into_iter()
+            .filter(|receipt| !state.receipts.iter().any(|known| known.id == receipt.id))
+            .collect();
+        let merged;
+        let state = if unseen.is_empty() {
+            state
         } else {
-            let receipt = push_receipt(
-                &mut state,
-                &request,
-                ReceiptResult::Denied,
-                "no matching policy rule",
-            );
-            ActionPolicyDecision {
-                allowed: false,
-                requires_approval: false,
-                reason: "no matching policy rule".into(),
-                approval_id: None,
-                receipt_id: receipt,
+            let mut with_journal = state.clone();
+            prepend_receipts(&mut with_journal, unseen);
+            merged = with_journal;
+            &merged
+        };
+
+        self.write_file(state)?;
+        match fs::remove_file(self.journal_path()) {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error)
+                .with_context(|| format!("failed to clear {}", self.journal_path().display())),
+            _ => Ok(()),
+        }
+    }
+
+    fn append_receipts(&self, receipts: &[ActionReceipt]) -> Result<()> {
+        use std::io::Write;
+
+        let path = self.journal_path();
+        let label = file_label(&path);
+        let mut lines = String::new();
+        for receipt in receipts {
+            let json = serde_json::to_string(receipt).context("failed to serialize receipt")?;
+            match &self.cipher {
+                Some(cipher) => lines.push_str(&cipher.seal(&label, json.as_bytes())?),
+                None => lines.push_str(&json),
             }
+            lines.push('\n');
+        }
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| {
+                drop_torn_line(&path, &file)?;
+                file.write_all(lines.as_bytes())
+            })
+            .with_context(|| format!("failed to append to {}", path.display()))
+    }
+}
+
+/// Truncates an unterminated last line left by a crash mid-append, so the
+/// next receipt starts on a line of its own instead of merging with it.
+fn drop_torn_line(path: &Path, file: &fs::File) -> std::io::Result<()> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let len = file.metadata()?.len();
+    if len == 0 {
+        return Ok(());
+    }
+    let mut reader = fs::File::open(path)?;
+    let mut last = [0_u8; 1];
+    reader.seek(SeekFrom::Start(len - 1))?;
+    reader.read_exact(&mut last)?;
+    if last[0] == b'\n' {
+        return Ok(());
+    }
+    let body = fs::read(path)?;
+    let keep = body
+        .iter()
+        .rposition(|byte| *byte == b'\n')
+        .map_or(0, |end| end + 1);
+    file.set_len(keep as u64)
+}
+
+#[derive(Debug, Clone)]
+pub struct ControlPlaneStore {
+    backend: Arc<dyn ControlPlaneBackend>,
+    lock: Arc<Mutex<()>>,
+    integrity: Option<IntegrityMonitor>,
+    events: Option<(EventBus, String)>,
+    receipt_metadata: Option<ReceiptMetadata>,
+    decision_cache: Option<Arc<Mutex<DecisionCache>>>,
+    clock: Option<PolicyClock>,
+}
+
+/// Time source for policy enforcement; replaceable so tests can move it.
+#[derive(Clone)]
+struct PolicyClock(Arc<dyn Fn() -> DateTime<Utc> + Send + Sync>);
+
+impl std::fmt::Debug for PolicyClock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PolicyClock").finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct DecisionKey {
+    actor_id: String,
+    actor_role: String,
+    action: String,
+    resource: String,
+    destination: String,
+}
+
+impl DecisionKey {
+    fn for_request(request: &ActionPolicyRequest) -> Self {
+        Self {
+            actor_id: request.actor_id.clone(),
+            actor_role: request.actor_role.clone(),
+            action: request.action.clone(),
+            resource: request.resource.clone(),
+            destination: request.destination.clone(),
+        }
+    }
+}
+
+/// Allow decisions remembered per workspace.
+#[derive(Debug, Default)]
+struct DecisionCache {
+    ttl: std::time::Duration,
+    /// Bumped on every invalidation; an evaluation only caches its result
+    /// if no policy write landed since it read the state.
+    generation: u64,
+    entries: HashMap<DecisionKey, CachedDecision>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedDecision {
+    cached_at: Instant,
+    /// Next trial lapse or staged-policy switch; the entry is stale from then.
+    deadline: Option<DateTime<Utc>>,
+    reason: String,
+    policy_revision: Option<u32>,
+    /// False when audit verbosity drops allowed receipts for the action.
+    keep_receipt: bool,
+}
+
+impl DecisionCache {
+    fn lookup(&self, request: &ActionPolicyRequest, now: DateTime<Utc>) -> Option<&CachedDecision> {
+        if request.approval_id.is_some() || request.on_behalf_of.is_some() {
+            return None;
+        }
+        self.entries
+            .get(&DecisionKey::for_request(request))
+            .filter(|cached| {
+                cached.cached_at.elapsed() < self.ttl
+                    && cached.deadline.is_none_or(|deadline| now < deadline)
+            })
+    }
+}
+
+impl ControlPlaneStore {
+    pub fn for_workspace(workspace_dir: &Path) -> Self {
+        Self::with_backend(Arc::new(JsonFileControlPlaneBackend::for_workspace(
+            workspace_dir,
+        )))
+    }
+
+    #[cfg(feature = "control-plane-sqlite")]
+    pub fn sqlite_for_workspace(workspace_dir: &Path) -> Result<Self> {
+        let backend =
+            crate::control_plane_sqlite::SqliteControlPlaneBackend::for_workspace(workspace_dir)?;
+        Ok(Self::with_backend(Arc::new(backend)))
+    }
+
+    pub fn with_backend(backend: Arc<dyn ControlPlaneBackend>) -> Self {
+        Self {
+            lock: state_file_lock(backend.location()),
+            backend,
+            integrity: None,
+            events: None,
+            receipt_metadata: None,
+            decision_cache: None,
+            clock: None,
+        }
+    }
+
+    /// Replaces the system clock used for policy enforcement (hours windows,
+    /// rate limits, grant expiry and re-auth freshness) and for the start,
+    /// expiry and revocation times stamped on grants and machine tokens.
+    #[must_use]
+    pub fn with_clock(mut self, clock: impl Fn() -> DateTime<Utc> + Send + Sync + 'static) -> Self {
+        self.clock = Some(PolicyClock(Arc::new(clock)));
+        self
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        self.clock
+            .as_ref()
+            .map_or_else(Utc::now, |clock| (clock.0)())
+    }
+
+    /// Answers repeated identical allow decisions from memory for up to
+    /// `ttl` instead of rewriting the state file on every tool call. The cache
+    /// is shared by all stores on the same backend and is dropped whenever
+    /// policy, approvals or access state change through any of them.
+    #[must_use]
+    pub fn with_decision_cache(mut self, ttl: std::time::Duration) -> Self {
+        let cache = decision_cache(self.backend.location());
+        cache.lock().ttl = ttl;
+        self.decision_cache = Some(cache);
+        self
+    }
+
+    /// Stamps every receipt written through this store with `metadata`.
+    #[must_use]
+    pub fn with_receipt_metadata(mut self, metadata: ReceiptMetadata) -> Self {
+        self.receipt_metadata = Some(metadata);
+        self
+    }
+
+    /// Publishes approval and break-glass activity on `bus` under
+    /// `profile_id`, so the UI can react without polling.
+    #[must_use]
+    pub fn with_event_bus(mut self, bus: EventBus, profile_id: impl Into<String>) -> Self {
+        self.events = Some((bus, profile_id.into()));
+        self
+    }
+
+    fn publish(&self, kind: RuntimeEventKind) {
+        if let Some((bus, profile_id)) = &self.events {
+            bus.publish(RuntimeEvent::new(profile_id, kind));
+        }
+    }
+
+    /// Refreshes the integrity manifest after every write so the monitor only
+    /// flags changes that did not go through this store.
+    #[must_use]
+    pub fn with_integrity_monitor(mut self, monitor: IntegrityMonitor) -> Self {
+        self.integrity = Some(monitor);
+        self
+    }
+
+    pub fn backend_name(&self) -> &'static str {
+        self.backend.backend_name()
+    }
+
+    pub fn load(&self) -> Result<ControlPlaneState> {
+        let _guard = self.lock.lock();
+        self.read_state()
+    }
+
+    pub fn save(&self, state: &ControlPlaneState) -> Result<()> {
+        let _guard = self.lock.lock();
+        let mut state = state.clone();
+        track_policy_changes(&mut state);
+        self.write_state(&state)?;
+        self.invalidate_decisions();
+        Ok(())
+    }
+
+    /// Runs a load-modify-save cycle while holding the workspace lock, so
+    /// concurrent commands cannot interleave and clobber each other's writes.
+    /// Cached decisions are dropped before the lock is released, so none
+    /// outlives the write that made it stale.
+    fn update<T>(&self, apply: impl FnOnce(&mut ControlPlaneState) -> Result<T>) -> Result<T> {
+        let _guard = self.lock.lock();
+        let out = self.apply_locked(apply)?;
+        self.invalidate_decisions();
+        Ok(out)
+    }
+
+    /// Like [`Self::update`] but leaves cached decisions in place; only for
+    /// writes that append receipts or approvals without changing policy.
+    fn record<T>(&self, apply: impl FnOnce(&mut ControlPlaneState) -> Result<T>) -> Result<T> {
+        let _guard = self.lock.lock();
+        self.apply_locked(apply)
+    }
+
+    fn apply_locked<T>(
+        &self,
+        apply: impl FnOnce(&mut ControlPlaneState) -> Result<T>,
+    ) -> Result<T> {
+        let mut state = self.read_state()?;
+        let out = apply(&mut state)?;
+        track_policy_changes(&mut state);
+        self.write_state(&state)?;
+        Ok(out)
+    }
+
+    /// Like [`Self::record`] but only loads receipts newer than `since`, so
+    /// policy evaluation stays cheap on backends with indexed receipts.
+    fn record_recent<T>(
+        &self,
+        since: DateTime<Utc>,
+        apply: impl FnOnce(&mut ControlPlaneState) -> Result<T>,
+    ) -> Result<T> {
+        let _guard = self.lock.lock();
+        let loaded = self.backend.read_recent_state(since)?;
+        let mut state = self.prepare_state(loaded)?;
+        let out = apply(&mut state)?;
+        track_policy_changes(&mut state);
+        self.backend.write_recent_state(&state)?;
+        self.record_integrity()?;
+        Ok(out)
+    }
+
+    fn invalidate_decisions(&self) {
+        if let Some(cache) = &self.decision_cache {
+            let mut cache = cache.lock();
+            cache.entries.clear();
+            cache.generation += 1;
+        }
+    }
+
+    fn decision_generation(&self) -> u64 {
+        self.decision_cache
+            .as_ref()
+            .map_or(0, |cache| cache.lock().generation)
+    }
+
+    /// Answers from the decision cache, appending the receipt to the backend
+    /// before its id is handed out.
+    fn cached_decision(
+        &self,
+        request: &ActionPolicyRequest,
+    ) -> Result<Option<ActionPolicyDecision>> {
+        let Some(cache) = &self.decision_cache else {
+            return Ok(None);
+        };
+        let Some(cached) = cache.lock().lookup(request, self.now()).cloned() else {
+            return Ok(None);
+        };
+        let receipt_id = if cached.keep_receipt {
+            let mut receipt = build_receipt(
+                self.receipt_metadata.as_ref(),
+                request,
+                ReceiptResult::Allowed,
+                &cached.reason,
+            );
+            receipt.policy_revision = cached.policy_revision;
+            let _guard = self.lock.lock();
+            self.backend
+                .append_receipts(std::slice::from_ref(&receipt))?;
+            receipt.id
+        } else {
+            String::new()
+        };
+        Ok(Some(ActionPolicyDecision {
+            allowed: true,
+            requires_approval: false,
+            requires_reauth: false,
+            reason: cached.reason,
+            approval_id: None,
+            receipt_id,
+        }))
+    }
+
+    fn read_state(&self) -> Result<ControlPlaneState> {
+        let loaded = self.backend.read_state()?;
+        self.prepare_state(loaded)
+    }
+
+    /// Normalizes a loaded state, creating and saving a fresh one when the
+    /// backend has none yet.
+    fn prepare_state(&self, loaded: Option<ControlPlaneState>) -> Result<ControlPlaneState> {
+        let Some(mut state) = loaded else {
+            let mut state = ControlPlaneState::default();
+            state.access_state.start_trial();
+            track_policy_changes(&mut state);
+            self.write_state(&state)?;
+            state.receipt_metadata.clone_from(&self.receipt_metadata);
+            return Ok(state);
         };
 
-        self.save(&state)?;
+        self.normalize(&mut state);
+        state.receipt_metadata.clone_from(&self.receipt_metadata);
+        Ok(state)
+    }
+
+    fn write_state(&self, state: &ControlPlaneState) -> Result<()> {
+        self.backend.write_state(state)?;
+        self.record_integrity()
+    }
+
+    fn record_integrity(&self) -> Result<()> {
+        if let Some(monitor) = &self.integrity {
+            if let Some(name) = self.backend.location().file_name().and_then(|n| n.to_str()) {
+                monitor.record(name)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_state(&self) -> Result<ControlPlaneState> {
+        self.load()
+    }
+
+    pub fn start_trial(&self) -> Result<AccessState> {
+        self.update(|state| {
+            state.access_state.start_trial();
+            Ok(state.access_state.clone())
+        })
+    }
+
+    /// Reports where the trial stands, downgrading a lapsed trial to the
+    /// personal view first.
+    pub fn trial_status(&self) -> Result<TrialStatus> {
+        self.update(|state| {
+            let now = Utc::now();
+            apply_trial_lapse(state, now);
+            Ok(state.access_state.trial_status(now))
+        })
+    }
+
+    pub fn set_paid_plan(&self, plan: AccessPlan) -> Result<AccessState> {
+        self.update(|state| {
+            state.access_state.set_paid_plan(plan)?;
+            Ok(state.access_state.clone())
+        })
+    }
+
+    pub fn set_active_view(&self, view: WorkspaceView) -> Result<AccessState> {
+        self.update(|state| {
+            state.access_state.set_active_view(view)?;
+            Ok(state.access_state.clone())
+        })
+    }
+
+    pub fn evaluate_action(&self, request: ActionPolicyRequest) -> Result<ActionPolicyDecision> {
+        if let Some(decision) = self.cached_decision(&request)? {
+            return Ok(decision);
+        }
+
+        let now = self.now();
+        // Rate limits look back one hour; older receipts are not needed.
+        let since = now - Duration::hours(1);
+        let (decision, expired, cacheable, changed, revision, deadline, generation) = self
+            .record_recent(since, |state| {
+                let expired = expire_break_glass(state, now);
+                let lapsed = apply_trial_lapse(state, now);
+                let switched = apply_staged_policies(state, now);
+                track_policy_changes(state);
+                let cacheable = is_cacheable(state, &request, now);
+                let decision = evaluate_request(state, &request, now);
+                let revision = state
+                    .policy_history
+                    .last()
+                    .map(|revision| revision.revision);
+                let deadline = next_policy_deadline(state, now);
+                Ok((
+                    decision,
+                    expired,
+                    cacheable,
+                    lapsed || switched,
+                    revision,
+                    deadline,
+                    self.decision_generation(),
+                ))
+            })?;
+        if changed || !expired.is_empty() {
+            self.invalidate_decisions();
+        } else if cacheable && decision.allowed {
+            if let Some(cache) = &self.decision_cache {
+                let mut cache = cache.lock();
+                // A policy write that committed after this evaluation read
+                // the state has bumped the generation; caching now would
+                // revive the decision that write invalidated.
+                if cache.generation == generation {
+                    cache.entries.insert(
+                        DecisionKey::for_request(&request),
+                        CachedDecision {
+                            cached_at: Instant::now(),
+                            deadline,
+                            reason: decision.reason.clone(),
+                            policy_revision: revision,
+                            keep_receipt: !decision.receipt_id.is_empty(),
+                        },
+                    );
+                }
+            }
+        }
+        for grant in &expired {
+            self.publish_break_glass(grant, "break-glass expired");
+        }
+        if decision.requires_approval && request.approval_id.is_none() {
+            if let Some(approval_id) = &decision.approval_id {
+                self.publish(RuntimeEventKind::ApprovalRequested {
+                    approval_id: approval_id.clone(),
+                    actor_id: request.actor_id.clone(),
+                    action: request.action.clone(),
+                    resource: request.resource.clone(),
+                });
+            }
+        }
         Ok(decision)
     }
 
+    /// Evaluates a list of planned actions in one pass. Actions that need
+    /// approval share a single approval group instead of prompting one by one.
+    pub fn evaluate_batch(
+        &self,
+        requests: Vec<ActionPolicyRequest>,
+    ) -> Result<BatchPolicyDecision> {
+        if requests.is_empty() {
+            anyhow::bail!("batch must contain at least one action");
+        }
+
+        let now = self.now();
+        let (batch, expired) = self.record_recent(now - Duration::hours(1), |state| {
+            let expired = expire_break_glass(state, now);
+            let decisions: Vec<ActionPolicyDecision> = requests
+                .iter()
+                .map(|request| evaluate_request(state, request, now))
+                .collect();
+
+            let opened: Vec<&str> = decisions
+                .iter()
+                .zip(&requests)
+                .filter(|(decision, request)| {
+                    decision.requires_approval && request.approval_id.is_none()
+                })
+                .filter_map(|(decision, _)| decision.approval_id.as_deref())
+                .collect();
+            let group_approval_id = if opened.is_empty() {
+                None
+            } else {
+                let group_id = uuid::Uuid::new_v4().to_string();
+                for approval in &mut state.approvals {
+                    if opened.contains(&approval.id.as_str()) {
+                        approval.group_id = Some(group_id.clone());
+                    }
+                }
+                Some(group_id)
+            };
+
+            Ok((
+                BatchPolicyDecision {
+                    decisions,
+                    group_approval_id,
+                },
+                expired,
+            ))
+        })?;
+        self.invalidate_decisions();
+
+        for grant in &expired {
+            self.publish_break_glass(grant, "break-glass expired");
+        }
+        if let Some(group_id) = &batch.group_approval_id {
+            let pending = batch
+                .decisions
+                .iter()
+                .filter(|decision| decision.requires_approval)
+                .count();
+            self.publish(RuntimeEventKind::ApprovalRequested {
+                approval_id: group_id.clone(),
+                actor_id: requests[0].actor_id.clone(),
+                action: format!("batch ({pending} actions)"),
+                resource: requests[0].resource.clone(),
+            });
+        }
+        Ok(batch)
+    }
+
+    /// Evaluates `request` against a throwaway copy of the state. When
+    /// `candidate_rules` is given it replaces the live rule set, so a policy
+    /// profile can be tested before it is applied.
+    pub fn simulate_action(
+        &self,
+        request: ActionPolicyRequest,
+        candidate_rules: Option<Vec<PolicyRule>>,
+    ) -> Result<PolicySimulation> {
+        let mut state = self.load()?;
+        if let Some(rules) = candidate_rules {
+            state.policy_rules = rules;
+        }
+
+        let matched_rule = matching_rule(&state.policy_rules, &request).cloned();
+        let decision = evaluate_request(&mut state, &request, self.now());
+        Ok(PolicySimulation {
+            allowed: decision.allowed,
+            requires_approval: decision.requires_approval,
+            requires_reauth: decision.requires_reauth,
+            reason: decision.reason,
+            matched_rule,
+        })
+    }
+
     pub fn list_receipts(&self, limit: usize) -> Result<Vec<ActionReceipt>> {
+        self.query_receipts(&ReceiptQuery {
+            limit,
+            ..ReceiptQuery::default()
+        })
+    }
+
+    pub fn query_receipts(&self, query: &ReceiptQuery) -> Result<Vec<ActionReceipt>> {
+        let _guard = self.lock.lock();
+        self.backend.query_receipts(query)
+    }
+
+    /// Reverts an allowed action using the undo plan on its receipt. The
+    /// inverse goes through the normal policy gate as `actor_id`, and
+    /// `execute` only runs when that evaluation allows it.
+    pub fn undo_receipt<F>(
+        &self,
+        receipt_id: &str,
+        actor_id: &str,
+        actor_role: &str,
+        execute: F,
+    ) -> Result<ActionPolicyDecision>
+    where
+        F: FnOnce(&UndoPlan) -> Result<()>,
+    {
         let state = self.load()?;
-        Ok(state
+        let Some(receipt) = state
             .receipts
-            .into_iter()
-            .take(limit.clamp(1, 1000))
-            .collect())
+            .iter()
+            .find(|receipt| receipt.id == receipt_id)
+        else {
+            anyhow::bail!("receipt '{receipt_id}' not found");
+        };
+        let Some(plan) = receipt.undo.clone() else {
+            anyhow::bail!("receipt '{receipt_id}' has no undo plan");
+        };
+
+        let mut context = BTreeMap::new();
+        context.insert("undoes".into(), Value::String(receipt_id.to_string()));
+        let decision = self.evaluate_action(ActionPolicyRequest {
+            actor_id: actor_id.to_string(),
+            actor_role: actor_role.to_string(),
+            action: plan.action.clone(),
+            resource: plan.resource.clone(),
+            destination: plan.destination.clone(),
+            approval_id: None,
+            occurred_at: None,
+            authenticated_at: None,
+            on_behalf_of: None,
+            context,
+        })?;
+        if decision.allowed {
+            execute(&plan).with_context(|| format!("failed to run {}", plan.action))?;
+        }
+        Ok(decision)
     }
 
     pub fn list_approvals(&self, pending_only: bool) -> Result<Vec<ApprovalRequest>> {
@@ -529,297 +1754,3358 @@ impl ControlPlaneStore {
         approved: bool,
         reason: Option<String>,
     ) -> Result<ApprovalRequest> {
-        if !matches!(approver_role, "owner" | "admin") {
-            anyhow::bail!("only owner/admin can resolve approvals");
-        }
+        self.decide_approval(approval_id, approver_role, None, approved, reason)
+    }
+
+    /// Resolves an approval on behalf of a specific user. The identity comes
+    /// from the shell's user registry; it must be active and hold owner or
+    /// admin, and the user id is recorded so the decision is attributable.
+    pub fn resolve_approval_as(
+        &self,
+        approval_id: &str,
+        identity: &ApproverIdentity,
+        approved: bool,
+        reason: Option<String>,
+    ) -> Result<ApprovalRequest> {
+        validate_approver(identity)?;
+        self.decide_approval(
+            approval_id,
+            &identity.role,
+            Some(&identity.user_id),
+            approved,
+            reason,
+        )
+    }
+
+    fn decide_approval(
+        &self,
+        approval_id: &str,
+        approver_role: &str,
+        approver_user_id: Option<&str>,
+        approved: bool,
+        reason: Option<String>,
+    ) -> Result<ApprovalRequest> {
+        if !matches!(approver_role, "owner" | "admin") {
+            anyhow::bail!("only owner/admin can resolve approvals");
+        }
+
+        let resolved = self.update(|state| {
+            let Some(approval) = state
+                .approvals
+                .iter_mut()
+                .find(|request| request.id == approval_id)
+            else {
+                anyhow::bail!("approval '{}' not found", approval_id);
+            };
+
+            approval.status = if approved {
+                ApprovalStatus::Approved
+            } else {
+                ApprovalStatus::Rejected
+            };
+            approval.decided_by = Some(approver_role.to_string());
+            approval.decided_by_user_id = approver_user_id.map(str::to_string);
+            approval.decided_at = Some(Utc::now().to_rfc3339());
+            approval.reason = reason;
+
+            Ok(approval.clone())
+        })?;
+
+        self.publish(RuntimeEventKind::ApprovalResolved {
+            approval_id: resolved.id.clone(),
+            approved,
+            decided_by: resolved
+                .decided_by_user_id
+                .clone()
+                .unwrap_or_else(|| approver_role.to_string()),
+        });
+        Ok(resolved)
+    }
+
+    /// Approves or rejects every still-pending approval in a batch group on
+    /// behalf of `identity`, with the same checks as
+    /// [`ControlPlaneStore::resolve_approval_as`].
+    pub fn resolve_approval_group(
+        &self,
+        group_id: &str,
+        identity: &ApproverIdentity,
+        approved: bool,
+        reason: Option<String>,
+    ) -> Result<Vec<ApprovalRequest>> {
+        validate_approver(identity)?;
+
+        let resolved = self.update(|state| {
+            let decided_at = Utc::now().to_rfc3339();
+            let mut resolved = Vec::new();
+            for approval in &mut state.approvals {
+                if approval.group_id.as_deref() != Some(group_id)
+                    || !matches!(approval.status, ApprovalStatus::Pending)
+                {
+                    continue;
+                }
+                approval.status = if approved {
+                    ApprovalStatus::Approved
+                } else {
+                    ApprovalStatus::Rejected
+                };
+                approval.decided_by = Some(identity.role.clone());
+                approval.decided_by_user_id = Some(identity.user_id.clone());
+                approval.decided_at = Some(decided_at.clone());
+                approval.reason.clone_from(&reason);
+                resolved.push(approval.clone());
+            }
+            if resolved.is_empty() {
+                anyhow::bail!("no pending approvals in group '{group_id}'");
+            }
+            Ok(resolved)
+        })?;
+
+        self.publish(RuntimeEventKind::ApprovalResolved {
+            approval_id: group_id.to_string(),
+            approved,
+            decided_by: identity.user_id.clone(),
+        });
+        Ok(resolved)
+    }
+
+    pub fn set_retention(
+        &self,
+        receipts_days: u32,
+        approvals_days: u32,
+    ) -> Result<RetentionPolicy> {
+        self.update(|state| {
+            state.retention = RetentionPolicy {
+                receipts_days: receipts_days.max(1),
+                approvals_days: approvals_days.max(1),
+                ..state.retention.clone()
+            };
+            Ok(state.retention.clone())
+        })
+    }
+
+    pub fn set_retention_policy(&self, policy: RetentionPolicy) -> Result<RetentionPolicy> {
+        self.update(|state| {
+            state.retention = RetentionPolicy {
+                receipts_days: policy.receipts_days.max(1),
+                approvals_days: policy.approvals_days.max(1),
+                audit_events_days: policy.audit_events_days.max(1),
+                outcomes_days: policy.outcomes_days.max(1),
+                completed_tasks_days: policy.completed_tasks_days.max(1),
+                diagnostics_logs_days: policy.diagnostics_logs_days.max(1),
+            };
+            Ok(state.retention.clone())
+        })
+    }
+
+    pub fn set_reauth_policy(&self, policy: ReauthPolicy) -> Result<ReauthPolicy> {
+        self.update(|state| {
+            state.reauth = ReauthPolicy {
+                freshness_secs: policy.freshness_secs.max(1),
+                ..policy
+            };
+            Ok(state.reauth.clone())
+        })
+    }
+
+    pub fn set_blocked_destinations(&self, destinations: Vec<String>) -> Result<Vec<String>> {
+        let cleaned = clean_destinations(destinations)?;
+        self.update(|state| {
+            state.blocked_destinations = cleaned;
+            Ok(state.blocked_destinations.clone())
+        })
+    }
+
+    pub fn set_residency_destinations(&self, destinations: Vec<String>) -> Result<Vec<String>> {
+        let cleaned = clean_destinations(destinations)?;
+        self.update(|state| {
+            state.residency_destinations = cleaned;
+            Ok(state.residency_destinations.clone())
+        })
+    }
+
+    pub fn set_allowed_provider_regions(&self, regions: Vec<String>) -> Result<Vec<String>> {
+        let mut cleaned = Vec::new();
+        for region in regions {
+            let region = region.trim().to_ascii_lowercase();
+            if !region.is_empty() && !cleaned.contains(&region) {
+                cleaned.push(region);
+            }
+        }
+
+        self.update(|state| {
+            state.allowed_provider_regions = cleaned;
+            Ok(state.allowed_provider_regions.clone())
+        })
+    }
+
+    pub fn set_provider_endpoint_regions(
+        &self,
+        endpoints: BTreeMap<String, String>,
+    ) -> Result<BTreeMap<String, String>> {
+        let mut cleaned = BTreeMap::new();
+        for (endpoint, region) in endpoints {
+            let endpoint = provider_endpoint_key(&endpoint);
+            let region = region.trim().to_ascii_lowercase();
+            if endpoint.is_empty() || region.is_empty() {
+                anyhow::bail!("provider endpoint regions need both an endpoint and a region");
+            }
+            cleaned.insert(endpoint, region);
+        }
+
+        self.update(|state| {
+            state.provider_endpoint_regions = cleaned;
+            Ok(state.provider_endpoint_regions.clone())
+        })
+    }
+
+    /// Allowed actions that a verbosity setting skips return an empty
+    /// `receipt_id`. Actions under a rate-limited rule are always recorded.
+    pub fn set_audit_verbosity(
+        &self,
+        policy: AuditVerbosityPolicy,
+    ) -> Result<AuditVerbosityPolicy> {
+        let mut actions = BTreeMap::new();
+        for (action, level) in policy.actions {
+            let action = action.trim().to_string();
+            if action.is_empty() {
+                anyhow::bail!("audit verbosity action must not be empty");
+            }
+            actions.insert(action, level);
+        }
+
+        self.update(|state| {
+            state.audit_verbosity = AuditVerbosityPolicy {
+                actions,
+                sample_every: policy.sample_every.max(1),
+            };
+            state.audit_sample_counts.retain(|action, _| {
+                state.audit_verbosity.level_for(action) == AuditVerbosity::Sampled
+            });
+            Ok(state.audit_verbosity.clone())
+        })
+    }
+
+    /// Schedules `rules` to become the live rule set at `effective_from`. With
+    /// `effective_until`, the rules that were live before come back afterwards.
+    pub fn stage_policy(
+        &self,
+        staged_by: &str,
+        name: &str,
+        rules: Vec<PolicyRule>,
+        effective_from: &str,
+        effective_until: Option<&str>,
+    ) -> Result<StagedPolicy> {
+        if rules.is_empty() {
+            anyhow::bail!("a staged policy needs at least one rule");
+        }
+        let Some(from) = parse_rfc3339(effective_from) else {
+            anyhow::bail!("'{effective_from}' is not an RFC 3339 timestamp");
+        };
+        let until = match effective_until {
+            Some(raw) => {
+                let Some(until) = parse_rfc3339(raw) else {
+                    anyhow::bail!("'{raw}' is not an RFC 3339 timestamp");
+                };
+                if until <= from {
+                    anyhow::bail!("effective_until must be after effective_from");
+                }
+                Some(until)
+            }
+            None => None,
+        };
+
+        let staged = StagedPolicy {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.trim().to_string(),
+            rules,
+            effective_from: from.to_rfc3339(),
+            effective_until: until.map(|until| until.to_rfc3339()),
+            staged_by: staged_by.to_string(),
+            staged_at: Utc::now().to_rfc3339(),
+            activated_at: None,
+            activated_revision: None,
+            replaced_rules: None,
+            ended_at: None,
+        };
+        self.update(|state| {
+            state.staged_policies.push(staged.clone());
+            Ok(staged)
+        })
+    }
+
+    pub fn list_staged_policies(&self) -> Result<Vec<StagedPolicy>> {
+        Ok(self.load()?.staged_policies)
+    }
+
+    /// Withdraws a staged policy that has not taken effect yet.
+    pub fn cancel_staged_policy(&self, staged_id: &str) -> Result<StagedPolicy> {
+        self.update(|state| {
+            let Some(index) = state
+                .staged_policies
+                .iter()
+                .position(|staged| staged.id == staged_id)
+            else {
+                anyhow::bail!("staged policy '{staged_id}' not found");
+            };
+            if state.staged_policies[index].activated_at.is_some() {
+                anyhow::bail!("staged policy '{staged_id}' is already in effect");
+            }
+            Ok(state.staged_policies.remove(index))
+        })
+    }
+
+    /// Replays the last day's decisions against a staged rule set and lists
+    /// the ones it would decide differently from the live rules.
+    pub fn preview_staged_policy(&self, staged_id: &str) -> Result<Vec<StagedPolicyImpact>> {
+        let state = self.load()?;
+        let Some(staged) = state
+            .staged_policies
+            .iter()
+            .find(|staged| staged.id == staged_id)
+        else {
+            anyhow::bail!("staged policy '{staged_id}' not found");
+        };
+
+        let since = Utc::now() - Duration::days(1);
+        let now = Utc::now().to_rfc3339();
+        let mut live = state.clone();
+        let mut candidate = state.clone();
+        candidate.policy_rules.clone_from(&staged.rules);
+
+        let mut impacts = Vec::new();
+        for receipt in state
+            .receipts
+            .iter()
+            .filter(|receipt| parse_rfc3339(&receipt.timestamp).is_some_and(|at| at >= since))
+        {
+            let request = ActionPolicyRequest {
+                actor_id: receipt.actor_id.clone(),
+                actor_role: receipt.actor_role.clone(),
+                action: receipt.action.clone(),
+                resource: receipt.resource.clone(),
+                destination: receipt.destination.clone(),
+                approval_id: None,
+                occurred_at: Some(receipt.timestamp.clone()),
+                authenticated_at: Some(now.clone()),
+                on_behalf_of: None,
+                context: BTreeMap::new(),
+            };
+            let at = parse_rfc3339(&receipt.timestamp).unwrap_or_else(Utc::now);
+            let current = decision_result(&evaluate_request(&mut live, &request, at));
+            let proposed = decision_result(&evaluate_request(&mut candidate, &request, at));
+            if current != proposed {
+                impacts.push(StagedPolicyImpact {
+                    receipt_id: receipt.id.clone(),
+                    actor_id: receipt.actor_id.clone(),
+                    action: receipt.action.clone(),
+                    resource: receipt.resource.clone(),
+                    current,
+                    staged: proposed,
+                });
+            }
+        }
+        Ok(impacts)
+    }
+
+    /// Lets `actor_id` act on behalf of `subject_id` for a limited window.
+    pub fn grant_impersonation(
+        &self,
+        granted_by: &str,
+        granted_by_role: &str,
+        actor_id: &str,
+        subject_id: &str,
+        reason: &str,
+        duration_minutes: u32,
+    ) -> Result<ImpersonationGrant> {
+        if !matches!(granted_by_role, "owner" | "admin") {
+            anyhow::bail!("only owner/admin can grant impersonation");
+        }
+        if actor_id == subject_id {
+            anyhow::bail!("impersonation needs two different users");
+        }
+        if reason.trim().is_empty() {
+            anyhow::bail!("impersonation requires a reason");
+        }
+
+        self.update(|state| {
+            let now = self.now();
+            let grant = ImpersonationGrant {
+                id: uuid::Uuid::new_v4().to_string(),
+                actor_id: actor_id.to_string(),
+                subject_id: subject_id.to_string(),
+                granted_by: granted_by.to_string(),
+                reason: reason.trim().to_string(),
+                created_at: now.to_rfc3339(),
+                expires_at: (now + Duration::minutes(i64::from(duration_minutes.clamp(5, 480))))
+                    .to_rfc3339(),
+                revoked_at: None,
+            };
+            state.impersonation_grants.push(grant.clone());
+            Ok(grant)
+        })
+    }
+
+    pub fn revoke_impersonation(&self, grant_id: &str) -> Result<ImpersonationGrant> {
+        self.update(|state| {
+            let Some(grant) = state
+                .impersonation_grants
+                .iter_mut()
+                .find(|grant| grant.id == grant_id)
+            else {
+                anyhow::bail!("impersonation grant '{grant_id}' not found");
+            };
+            grant.revoked_at = Some(self.now().to_rfc3339());
+            Ok(grant.clone())
+        })
+    }
+
+    pub fn list_impersonation_grants(&self) -> Result<Vec<ImpersonationGrant>> {
+        Ok(self.load()?.impersonation_grants)
+    }
+
+    /// Elevates `actor_id` to `role` for `hours` (1-72). The grant lapses on
+    /// its own; [`ControlPlaneStore::sweep_role_grants`] records the expiry.
+    pub fn grant_temporary_role(
+        &self,
+        granted_by: &str,
+        granted_by_role: &str,
+        actor_id: &str,
+        role: &str,
+        reason: &str,
+        hours: u32,
+    ) -> Result<RoleGrant> {
+        if !matches!(granted_by_role, "owner" | "admin") {
+            anyhow::bail!("only owner/admin can grant temporary roles");
+        }
+        if !matches!(role, "admin" | "operator") {
+            anyhow::bail!("temporary role must be admin or operator");
+        }
+        if role == "admin" && granted_by_role != "owner" {
+            anyhow::bail!("only owners can grant temporary admin");
+        }
+        if reason.trim().is_empty() {
+            anyhow::bail!("temporary role grant requires a reason");
+        }
+
+        let grant = self.update(|state| {
+            let now = self.now();
+            let grant = RoleGrant {
+                id: uuid::Uuid::new_v4().to_string(),
+                actor_id: actor_id.to_string(),
+                role: role.to_string(),
+                granted_by: granted_by.to_string(),
+                reason: reason.trim().to_string(),
+                granted_at: now.to_rfc3339(),
+                expires_at: (now + Duration::hours(i64::from(hours.clamp(1, 72)))).to_rfc3339(),
+                revoked_at: None,
+                expired_at: None,
+            };
+            state.role_grants.push(grant.clone());
+            push_role_grant_receipt(state, &grant, granted_by, "rbac.role_grant");
+            Ok(grant)
+        })?;
+        self.publish_role_grant(&grant, &format!("temporarily elevated to {}", grant.role));
+        Ok(grant)
+    }
+
+    pub fn revoke_role_grant(&self, grant_id: &str, revoked_by: &str) -> Result<RoleGrant> {
+        let grant = self.update(|state| {
+            let Some(grant) = state
+                .role_grants
+                .iter_mut()
+                .find(|grant| grant.id == grant_id)
+            else {
+                anyhow::bail!("role grant '{grant_id}' not found");
+            };
+            if grant.revoked_at.is_some() || grant.expired_at.is_some() {
+                anyhow::bail!("role grant '{grant_id}' is no longer active");
+            }
+            grant.revoked_at = Some(self.now().to_rfc3339());
+            let grant = grant.clone();
+            push_role_grant_receipt(state, &grant, revoked_by, "rbac.role_revoke");
+            Ok(grant)
+        })?;
+        self.publish_role_grant(&grant, "temporary role revoked");
+        Ok(grant)
+    }
+
+    /// Lists grants after sweeping any whose window has closed.
+    pub fn list_role_grants(&self) -> Result<Vec<RoleGrant>> {
+        self.sweep_role_grants()?;
+        Ok(self.load()?.role_grants)
+    }
+
+    /// Marks lapsed grants as expired, records each expiry and returns them.
+    pub fn sweep_role_grants(&self) -> Result<Vec<RoleGrant>> {
+        let expired = self.update(|state| {
+            let now = self.now();
+            let mut expired = Vec::new();
+            for grant in &mut state.role_grants {
+                if grant.revoked_at.is_none() && grant.expired_at.is_none() && !grant.is_active(now)
+                {
+                    grant.expired_at = Some(now.to_rfc3339());
+                    expired.push(grant.clone());
+                }
+            }
+            for grant in &expired {
+                push_role_grant_receipt(state, grant, "system", "rbac.role_expire");
+            }
+            Ok(expired)
+        })?;
+        for grant in &expired {
+            self.publish_role_grant(grant, "temporary role expired");
+        }
+        Ok(expired)
+    }
+
+    /// Runs [`ControlPlaneStore::sweep_role_grants`] on a fixed interval until
+    /// the returned task is aborted.
+    pub fn spawn_role_grant_sweep(self, every: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                ticker.tick().await;
+                if let Err(error) = self.sweep_role_grants() {
+                    tracing::warn!("role grant sweep failed: {error}");
+                }
+            }
+        })
+    }
+
+    fn publish_role_grant(&self, grant: &RoleGrant, message: &str) {
+        self.publish(RuntimeEventKind::SecurityAlert {
+            category: "role_grant".into(),
+            subject: grant.actor_id.clone(),
+            message: message.into(),
+        });
+    }
+
+    pub fn create_machine_token(
+        &self,
+        created_by: &str,
+        created_by_role: &str,
+        name: &str,
+        allowed_actions: Vec<String>,
+        ttl_days: Option<u32>,
+    ) -> Result<IssuedMachineToken> {
+        if !matches!(created_by_role, "owner" | "admin") {
+            anyhow::bail!("only owner/admin can create machine tokens");
+        }
+        if name.trim().is_empty() {
+            anyhow::bail!("machine token name must not be empty");
+        }
+        let allowed_actions: Vec<String> = allowed_actions
+            .into_iter()
+            .map(|action| action.trim().to_string())
+            .filter(|action| !action.is_empty())
+            .collect();
+        if allowed_actions.is_empty() {
+            anyhow::bail!("machine token needs at least one allowed action");
+        }
+        if allowed_actions.iter().any(|action| action == "*") {
+            anyhow::bail!("machine tokens cannot be granted every action");
+        }
+
+        let mut bytes = [0_u8; 32];
+        rand::rng().fill_bytes(&mut bytes);
+        let secret = format!(
+            "zcmt_{}",
+            base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+        );
+
+        self.update(|state| {
+            let now = self.now();
+            let token = MachineToken {
+                id: uuid::Uuid::new_v4().to_string(),
+                name: name.trim().to_string(),
+                token_sha256: token_digest(&secret),
+                allowed_actions,
+                created_by: created_by.to_string(),
+                created_at: now.to_rfc3339(),
+                expires_at: ttl_days
+                    .map(|days| (now + Duration::days(i64::from(days.max(1)))).to_rfc3339()),
+                last_used_at: None,
+                revoked_at: None,
+            };
+            state.machine_tokens.push(token.clone());
+            Ok(IssuedMachineToken { token, secret })
+        })
+    }
+
+    pub fn list_machine_tokens(&self) -> Result<Vec<MachineToken>> {
+        Ok(self.load()?.machine_tokens)
+    }
+
+    pub fn revoke_machine_token(&self, token_id: &str) -> Result<MachineToken> {
+        self.update(|state| {
+            let Some(token) = state
+                .machine_tokens
+                .iter_mut()
+                .find(|token| token.id == token_id)
+            else {
+                anyhow::bail!("machine token '{token_id}' not found");
+            };
+            token.revoked_at = Some(self.now().to_rfc3339());
+            Ok(token.clone())
+        })
+    }
+
+    /// Cleans up after a deactivated user: rejects their pending approvals,
+    /// revokes the machine tokens they created and records one receipt that
+    /// summarizes the cascade.
+    pub fn offboard_actor(&self, actor_id: &str, offboarded_by: &str) -> Result<OffboardSummary> {
+        if actor_id.trim().is_empty() {
+            anyhow::bail!("actor_id must not be empty");
+        }
+
+        let (summary, role_grants, break_glass) = self.update(|state| {
+            let now = self.now();
+            let mut summary = OffboardSummary {
+                actor_id: actor_id.to_string(),
+                ..OffboardSummary::default()
+            };
+            let mut role_grants = Vec::new();
+            let mut break_glass = Vec::new();
+            for approval in &mut state.approvals {
+                if approval.actor_id == actor_id
+                    && matches!(approval.status, ApprovalStatus::Pending)
+                {
+                    approval.status = ApprovalStatus::Rejected;
+                    approval.decided_by = Some("system".into());
+                    approval.decided_by_user_id = Some(offboarded_by.to_string());
+                    approval.decided_at = Some(now.to_rfc3339());
+                    approval.reason = Some("requester was deactivated".into());
+                    summary.cancelled_approvals.push(approval.id.clone());
+                }
+            }
+            for token in &mut state.machine_tokens {
+                if token.created_by == actor_id && token.is_active(now) {
+                    token.revoked_at = Some(now.to_rfc3339());
+                    summary.revoked_machine_tokens.push(token.id.clone());
+                }
+            }
+            for grant in &mut state.role_grants {
+                if grant.actor_id == actor_id && grant.is_active(now) {
+                    grant.revoked_at = Some(now.to_rfc3339());
+                    summary.revoked_role_grants.push(grant.id.clone());
+                    role_grants.push(grant.clone());
+                }
+            }
+            for grant in &mut state.impersonation_grants {
+                let involved = grant.actor_id == actor_id || grant.subject_id == actor_id;
+                if involved && grant.permits(&grant.actor_id, &grant.subject_id, now) {
+                    grant.revoked_at = Some(now.to_rfc3339());
+                    summary.revoked_impersonation_grants.push(grant.id.clone());
+                }
+            }
+            for grant in &mut state.break_glass {
+                let open =
+                    matches!(grant.status, BreakGlassStatus::Requested) || grant.is_active(now);
+                if grant.actor_id == actor_id && open {
+                    grant.status = BreakGlassStatus::Revoked;
+                    grant.revoked_by = Some(offboarded_by.to_string());
+                    grant.revoked_at = Some(now.to_rfc3339());
+                    summary.revoked_break_glass.push(grant.id.clone());
+                    break_glass.push(grant.clone());
+                }
+            }
+
+            let mut context = BTreeMap::new();
+            context.insert(
+                "cancelled_approvals".into(),
+                Value::from(summary.cancelled_approvals.clone()),
+            );
+            context.insert(
+                "revoked_machine_tokens".into(),
+                Value::from(summary.revoked_machine_tokens.clone()),
+            );
+            context.insert(
+                "revoked_role_grants".into(),
+                Value::from(summary.revoked_role_grants.clone()),
+            );
+            context.insert(
+                "revoked_impersonation_grants".into(),
+                Value::from(summary.revoked_impersonation_grants.clone()),
+            );
+            context.insert(
+                "revoked_break_glass".into(),
+                Value::from(summary.revoked_break_glass.clone()),
+            );
+            let request = ActionPolicyRequest {
+                actor_id: offboarded_by.to_string(),
+                actor_role: "system".into(),
+                action: "rbac.offboard".into(),
+                resource: format!("user:{actor_id}"),
+                destination: "local".into(),
+                approval_id: None,
+                occurred_at: None,
+                authenticated_at: None,
+                on_behalf_of: None,
+                context,
+            };
+            summary.receipt_id = push_receipt(
+                state,
+                &request,
+                ReceiptResult::Allowed,
+                "user deactivated; dependent access revoked",
+            );
+            Ok((summary, role_grants, break_glass))
+        })?;
+
+        for grant in &role_grants {
+            self.publish_role_grant(grant, "temporary role revoked: user deactivated");
+        }
+        for grant in &break_glass {
+            self.publish_break_glass(grant, "break-glass revoked: user deactivated");
+        }
+        for approval_id in &summary.cancelled_approvals {
+            self.publish(RuntimeEventKind::ApprovalResolved {
+                approval_id: approval_id.clone(),
+                approved: false,
+                decided_by: offboarded_by.to_string(),
+            });
+        }
+        Ok(summary)
+    }
+
+    /// Evaluates an action presented with a machine token secret. The token's
+    /// allowlist stands in for role rules; blocked destinations still apply.
+    /// Unknown, expired or revoked tokens are an error, not a receipt.
+    pub fn evaluate_machine_action(
+        &self,
+        secret: &str,
+        action: &str,
+        resource: &str,
+        destination: &str,
+    ) -> Result<ActionPolicyDecision> {
+        let digest = token_digest(secret.trim());
+        self.record(|state| {
+            let now = self.now();
+            let Some(token) = state
+                .machine_tokens
+                .iter_mut()
+                .find(|token| token.token_sha256 == digest && token.is_active(now))
+            else {
+                anyhow::bail!("machine token is invalid, expired or revoked");
+            };
+            token.last_used_at = Some(now.to_rfc3339());
+            let token = token.clone();
+
+            let mut context = BTreeMap::new();
+            context.insert("machine_token".into(), Value::String(token.name.clone()));
+            let request = ActionPolicyRequest {
+                actor_id: token.actor_id(),
+                actor_role: "machine".into(),
+                action: action.to_string(),
+                resource: resource.to_string(),
+                destination: destination.to_string(),
+                approval_id: None,
+                occurred_at: None,
+                authenticated_at: None,
+                on_behalf_of: None,
+                context,
+            };
+
+            let denial = if let Some(entry) =
+                blocked_destination(&state.blocked_destinations, destination)
+            {
+                Some(format!("destination is blocked by '{entry}'"))
+            } else if let Some(reason) =
+                residency_denial(&state.residency_destinations, destination)
+            {
+                Some(reason)
+            } else if !token
+                .allowed_actions
+                .iter()
+                .any(|pattern| glob_match(pattern, action))
+            {
+                Some("action is not in the machine token allowlist".to_string())
+            } else {
+                None
+            };
+
+            let (allowed, reason) = match denial {
+                Some(reason) => (false, reason),
+                None => (true, "machine token allowed".to_string()),
+            };
+            let result = if allowed {
+                ReceiptResult::Allowed
+            } else {
+                ReceiptResult::Denied
+            };
+            let receipt_id = push_receipt(state, &request, result, &reason);
+            Ok(ActionPolicyDecision {
+                allowed,
+                requires_approval: false,
+                requires_reauth: false,
+                reason,
+                approval_id: None,
+                receipt_id,
+            })
+        })
+    }
+
+    /// Files a break-glass request. It grants nothing until the same operator
+    /// activates it with [`ControlPlaneStore::break_glass_activate`].
+    pub fn break_glass_request(
+        &self,
+        actor_id: &str,
+        actor_role: &str,
+        reason: &str,
+        duration_minutes: u32,
+    ) -> Result<BreakGlassGrant> {
+        if actor_role != "operator" {
+            anyhow::bail!("break-glass is only available to operators");
+        }
+        if reason.trim().is_empty() {
+            anyhow::bail!("break-glass requires a reason");
+        }
+
+        let grant = self.update(|state| {
+            let grant = BreakGlassGrant {
+                id: uuid::Uuid::new_v4().to_string(),
+                actor_id: actor_id.to_string(),
+                original_role: actor_role.to_string(),
+                reason: reason.trim().to_string(),
+                duration_minutes: duration_minutes.clamp(5, 240),
+                requested_at: self.now().to_rfc3339(),
+                activated_at: None,
+                expires_at: None,
+                status: BreakGlassStatus::Requested,
+                revoked_by: None,
+                revoked_at: None,
+            };
+            state.break_glass.push(grant.clone());
+            push_break_glass_receipt(state, &grant, "break_glass.request");
+            Ok(grant)
+        })?;
+        self.publish_break_glass(&grant, "break-glass requested");
+        Ok(grant)
+    }
+
+    pub fn break_glass_activate(&self, grant_id: &str, actor_id: &str) -> Result<BreakGlassGrant> {
+        let grant = self.update(|state| {
+            let now = self.now();
+            let Some(grant) = state
+                .break_glass
+                .iter_mut()
+                .find(|grant| grant.id == grant_id)
+            else {
+                anyhow::bail!("break-glass grant '{grant_id}' not found");
+            };
+            if grant.actor_id != actor_id {
+                anyhow::bail!("break-glass grant belongs to another operator");
+            }
+            if !matches!(grant.status, BreakGlassStatus::Requested) {
+                anyhow::bail!("break-glass grant '{grant_id}' is not awaiting activation");
+            }
+
+            grant.status = BreakGlassStatus::Active;
+            grant.activated_at = Some(now.to_rfc3339());
+            grant.expires_at =
+                Some((now + Duration::minutes(i64::from(grant.duration_minutes))).to_rfc3339());
+            let grant = grant.clone();
+            push_break_glass_receipt(state, &grant, "break_glass.activate");
+            Ok(grant)
+        })?;
+        self.publish_break_glass(&grant, "break-glass activated: operator elevated to admin");
+        Ok(grant)
+    }
+
+    /// Ends an active grant early. Expired, revoked and never-activated
+    /// grants are rejected rather than re-stamped.
+    pub fn break_glass_revoke(&self, grant_id: &str, actor_id: &str) -> Result<BreakGlassGrant> {
+        let grant = self.update(|state| {
+            let now = self.now();
+            let Some(grant) = state
+                .break_glass
+                .iter_mut()
+                .find(|grant| grant.id == grant_id)
+            else {
+                anyhow::bail!("break-glass grant '{grant_id}' not found");
+            };
+            if !grant.is_active(now) {
+                anyhow::bail!("break-glass grant '{grant_id}' is not active");
+            }
+
+            grant.status = BreakGlassStatus::Revoked;
+            grant.revoked_by = Some(actor_id.to_string());
+            grant.revoked_at = Some(now.to_rfc3339());
+            let grant = grant.clone();
+            push_break_glass_receipt(state, &grant, "break_glass.revoke");
+            Ok(grant)
+        })?;
+        self.publish_break_glass(&grant, "break-glass revoked");
+        Ok(grant)
+    }
+
+    /// Lists grants after expiring any whose window has closed.
+    pub fn list_break_glass(&self) -> Result<Vec<BreakGlassGrant>> {
+        let (grants, expired) = self.update(|state| {
+            let expired = expire_break_glass(state, self.now());
+            Ok((state.break_glass.clone(), expired))
+        })?;
+        for grant in &expired {
+            self.publish_break_glass(grant, "break-glass expired");
+        }
+        Ok(grants)
+    }
+
+    fn publish_break_glass(&self, grant: &BreakGlassGrant, message: &str) {
+        self.publish(RuntimeEventKind::SecurityAlert {
+            category: "break_glass".into(),
+            subject: grant.actor_id.clone(),
+            message: message.into(),
+        });
+    }
+
+    pub fn purge_by_retention(&self) -> Result<PurgeSummary> {
+        self.update(|state| {
+            let now = Utc::now();
+
+            let receipts_cutoff = now - Duration::days(i64::from(state.retention.receipts_days));
+            let approvals_cutoff = now - Duration::days(i64::from(state.retention.approvals_days));
+
+            let receipts_before = state.receipts.len();
+            state.receipts.retain(|receipt| {
+                parse_rfc3339(&receipt.timestamp).is_none_or(|created| created >= receipts_cutoff)
+            });
+
+            let approvals_before = state.approvals.len();
+            state.approvals.retain(|request| {
+                parse_rfc3339(&request.created_at).is_none_or(|created| created >= approvals_cutoff)
+            });
+
+            Ok(PurgeSummary {
+                removed_receipts: receipts_before.saturating_sub(state.receipts.len()),
+                removed_approvals: approvals_before.saturating_sub(state.approvals.len()),
+                removed_log_files: 0,
+            })
+        })
+    }
+
+    /// Applies retention to the control-plane store and to the diagnostics
+    /// logs in `log_dir`.
+    pub fn purge_with_logs(&self, log_dir: &Path) -> Result<PurgeSummary> {
+        let mut summary = self.purge_by_retention()?;
+        let days = self.load()?.retention.diagnostics_logs_days;
+        summary.removed_log_files = crate::logs::purge_logs_older_than(log_dir, days)?;
+        Ok(summary)
+    }
+
+    /// Reconstructs what the control plane looked like at `as_of`: the rule
+    /// set then in effect, approvals as they stood, the receipts written up
+    /// to that point and any break-glass elevation that was active.
+    pub fn state_at(&self, as_of: &str) -> Result<ControlPlaneStateAt> {
+        let Some(at) = parse_rfc3339(as_of) else {
+            anyhow::bail!("'{as_of}' is not an RFC 3339 timestamp");
+        };
+        let state = self.load()?;
+        let not_after = |raw: &str| parse_rfc3339(raw).is_some_and(|time| time <= at);
+
+        let revision = state
+            .policy_history
+            .iter()
+            .rev()
+            .find(|revision| not_after(&revision.effective_at));
+
+        let approvals = state
+            .approvals
+            .into_iter()
+            .filter(|approval| not_after(&approval.created_at))
+            .map(|mut approval| {
+                if !approval.decided_at.as_deref().is_some_and(not_after) {
+                    approval.status = ApprovalStatus::Pending;
+                    approval.decided_by = None;
+                    approval.decided_by_user_id = None;
+                    approval.decided_at = None;
+                    approval.reason = None;
+                }
+                approval
+            })
+            .collect();
+
+        let recent_receipts = state
+            .receipts
+            .into_iter()
+            .filter(|receipt| not_after(&receipt.timestamp))
+            .take(100)
+            .collect();
+
+        let active_break_glass = state
+            .break_glass
+            .into_iter()
+            .filter(|grant| {
+                grant.activated_at.as_deref().is_some_and(not_after)
+                    && grant
+                        .expires_at
+                        .as_deref()
+                        .and_then(parse_rfc3339)
+                        .is_some_and(|expires| at < expires)
+            })
+            .collect();
+
+        Ok(ControlPlaneStateAt {
+            as_of: at.to_rfc3339(),
+            policy_revision: revision.map(|revision| revision.revision),
+            policy_rules: revision
+                .map(|revision| revision.rules.clone())
+                .unwrap_or_default(),
+            approvals,
+            recent_receipts,
+            active_break_glass,
+        })
+    }
+
+    /// Drops dead records (duplicate ids, approvals and break-glass grants
+    /// closed longer ago than the approvals retention window) and rewrites
+    /// the store, reporting its on-disk size before and after.
+    pub fn compact(&self) -> Result<CompactionReport> {
+        let location = self.backend.location().to_path_buf();
+        let size = || fs::metadata(&location).map_or(0, |meta| meta.len());
+        let bytes_before = size();
+
+        let mut report = self.update(|state| {
+            let cutoff = Utc::now() - Duration::days(i64::from(state.retention.approvals_days));
+            let closed_before = |at: Option<&str>| {
+                at.and_then(parse_rfc3339)
+                    .is_some_and(|closed| closed < cutoff)
+            };
+
+            let mut seen = std::collections::HashSet::new();
+            let receipts_before = state.receipts.len();
+            state
+                .receipts
+                .retain(|receipt| seen.insert(receipt.id.clone()));
+            let removed_duplicate_receipts = receipts_before - state.receipts.len();
+
+            seen.clear();
+            let approvals_before = state.approvals.len();
+            state
+                .approvals
+                .retain(|approval| seen.insert(approval.id.clone()));
+            let removed_duplicate_approvals = approvals_before - state.approvals.len();
+
+            let approvals_before = state.approvals.len();
+            state.approvals.retain(|approval| {
+                matches!(approval.status, ApprovalStatus::Pending)
+                    || !closed_before(approval.decided_at.as_deref())
+            });
+            let removed_resolved_approvals = approvals_before - state.approvals.len();
+
+            let grants_before = state.break_glass.len();
+            state.break_glass.retain(|grant| {
+                matches!(
+                    grant.status,
+                    BreakGlassStatus::Requested | BreakGlassStatus::Active
+                ) || !closed_before(grant.expires_at.as_deref().or(Some(&grant.requested_at)))
+            });
+
+            Ok(CompactionReport {
+                bytes_before,
+                bytes_after: 0,
+                removed_duplicate_receipts,
+                removed_duplicate_approvals,
+                removed_resolved_approvals,
+                removed_closed_break_glass: grants_before - state.break_glass.len(),
+            })
+        })?;
+        report.bytes_after = size();
+        Ok(report)
+    }
+
+    /// Runs [`ControlPlaneStore::compact`] on a fixed interval until the
+    /// returned task is aborted.
+    pub fn spawn_periodic_compaction(
+        self,
+        every: std::time::Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                ticker.tick().await;
+                if let Err(error) = self.compact() {
+                    tracing::warn!("control-plane compaction failed: {error}");
+                }
+            }
+        })
+    }
+
+    pub fn export_receipts(&self, output_path: &Path) -> Result<PathBuf> {
+        let state = self.load()?;
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+
+        let payload = serde_json::to_string_pretty(&state.receipts)
+            .context("failed to serialize control-plane receipts")?;
+        fs::write(output_path, payload)
+            .with_context(|| format!("failed to write {}", output_path.display()))?;
+        Ok(output_path.to_path_buf())
+    }
+
+    fn normalize(&self, state: &mut ControlPlaneState) {
+        if state.policy_rules.is_empty() {
+            state.policy_rules = default_policy_rules();
+        }
+        if state.access_state.trial_started_at.is_none()
+            && matches!(state.access_state.plan, AccessPlan::Trial)
+        {
+            state.access_state.start_trial();
+        }
+    }
+}
+
+/// Returns the process-wide lock guarding a control-plane storage location.
+/// Every store opened for the same workspace shares one lock, so short-lived
+/// stores created per command still serialize their read-modify-write cycles.
+fn state_file_lock(path: &Path) -> Arc<Mutex<()>> {
+    static LOCKS: OnceLock<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> = OnceLock::new();
+    let mut locks = LOCKS.get_or_init(|| Mutex::new(HashMap::new())).lock();
+    Arc::clone(locks.entry(path.to_path_buf()).or_default())
+}
+
+/// An approver must name an active user holding owner or admin.
+fn validate_approver(identity: &ApproverIdentity) -> Result<()> {
+    if identity.user_id.trim().is_empty() {
+        anyhow::bail!("approver identity is missing a user id");
+    }
+    if !identity.active {
+        anyhow::bail!("approver '{}' is not active", identity.user_id);
+    }
+    if !matches!(identity.role.as_str(), "owner" | "admin") {
+        anyhow::bail!("only owner/admin can resolve approvals");
+    }
+    Ok(())
+}
+
+fn token_digest(secret: &str) -> String {
+    format!("{:x}", Sha256::digest(secret.as_bytes()))
+}
+
+fn decision_cache(path: &Path) -> Arc<Mutex<DecisionCache>> {
+    static CACHES: OnceLock<Mutex<HashMap<PathBuf, Arc<Mutex<DecisionCache>>>>> = OnceLock::new();
+    let mut caches = CACHES.get_or_init(|| Mutex::new(HashMap::new())).lock();
+    Arc::clone(caches.entry(path.to_path_buf()).or_default())
+}
+
+/// Only plain allow decisions that depend on nothing but the request and the
+/// current policy may be served from the cache: no approvals, impersonation,
+/// break-glass elevation, re-auth freshness or time/rate conditions.
+fn is_cacheable(
+    state: &ControlPlaneState,
+    request: &ActionPolicyRequest,
+    now: DateTime<Utc>,
+) -> bool {
+    request.approval_id.is_none()
+        && request.on_behalf_of.is_none()
+        && !state.reauth.is_sensitive(&request.action)
+        && state.audit_verbosity.level_for(&request.action) != AuditVerbosity::Sampled
+        && !state
+            .break_glass
+            .iter()
+            .any(|grant| grant.actor_id == request.actor_id && grant.is_active(now))
+        && !state
+            .role_grants
+            .iter()
+            .any(|grant| grant.actor_id == request.actor_id && grant.is_active(now))
+        && matching_rule(&state.policy_rules, request).is_some_and(|rule| {
+            !rule.require_approval
+                && rule.allowed_hours.is_none()
+                && rule.max_invocations_per_hour.is_none()
+        })
+}
+
+/// Inserts `receipts` (oldest first) ahead of the stored newest-first list.
+fn prepend_receipts(state: &mut ControlPlaneState, receipts: Vec<ActionReceipt>) {
+    if receipts.is_empty() {
+        return;
+    }
+    let older = std::mem::take(&mut state.receipts);
+    state.receipts = receipts.into_iter().rev().chain(older).collect();
+    state.receipts.truncate(10_000);
+}
+
+/// Decides `request` as of `now`, which must come from the server clock:
+/// the caller-supplied `occurred_at` is only recorded on the receipt.
+fn evaluate_request(
+    state: &mut ControlPlaneState,
+    request: &ActionPolicyRequest,
+    now: DateTime<Utc>,
+) -> ActionPolicyDecision {
+    if let Some(subject_id) = request
+        .on_behalf_of
+        .as_deref()
+        .filter(|subject_id| *subject_id != request.actor_id)
+    {
+        let granted = state
+            .impersonation_grants
+            .iter()
+            .any(|grant| grant.permits(&request.actor_id, subject_id, now));
+        if !granted {
+            let reason = "acting on behalf of another user requires an impersonation grant";
+            let receipt = push_receipt(state, request, ReceiptResult::Denied, reason);
+            return ActionPolicyDecision {
+                allowed: false,
+                requires_approval: false,
+                requires_reauth: false,
+                reason: reason.into(),
+                approval_id: None,
+                receipt_id: receipt,
+            };
+        }
+    }
+
+    let elevated;
+    let request = match state
+        .break_glass
+        .iter()
+        .find(|grant| grant.actor_id == request.actor_id && grant.is_active(now))
+    {
+        Some(grant) => {
+            let mut context = request.context.clone();
+            context.insert(
+                "break_glass_grant_id".into(),
+                Value::String(grant.id.clone()),
+            );
+            elevated = ActionPolicyRequest {
+                actor_role: "admin".into(),
+                context,
+                ..request.clone()
+            };
+            &elevated
+        }
+        None => match state
+            .role_grants
+            .iter()
+            .find(|grant| grant.actor_id == request.actor_id && grant.is_active(now))
+        {
+            Some(grant) => {
+                let mut context = request.context.clone();
+                context.insert("role_grant_id".into(), Value::String(grant.id.clone()));
+                elevated = ActionPolicyRequest {
+                    actor_role: grant.role.clone(),
+                    context,
+                    ..request.clone()
+                };
+                &elevated
+            }
+            None => request,
+        },
+    };
+
+    if !state
+        .access_state
+        .can_access_view(&state.access_state.active_view)
+    {
+        let receipt = push_receipt(
+            state,
+            request,
+            ReceiptResult::Denied,
+            "access plan does not permit the current workspace view",
+        );
+        return ActionPolicyDecision {
+            allowed: false,
+            requires_approval: false,
+            requires_reauth: false,
+            reason: "access plan does not permit the current workspace view".into(),
+            approval_id: None,
+            receipt_id: receipt,
+        };
+    }
+
+    if let Some(entry) = blocked_destination(&state.blocked_destinations, &request.destination) {
+        let reason = format!("destination is blocked by '{entry}'");
+        let receipt = push_receipt(state, request, ReceiptResult::Denied, &reason);
+        return ActionPolicyDecision {
+            allowed: false,
+            requires_approval: false,
+            requires_reauth: false,
+            reason,
+            approval_id: None,
+            receipt_id: receipt,
+        };
+    }
+
+    if let Some(reason) = residency_denial(&state.residency_destinations, &request.destination) {
+        let receipt = push_receipt(state, request, ReceiptResult::Denied, &reason);
+        return ActionPolicyDecision {
+            allowed: false,
+            requires_approval: false,
+            requires_reauth: false,
+            reason,
+            approval_id: None,
+            receipt_id: receipt,
+        };
+    }
+
+    if let Some(reason) = provider_request_denial(
+        &state.allowed_provider_regions,
+        &state.provider_endpoint_regions,
+        request,
+    ) {
+        let receipt = push_receipt(state, request, ReceiptResult::Denied, &reason);
+        return ActionPolicyDecision {
+            allowed: false,
+            requires_approval: false,
+            requires_reauth: false,
+            reason,
+            approval_id: None,
+            receipt_id: receipt,
+        };
+    }
+
+    if state.reauth.is_sensitive(&request.action)
+        && !state
+            .reauth
+            .is_fresh(request.authenticated_at.as_deref(), now)
+    {
+        let receipt = push_receipt(
+            state,
+            request,
+            ReceiptResult::Denied,
+            "sensitive action requires recent re-authentication",
+        );
+        return ActionPolicyDecision {
+            allowed: false,
+            requires_approval: false,
+            requires_reauth: true,
+            reason: "sensitive action requires recent re-authentication".into(),
+            approval_id: None,
+            receipt_id: receipt,
+        };
+    }
+
+    let Some(rule) = matching_rule(&state.policy_rules, request) else {
+        let receipt = push_receipt(
+            state,
+            request,
+            ReceiptResult::Denied,
+            "no matching policy rule",
+        );
+        return ActionPolicyDecision {
+            allowed: false,
+            requires_approval: false,
+            requires_reauth: false,
+            reason: "no matching policy rule".into(),
+            approval_id: None,
+            receipt_id: receipt,
+        };
+    };
+
+    let needs_approval = rule.require_approval;
+    let rate_limited = rule.max_invocations_per_hour.is_some();
+    if let Some(reason) = rule.condition_violation(&state.receipts, request, now) {
+        let receipt = push_receipt(state, request, ReceiptResult::Denied, &reason);
+        return ActionPolicyDecision {
+            allowed: false,
+            requires_approval: false,
+            requires_reauth: false,
+            reason,
+            approval_id: None,
+            receipt_id: receipt,
+        };
+    }
+
+    if !needs_approval {
+        let receipt = if rate_limited || keeps_allowed_receipt(state, &request.action) {
+            push_receipt(state, request, ReceiptResult::Allowed, "policy allowed")
+        } else {
+            String::new()
+        };
+        return ActionPolicyDecision {
+            allowed: true,
+            requires_approval: false,
+            requires_reauth: false,
+            reason: "policy allowed".into(),
+            approval_id: None,
+            receipt_id: receipt,
+        };
+    }
+
+    let Some(existing_approval_id) = request.approval_id.as_deref() else {
+        let approval_id = uuid::Uuid::new_v4().to_string();
+        state.approvals.push(ApprovalRequest {
+            id: approval_id.clone(),
+            created_at: now.to_rfc3339(),
+            actor_id: request.actor_id.clone(),
+            actor_role: request.actor_role.clone(),
+            action: request.action.clone(),
+            resource: request.resource.clone(),
+            destination: request.destination.clone(),
+            status: ApprovalStatus::Pending,
+            decided_by: None,
+            decided_by_user_id: None,
+            decided_at: None,
+            group_id: None,
+            reason: None,
+            context: request.context.clone(),
+        });
+        let receipt = push_receipt(
+            state,
+            request,
+            ReceiptResult::PendingApproval,
+            "action requires approval",
+        );
+        return ActionPolicyDecision {
+            allowed: false,
+            requires_approval: true,
+            requires_reauth: false,
+            reason: "action requires approval".into(),
+            approval_id: Some(approval_id),
+            receipt_id: receipt,
+        };
+    };
+
+    let Some(approval) = state
+        .approvals
+        .iter()
+        .find(|approval| approval.id == existing_approval_id)
+    else {
+        let receipt = push_receipt(state, request, ReceiptResult::Denied, "approval not found");
+        return ActionPolicyDecision {
+            allowed: false,
+            requires_approval: false,
+            requires_reauth: false,
+            reason: "approval not found".into(),
+            approval_id: Some(existing_approval_id.to_string()),
+            receipt_id: receipt,
+        };
+    };
+
+    let matches_request = approval.actor_id == request.actor_id
+        && approval.actor_role == request.actor_role
+        && approval.action == request.action
+        && approval.resource == request.resource
+        && approval.destination == request.destination;
+
+    let (result, allowed, requires_approval, reason) = if matches_request {
+        match approval.status {
+            ApprovalStatus::Approved => (ReceiptResult::Allowed, true, false, "approved action"),
+            ApprovalStatus::Rejected => (ReceiptResult::Denied, false, false, "approval rejected"),
+            ApprovalStatus::Pending => (
+                ReceiptResult::PendingApproval,
+                false,
+                true,
+                "approval is still pending",
+            ),
+        }
+    } else {
+        (
+            ReceiptResult::Denied,
+            false,
+            false,
+            "approval does not match action request",
+        )
+    };
+
+    let receipt = push_receipt(state, request, result, reason);
+    ActionPolicyDecision {
+        allowed,
+        requires_approval,
+        requires_reauth: false,
+        reason: reason.into(),
+        approval_id: Some(existing_approval_id.to_string()),
+        receipt_id: receipt,
+    }
+}
+
+/// Appends a policy revision whenever the live rule set differs from the
+/// latest recorded one. The first revision of an existing workspace is dated
+/// to its trial start, the earliest moment those rules can have applied.
+fn track_policy_changes(state: &mut ControlPlaneState) {
+    if state
+        .policy_history
+        .last()
+        .is_some_and(|revision| revision.rules == state.policy_rules)
+    {
+        return;
+    }
+
+    let now = Utc::now().to_rfc3339();
+    let effective_at = if state.policy_history.is_empty() {
+        state.access_state.trial_started_at.clone().unwrap_or(now)
+    } else {
+        now
+    };
+    let revision = state
+        .policy_history
+        .last()
+        .map_or(1, |revision| revision.revision + 1);
+    state.policy_history.push(PolicyRevision {
+        revision,
+        effective_at,
+        rules: state.policy_rules.clone(),
+    });
+}
+
+/// Earliest upcoming moment at which `evaluate_action` would change the state
+/// on its own: a trial lapsing or a staged rule set switching in or out.
+fn next_policy_deadline(state: &ControlPlaneState, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let access = &state.access_state;
+    let trial_lapse = access
+        .trial_downgraded_at
+        .is_none()
+        .then(|| access.trial_status(now))
+        .filter(|status| status.phase == TrialPhase::Active)
+        .and_then(|status| status.expires_at.as_deref().and_then(parse_rfc3339));
+    let staged = state
+        .staged_policies
+        .iter()
+        .filter(|staged| staged.ended_at.is_none())
+        .flat_map(|staged| {
+            let from = staged
+                .activated_at
+                .is_none()
+                .then(|| parse_rfc3339(&staged.effective_from))
+                .flatten();
+            let until = staged.effective_until.as_deref().and_then(parse_rfc3339);
+            [from, until]
+        })
+        .flatten();
+    trial_lapse
+        .into_iter()
+        .chain(staged)
+        .filter(|deadline| *deadline > now)
+        .min()
+}
+
+/// Swaps staged rule sets in and out as their effective windows open and
+/// close. Returns true when the live rules changed.
+fn apply_staged_policies(state: &mut ControlPlaneState, now: DateTime<Utc>) -> bool {
+    let mut changed = false;
+    for index in 0..state.staged_policies.len() {
+        let staged = &state.staged_policies[index];
+        if staged.ended_at.is_some() {
+            continue;
+        }
+        let from = parse_rfc3339(&staged.effective_from);
+        let until = staged.effective_until.as_deref().and_then(parse_rfc3339);
+        let window_closed = until.is_some_and(|until| until <= now);
+
+        if staged.activated_at.is_none() {
+            if window_closed {
+                state.staged_policies[index].ended_at = Some(now.to_rfc3339());
+            } else if from.is_some_and(|from| from <= now) {
+                let rules = staged.rules.clone();
+                let replaced = std::mem::replace(&mut state.policy_rules, rules);
+                track_policy_changes(state);
+                let revision = state
+                    .policy_history
+                    .last()
+                    .map(|revision| revision.revision);
+                let staged = &mut state.staged_policies[index];
+                staged.activated_at = Some(now.to_rfc3339());
+                staged.activated_revision = revision;
+                staged.replaced_rules = Some(replaced);
+                changed = true;
+            }
+        } else if window_closed {
+            let staged = &mut state.staged_policies[index];
+            staged.ended_at = Some(now.to_rfc3339());
+            // Rules edited by hand while the window was open stay as they are.
+            if state.policy_rules == staged.rules {
+                if let Some(previous) = staged.replaced_rules.clone() {
+                    state.policy_rules = previous;
+                    track_policy_changes(state);
+                    changed = true;
+                }
+            }
+        }
+    }
+    changed
+}
+
+fn decision_result(decision: &ActionPolicyDecision) -> ReceiptResult {
+    if decision.allowed {
+        ReceiptResult::Allowed
+    } else if decision.requires_approval {
+        ReceiptResult::PendingApproval
+    } else {
+        ReceiptResult::Denied
+    }
+}
+
+/// Moves a trial that has run out back to the personal view, once, and
+/// leaves a receipt so the downgrade shows up in the audit trail.
+fn apply_trial_lapse(state: &mut ControlPlaneState, now: DateTime<Utc>) -> bool {
+    let access = &mut state.access_state;
+    if access.trial_downgraded_at.is_some()
+        || !matches!(
+            access.trial_status(now).phase,
+            TrialPhase::Grace | TrialPhase::Expired
+        )
+    {
+        return false;
+    }
+
+    let previous_view = access.active_view.as_str();
+    access.active_view = WorkspaceView::Personal;
+    access.trial_downgraded_at = Some(now.to_rfc3339());
+    access.updated_at = now.to_rfc3339();
+
+    let mut context = BTreeMap::new();
+    context.insert("previous_view".into(), Value::String(previous_view.into()));
+    let request = ActionPolicyRequest {
+        actor_id: "system".into(),
+        actor_role: "system".into(),
+        action: "access.trial_lapsed".into(),
+        resource: "access_state".into(),
+        destination: "local".into(),
+        approval_id: None,
+        occurred_at: None,
+        authenticated_at: None,
+        on_behalf_of: None,
+        context,
+    };
+    push_receipt(
+        state,
+        &request,
+        ReceiptResult::Allowed,
+        "trial ended; workspace downgraded to personal view",
+    );
+    true
+}
+
+/// Marks active grants past their window as expired and returns them.
+fn expire_break_glass(state: &mut ControlPlaneState, now: DateTime<Utc>) -> Vec<BreakGlassGrant> {
+    let mut expired = Vec::new();
+    for grant in &mut state.break_glass {
+        if matches!(grant.status, BreakGlassStatus::Active) && !grant.is_active(now) {
+            grant.status = BreakGlassStatus::Expired;
+            expired.push(grant.clone());
+        }
+    }
+    for grant in &expired {
+        push_break_glass_receipt(state, grant, "break_glass.expire");
+    }
+    expired
+}
+
+/// Break-glass receipts carry `break_glass: true` in their context so audit
+/// views can surface them prominently.
+fn push_break_glass_receipt(state: &mut ControlPlaneState, grant: &BreakGlassGrant, action: &str) {
+    let mut context = BTreeMap::new();
+    context.insert("break_glass".into(), Value::Bool(true));
+    context.insert("grant_id".into(), Value::String(grant.id.clone()));
+    context.insert("reason".into(), Value::String(grant.reason.clone()));
+    if let Some(expires_at) = &grant.expires_at {
+        context.insert("expires_at".into(), Value::String(expires_at.clone()));
+    }
+    if let Some(revoked_by) = &grant.revoked_by {
+        context.insert("revoked_by".into(), Value::String(revoked_by.clone()));
+    }
+
+    let request = ActionPolicyRequest {
+        actor_id: grant.actor_id.clone(),
+        actor_role: grant.original_role.clone(),
+        action: action.to_string(),
+        resource: format!("break_glass:{}", grant.id),
+        destination: "local".into(),
+        approval_id: None,
+        occurred_at: None,
+        authenticated_at: None,
+        on_behalf_of: None,
+        context,
+    };
+    push_receipt(state, &request, ReceiptResult::Allowed, action);
+}
+
+fn push_role_grant_receipt(
+    state: &mut ControlPlaneState,
+    grant: &RoleGrant,
+    actor_id: &str,
+    action: &str,
+) {
+    let mut context = BTreeMap::new();
+    context.insert("grant_id".into(), Value::String(grant.id.clone()));
+    context.insert("role".into(), Value::String(grant.role.clone()));
+    context.insert("reason".into(), Value::String(grant.reason.clone()));
+    context.insert("expires_at".into(), Value::String(grant.expires_at.clone()));
+
+    let request = ActionPolicyRequest {
+        actor_id: actor_id.to_string(),
+        actor_role: "system".into(),
+        action: action.to_string(),
+        resource: format!("user:{}", grant.actor_id),
+        destination: "local".into(),
+        approval_id: None,
+        occurred_at: None,
+        authenticated_at: None,
+        on_behalf_of: None,
+        context,
+    };
+    push_receipt(state, &request, ReceiptResult::Allowed, action);
+}
+
+fn keeps_allowed_receipt(state: &mut ControlPlaneState, action: &str) -> bool {
+    match state.audit_verbosity.level_for(action) {
+        AuditVerbosity::Always => true,
+        AuditVerbosity::DeniedOnly => false,
+        AuditVerbosity::Sampled => {
+            let seen = state
+                .audit_sample_counts
+                .entry(action.to_string())
+                .or_insert(0);
+            *seen += 1;
+            (*seen - 1).is_multiple_of(u64::from(state.audit_verbosity.sample_every.max(1)))
+        }
+    }
+}
+
+fn push_receipt(
+    state: &mut ControlPlaneState,
+    request: &ActionPolicyRequest,
+    result: ReceiptResult,
+    reason: &str,
+) -> String {
+    let mut receipt = build_receipt(state.receipt_metadata.as_ref(), request, result, reason);
+    receipt.policy_revision = state
+        .policy_history
+        .last()
+        .map(|revision| revision.revision);
+    let receipt_id = receipt.id.clone();
+    state.receipts.insert(0, receipt);
+    if state.receipts.len() > 10_000 {
+        state.receipts.truncate(10_000);
+    }
+    receipt_id
+}
+
+fn build_receipt(
+    metadata: Option<&ReceiptMetadata>,
+    request: &ActionPolicyRequest,
+    result: ReceiptResult,
+    reason: &str,
+) -> ActionReceipt {
+    let mut context = request.context.clone();
+    if let Some(occurred_at) = &request.occurred_at {
+        context
+            .entry("occurred_at".into())
+            .or_insert_with(|| Value::String(occurred_at.clone()));
+    }
+    if let Some(metadata) = metadata {
+        if let Ok(value) = serde_json::to_value(metadata) {
+            context.entry("runtime".into()).or_insert(value);
+        }
+    }
+    let undo = if result == ReceiptResult::Allowed {
+        UndoPlan::for_action(&request.action, &request.resource, &request.destination)
+    } else {
+        None
+    };
+    ActionReceipt {
+        id: uuid::Uuid::new_v4().to_string(),
+        timestamp: Utc::now().to_rfc3339(),
+        actor_id: request.actor_id.clone(),
+        actor_role: request.actor_role.clone(),
+        action: request.action.clone(),
+        resource: request.resource.clone(),
+        destination: request.destination.clone(),
+        result,
+        reason: reason.to_string(),
+        on_behalf_of: request.on_behalf_of.clone(),
+        context,
+        undo,
+        policy_revision: None,
+    }
+}
+
+fn clean_destinations(destinations: Vec<String>) -> Result<Vec<String>> {
+    let mut cleaned = Vec::new();
+    for entry in destinations {
+        let entry = entry.trim().trim_start_matches("*.").to_ascii_lowercase();
+        if entry.is_empty() {
+            continue;
+        }
+        if entry.contains('/') && parse_cidr(&entry).is_none() {
+            anyhow::bail!("invalid IP range '{entry}'");
+        }
+        if !cleaned.contains(&entry) {
+            cleaned.push(entry);
+        }
+    }
+    Ok(cleaned)
+}
+
+/// Denial reason for a network destination outside the residency allowlist.
+/// Symbolic destinations such as `local` or `provider` have no host to check.
+fn residency_denial(allowed: &[String], destination: &str) -> Option<String> {
+    if allowed.is_empty() {
+        return None;
+    }
+    let host = destination_host(destination);
+    if !host.contains('.') && host.parse::<IpAddr>().is_err() {
+        return None;
+    }
+    match blocked_destination(allowed, destination) {
+        Some(_) => None,
+        None => Some(format!(
+            "destination '{host}' is outside the data residency allowlist"
+        )),
+    }
+}
+
+/// Returns the deny-list entry that covers `destination`, if any. URLs are
+/// reduced to their host before matching.
+fn blocked_destination<'a>(blocked: &'a [String], destination: &str) -> Option<&'a str> {
+    if blocked.is_empty() {
+        return None;
+    }
+
+    let host = destination_host(destination);
+    let ip = host.parse::<IpAddr>().ok();
+    blocked
+        .iter()
+        .find(|entry| match (parse_cidr(entry), ip) {
+            (Some((network, prefix)), Some(ip)) => ip_in_network(ip, network, prefix),
+            (Some(_), None) => false,
+            (None, _) => host == entry.as_str() || host.ends_with(&format!(".{entry}")),
+        })
+        .map(String::as_str)
+}
+
+fn destination_host(destination: &str) -> String {
+    let rest = destination
+        .split_once("://")
+        .map_or(destination, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let authority = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = if let Some(bracketed) = authority.strip_prefix('[') {
+        bracketed.split(']').next().unwrap_or_default()
+    } else if authority.matches(':').count() == 1 {
+        authority.split(':').next().unwrap_or_default()
+    } else {
+        authority
+    };
+    host.trim_end_matches('.').to_ascii_lowercase()
+}
+
+fn parse_cidr(entry: &str) -> Option<(IpAddr, u8)> {
+    let (address, prefix) = entry.split_once('/').unwrap_or((entry, ""));
+    let address = address.parse::<IpAddr>().ok()?;
+    let max = if address.is_ipv4() { 32 } else { 128 };
+    let prefix = if prefix.is_empty() {
+        max
+    } else {
+        prefix.parse::<u8>().ok().filter(|prefix| *prefix <= max)?
+    };
+    Some((address, prefix))
+}
+
+fn ip_in_network(ip: IpAddr, network: IpAddr, prefix: u8) -> bool {
+    match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) => {
+            let mask = u32::MAX.checked_shl(32 - u32::from(prefix)).unwrap_or(0);
+            u32::from(ip) & mask == u32::from(network) & mask
+        }
+        (IpAddr::V6(ip), IpAddr::V6(network)) => {
+            let mask = u128::MAX.checked_shl(128 - u32::from(prefix)).unwrap_or(0);
+            u128::from(ip) & mask == u128::from(network) & mask
+        }
+        _ => false,
+    }
+}
+
+/// Picks the enabled rule that matches `request` with the highest priority,
+/// preferring the earliest rule among equals.
+fn matching_rule<'a>(
+    rules: &'a [PolicyRule],
+    request: &ActionPolicyRequest,
+) -> Option<&'a PolicyRule> {
+    rules.iter().filter(|rule| rule.matches(request)).fold(
+        None,
+        |best: Option<&PolicyRule>, rule| match best {
+            Some(best) if best.priority >= rule.priority => Some(best),
+            _ => Some(rule),
+        },
+    )
+}
+
+fn matches_filter(filters: &[String], value: &str) -> bool {
+    filters.is_empty() || filters.iter().any(|filter| glob_match(filter, value))
+}
+
+/// Matches `value` against a pattern where `*` spans any run of characters
+/// (including `/` and `:`) and `?` matches exactly one.
+fn glob_match(pattern: &str, value: &str) -> bool {
+    if !pattern.contains(['*', '?']) {
+        return pattern == value;
+    }
+
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+    let (mut p, mut v) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while v < value.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == value[v]) {
+            p += 1;
+            v += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, v));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            v = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|ch| *ch == '*')
+}
+
+fn parse_rfc3339(raw: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(raw)
+        .ok()
+        .map(|value| value.with_timezone(&Utc))
+}
+
+fn default_policy_rules() -> Vec<PolicyRule> {
+    vec![
+        PolicyRule {
+            id: "owner-full-access".into(),
+            actor_roles: vec!["owner".into()],
+            actions: vec!["*".into()],
+            resources: vec!["*".into()],
+            destinations: vec!["*".into()],
+            require_approval: false,
+            enabled: true,
+            allowed_hours: None,
+            max_invocations_per_hour: None,
+            priority: 0,
+        },
+        PolicyRule {
+            id: "admin-full-access".into(),
+            actor_roles: vec!["admin".into()],
+            actions: vec!["*".into()],
+            resources: vec!["*".into()],
+            destinations: vec!["*".into()],
+            require_approval: false,
+            enabled: true,
+            allowed_hours: None,
+            max_invocations_per_hour: None,
+            priority: 0,
+        },
+        PolicyRule {
+            id: "operator-runtime".into(),
+            actor_roles: vec!["operator".into()],
+            actions: vec![
+                "runtime.start".into(),
+                "runtime.stop".into(),
+                "runtime.send_message".into(),
+                "background.enable".into(),
+                "background.disable".into(),
+                "logs.read".into(),
+                "logs.export".into(),
+                "receipts.read".into(),
+            ],
+            resources: vec!["*".into()],
+            destinations: vec!["local".into(), "provider".into(), "workspace".into()],
+            require_approval: false,
+            enabled: true,
+            allowed_hours: None,
+            max_invocations_per_hour: None,
+            priority: 0,
+        },
+        PolicyRule {
+            id: "operator-governed-changes".into(),
+            actor_roles: vec!["operator".into()],
+            actions: vec![
+                "integration.install".into(),
+                "integration.enable".into(),
+                "integration.disable".into(),
+                "skills.install".into(),
+                "skills.enable".into(),
+                "skills.disable".into(),
+                "skills.remove".into(),
+                "mcp.install".into(),
+                "mcp.enable".into(),
+                "mcp.disable".into(),
+                "mcp.update_config".into(),
+                "mcp.remove".into(),
+                "skills.grant".into(),
+                "mcp.grant".into(),
+            ],
+            resources: vec!["*".into()],
+            destinations: vec!["*".into()],
+            require_approval: true,
+            enabled: true,
+            allowed_hours: None,
+            max_invocations_per_hour: None,
+            priority: 0,
+        },
+        PolicyRule {
+            id: "viewer-readonly".into(),
+            actor_roles: vec!["viewer".into()],
+            actions: vec![
+                "logs.read".into(),
+                "receipts.read".into(),
+                "profiles.read".into(),
+            ],
+            resources: vec!["*".into()],
+            destinations: vec!["local".into(), "workspace".into()],
+            require_approval: false,
+            enabled: true,
+            allowed_hours: None,
+            max_invocations_per_hour: None,
+            priority: 0,
+        },
+    ]
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    pub(crate) fn request(
+        actor_role: &str,
+        action: &str,
+        resource: &str,
+        destination: &str,
+    ) -> ActionPolicyRequest {
+        ActionPolicyRequest {
+            actor_id: format!("{actor_role}-a"),
+            actor_role: actor_role.into(),
+            action: action.into(),
+            resource: resource.into(),
+            destination: destination.into(),
+            approval_id: None,
+            occurred_at: None,
+            authenticated_at: None,
+            on_behalf_of: None,
+            context: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn trial_allows_personal_and_org_views() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
+        let mut access = store.start_trial().unwrap();
+
+        assert!(access.can_access_view(&WorkspaceView::Personal));
+        assert!(access.can_access_view(&WorkspaceView::Org));
+
+        access.set_paid_plan(AccessPlan::Personal).unwrap();
+        assert!(access.can_access_view(&WorkspaceView::Personal));
+        assert!(!access.can_access_view(&WorkspaceView::Org));
+    }
+
+    #[test]
+    fn operator_enable_actions_require_approval() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
+        let _ = store.start_trial().unwrap();
+
+        let decision = store
+            .evaluate_action(request(
+                "operator",
+                "integration.enable",
+                "integration:slack",
+                "api.slack.com",
+            ))
+            .unwrap();
+
+        assert!(!decision.allowed);
+        assert!(decision.requires_approval);
+        assert!(decision.approval_id.is_some());
+    }
+
+    #[test]
+    fn approved_action_replay_is_allowed() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
+        let _ = store.start_trial().unwrap();
+
+        let initial = store
+            .evaluate_action(request(
+                "operator",
+                "integration.enable",
+                "integration:slack",
+                "api.slack.com",
+            ))
+            .unwrap();
+
+        let approval_id = initial.approval_id.clone().unwrap();
+        let _ = store
+            .resolve_approval(&approval_id, "admin", true, Some("approved".into()))
+            .unwrap();
+
+        let replay = store
+            .evaluate_action(ActionPolicyRequest {
+                approval_id: Some(approval_id),
+                ..request(
+                    "operator",
+                    "integration.enable",
+                    "integration:slack",
+                    "api.slack.com",
+                )
+            })
+            .unwrap();
 
-        let mut state = self.load()?;
-        let Some(approval) = state
-            .approvals
+        assert!(replay.allowed);
+        assert!(!replay.requires_approval);
+    }
+
+    #[test]
+    fn concurrent_evaluations_do_not_lose_receipts() {
+        let tmp = TempDir::new().unwrap();
+        let _ = ControlPlaneStore::for_workspace(tmp.path())
+            .start_trial()
+            .unwrap();
+
+        let workers: Vec<_> = (0..8)
+            .map(|worker| {
+                let workspace = tmp.path().to_path_buf();
+                std::thread::spawn(move || {
+                    let store = ControlPlaneStore::for_workspace(&workspace);
+                    for _ in 0..5 {
+                        store
+                            .evaluate_action(ActionPolicyRequest {
+                                actor_id: format!("operator-{worker}"),
+                                ..request("operator", "runtime.start", "runtime", "local")
+                            })
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        let store = ControlPlaneStore::for_workspace(tmp.path());
+        assert_eq!(store.get_state().unwrap().receipts.len(), 40);
+    }
+
+    #[test]
+    fn sensitive_actions_require_fresh_authentication() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
+        let _ = store.start_trial().unwrap();
+        assert!(
+            store
+                .evaluate_action(request(
+                    "owner",
+                    "secrets.set",
+                    "secret:openai_api_key",
+                    "local"
+                ))
+                .unwrap()
+                .allowed
+        );
+        store
+            .set_reauth_policy(ReauthPolicy {
+                enabled: true,
+                ..ReauthPolicy::default()
+            })
+            .unwrap();
+
+        let stale = store
+            .evaluate_action(ActionPolicyRequest {
+                authenticated_at: Some((Utc::now() - Duration::hours(1)).to_rfc3339()),
+                ..request("owner", "secrets.set", "secret:openai_api_key", "local")
+            })
+            .unwrap();
+        assert!(!stale.allowed);
+        assert!(stale.requires_reauth);
+
+        let claimed_fresh = store
+            .evaluate_action(ActionPolicyRequest {
+                authenticated_at: Some((Utc::now() - Duration::hours(1)).to_rfc3339()),
+                occurred_at: Some((Utc::now() - Duration::hours(1)).to_rfc3339()),
+                ..request("owner", "secrets.set", "secret:openai_api_key", "local")
+            })
+            .unwrap();
+        assert!(claimed_fresh.requires_reauth);
+
+        let fresh = store
+            .evaluate_action(ActionPolicyRequest {
+                authenticated_at: Some(Utc::now().to_rfc3339()),
+                ..request("owner", "secrets.set", "secret:openai_api_key", "local")
+            })
+            .unwrap();
+        assert!(fresh.allowed);
+
+        let routine = store
+            .evaluate_action(request("owner", "secretsync.run", "runtime", "local"))
+            .unwrap();
+        assert!(routine.allowed);
+    }
+
+    #[test]
+    fn simulation_does_not_persist_and_reports_matched_rule() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
+        let before = store.get_state().unwrap();
+
+        let simulated = store
+            .simulate_action(
+                request(
+                    "operator",
+                    "integration.enable",
+                    "integration:slack",
+                    "slack",
+                ),
+                None,
+            )
+            .unwrap();
+        assert!(simulated.requires_approval);
+        assert_eq!(
+            simulated.matched_rule.map(|rule| rule.id),
+            Some("operator-governed-changes".to_string())
+        );
+
+        let after = store.get_state().unwrap();
+        assert_eq!(after.receipts.len(), before.receipts.len());
+        assert!(after.approvals.is_empty());
+
+        let candidate = store
+            .simulate_action(
+                request(
+                    "operator",
+                    "integration.enable",
+                    "integration:slack",
+                    "slack",
+                ),
+                Some(Vec::new()),
+            )
+            .unwrap();
+        assert!(!candidate.allowed);
+        assert!(candidate.matched_rule.is_none());
+    }
+
+    #[test]
+    fn rule_conditions_enforce_hours_and_rate_limits() {
+        let tmp = TempDir::new().unwrap();
+        let clock = Arc::new(Mutex::new(parse_rfc3339("2026-03-02T22:00:00Z").unwrap()));
+        let store = ControlPlaneStore::for_workspace(tmp.path()).with_clock({
+            let clock = Arc::clone(&clock);
+            move || *clock.lock()
+        });
+        let mut state = store.get_state().unwrap();
+        let rule = state
+            .policy_rules
             .iter_mut()
-            .find(|request| request.id == approval_id)
-        else {
-            anyhow::bail!("approval '{}' not found", approval_id);
+            .find(|rule| rule.id == "operator-runtime")
+            .unwrap();
+        rule.allowed_hours = Some(AllowedHours {
+            start_hour: 9,
+            end_hour: 17,
+            utc_offset_minutes: 60,
+        });
+        rule.max_invocations_per_hour = Some(2);
+        store.save(&state).unwrap();
+
+        let claimed = |time: &str| ActionPolicyRequest {
+            occurred_at: Some(format!("2026-03-02T{time}Z")),
+            ..request("operator", "runtime.start", "runtime", "local")
         };
 
-        approval.status = if approved {
-            ApprovalStatus::Approved
-        } else {
-            ApprovalStatus::Rejected
+        let night = store.evaluate_action(claimed("09:30:00")).unwrap();
+        assert!(!night.allowed);
+        assert!(night.reason.contains("between 09:00 and 17:00"));
+
+        *clock.lock() = parse_rfc3339("2026-03-02T09:30:00Z").unwrap();
+        assert!(store.evaluate_action(claimed("09:30:00")).unwrap().allowed);
+
+        let mut state = store.get_state().unwrap();
+        state.policy_rules[2].allowed_hours = None;
+        store.save(&state).unwrap();
+        let second = store
+            .evaluate_action(request("operator", "runtime.start", "runtime", "local"))
+            .unwrap();
+        assert!(second.allowed);
+        let throttled = store
+            .evaluate_action(request("operator", "runtime.start", "runtime", "local"))
+            .unwrap();
+        assert!(!throttled.allowed);
+        assert!(throttled.reason.contains("2 invocations per hour"));
+        let future = store.evaluate_action(claimed("23:59:00")).unwrap();
+        assert!(!future.allowed);
+        assert_eq!(
+            store.list_receipts(1).unwrap()[0].context["occurred_at"],
+            "2026-03-02T23:59:00Z"
+        );
+    }
+
+    #[test]
+    fn break_glass_elevates_until_expiry() {
+        let tmp = TempDir::new().unwrap();
+        let bus = EventBus::new(16);
+        let mut events = bus.subscribe();
+        let store = ControlPlaneStore::for_workspace(tmp.path()).with_event_bus(bus, "profile-a");
+
+        let denied = store
+            .evaluate_action(request("operator", "profiles.delete", "profile:b", "local"))
+            .unwrap();
+        assert!(!denied.allowed);
+
+        assert!(store
+            .break_glass_request("operator-a", "viewer", "outage", 30)
+            .is_err());
+        let grant = store
+            .break_glass_request("operator-a", "operator", "prod outage", 30)
+            .unwrap();
+        assert!(store.break_glass_activate(&grant.id, "operator-b").is_err());
+        store.break_glass_activate(&grant.id, "operator-a").unwrap();
+
+        let elevated = store
+            .evaluate_action(request("operator", "profiles.delete", "profile:b", "local"))
+            .unwrap();
+        assert!(elevated.allowed);
+
+        let receipts = store.list_receipts(10).unwrap();
+        assert_eq!(receipts[0].actor_role, "admin");
+        assert!(store.break_glass_revoke("missing", "owner").is_err());
+        assert!(receipts
+            .iter()
+            .any(|receipt| receipt.action == "break_glass.activate"
+                && receipt.context.get("break_glass") == Some(&Value::Bool(true))));
+        assert!(matches!(
+            events.try_recv().unwrap().kind,
+            RuntimeEventKind::SecurityAlert { ref category, .. } if category == "break_glass"
+        ));
+
+        let mut state = store.get_state().unwrap();
+        state.break_glass[0].expires_at = Some((Utc::now() - Duration::minutes(1)).to_rfc3339());
+        store.save(&state).unwrap();
+
+        let after = store
+            .evaluate_action(request("operator", "profiles.delete", "profile:b", "local"))
+            .unwrap();
+        assert!(!after.allowed);
+        assert_eq!(
+            store.list_break_glass().unwrap()[0].status,
+            BreakGlassStatus::Expired
+        );
+        assert!(store.break_glass_revoke(&grant.id, "owner").is_err());
+
+        let pending = store
+            .break_glass_request("operator-a", "operator", "second outage", 30)
+            .unwrap();
+        assert!(store.break_glass_revoke(&pending.id, "owner").is_err());
+        store
+            .break_glass_activate(&pending.id, "operator-a")
+            .unwrap();
+        let revoked = store.break_glass_revoke(&pending.id, "owner").unwrap();
+        assert_eq!(revoked.status, BreakGlassStatus::Revoked);
+        assert_eq!(revoked.revoked_by.as_deref(), Some("owner"));
+        assert!(store.break_glass_revoke(&pending.id, "owner").is_err());
+        let receipt = &store.list_receipts(1).unwrap()[0];
+        assert_eq!(receipt.action, "break_glass.revoke");
+        assert_eq!(
+            receipt.context.get("revoked_by"),
+            Some(&Value::String("owner".into()))
+        );
+        assert!(
+            !store
+                .evaluate_action(request("operator", "profiles.delete", "profile:b", "local"))
+                .unwrap()
+                .allowed
+        );
+    }
+
+    #[test]
+    fn compaction_drops_dead_records_and_reports_size() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
+        let decision = store
+            .evaluate_action(request("operator", "skills.install", "skill:a", "local"))
+            .unwrap();
+        store
+            .resolve_approval(
+                decision.approval_id.as_deref().unwrap(),
+                "owner",
+                false,
+                None,
+            )
+            .unwrap();
+        store
+            .evaluate_action(request("operator", "skills.install", "skill:b", "local"))
+            .unwrap();
+
+        let mut state = store.get_state().unwrap();
+        state.approvals[0].decided_at = Some((Utc::now() - Duration::days(400)).to_rfc3339());
+        let duplicate = state.receipts[0].clone();
+        state.receipts.push(duplicate);
+        store.save(&state).unwrap();
+
+        let report = store.compact().unwrap();
+        assert_eq!(report.removed_duplicate_receipts, 1);
+        assert_eq!(report.removed_resolved_approvals, 1);
+        assert!(report.bytes_after < report.bytes_before);
+        assert_eq!(store.list_approvals(false).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn blocked_destinations_override_allow_rules() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
+        store
+            .set_blocked_destinations(vec!["*.Example.com".into(), "10.0.0.0/8".into()])
+            .unwrap();
+        assert!(store
+            .set_blocked_destinations(vec!["10.0.0.0/99".into()])
+            .is_err());
+
+        for destination in [
+            "https://api.example.com/v1",
+            "example.com",
+            "http://10.2.3.4:8080/sync",
+        ] {
+            let decision = store
+                .evaluate_action(request("owner", "audit.remote_sync", "audit", destination))
+                .unwrap();
+            assert!(!decision.allowed, "{destination} should be blocked");
+        }
+
+        for destination in ["https://notexample.com", "http://11.0.0.1", "local"] {
+            let decision = store
+                .evaluate_action(request("owner", "audit.remote_sync", "audit", destination))
+                .unwrap();
+            assert!(decision.allowed, "{destination} should be allowed");
+        }
+    }
+
+    #[test]
+    fn receipts_carry_runtime_metadata() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path())
+            .with_receipt_metadata(ReceiptMetadata::detect("0.9.0", "desktop", "profile-a"));
+        store
+            .evaluate_action(request("owner", "runtime.start", "runtime", "local"))
+            .unwrap();
+
+        let receipt = &store.list_receipts(1).unwrap()[0];
+        let runtime = receipt.context.get("runtime").unwrap();
+        assert_eq!(runtime["app_version"], "0.9.0");
+        assert_eq!(runtime["protocol_version"], CORE_PROTOCOL_VERSION);
+        assert_eq!(runtime["profile_id"], "profile-a");
+        assert!(!runtime["hostname"].as_str().unwrap().is_empty());
+
+        let raw = fs::read_to_string(tmp.path().join(CONTROL_PLANE_FILE)).unwrap();
+        assert!(!raw.contains("receipt_metadata"));
+    }
+
+    #[test]
+    fn identity_approvals_record_the_deciding_user() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
+        let decision = store
+            .evaluate_action(request("operator", "mcp.install", "mcp:github", "local"))
+            .unwrap();
+        let approval_id = decision.approval_id.unwrap();
+
+        let suspended = ApproverIdentity {
+            user_id: "u-admin".into(),
+            role: "admin".into(),
+            active: false,
+        };
+        assert!(store
+            .resolve_approval_as(&approval_id, &suspended, true, None)
+            .is_err());
+        let operator = ApproverIdentity {
+            user_id: "u-op".into(),
+            role: "operator".into(),
+            active: true,
+        };
+        assert!(store
+            .resolve_approval_as(&approval_id, &operator, true, None)
+            .is_err());
+
+        let admin = ApproverIdentity {
+            active: true,
+            ..suspended
+        };
+        let resolved = store
+            .resolve_approval_as(&approval_id, &admin, true, None)
+            .unwrap();
+        assert_eq!(resolved.decided_by.as_deref(), Some("admin"));
+        assert_eq!(resolved.decided_by_user_id.as_deref(), Some("u-admin"));
+    }
+
+    #[test]
+    fn glob_filters_scope_resources_and_priority_breaks_ties() {
+        assert!(glob_match("integration:slack*", "integration:slack-eu"));
+        assert!(glob_match("skill:finance/*", "skill:finance/invoices"));
+        assert!(glob_match("skill:?a*", "skill:tax"));
+        assert!(!glob_match("skill:finance/*", "skill:hr/payroll"));
+        assert!(glob_match("*", ""));
+
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
+        let mut state = store.get_state().unwrap();
+        state.policy_rules.push(PolicyRule {
+            id: "finance-skills-need-approval".into(),
+            actor_roles: vec!["admin".into()],
+            actions: vec!["skills.*".into()],
+            resources: vec!["skill:finance/*".into()],
+            destinations: vec!["*".into()],
+            require_approval: true,
+            enabled: true,
+            allowed_hours: None,
+            max_invocations_per_hour: None,
+            priority: 10,
+        });
+        store.save(&state).unwrap();
+
+        let finance = store
+            .evaluate_action(request(
+                "admin",
+                "skills.enable",
+                "skill:finance/invoices",
+                "local",
+            ))
+            .unwrap();
+        assert!(finance.requires_approval);
+
+        let other = store
+            .evaluate_action(request(
+                "admin",
+                "skills.enable",
+                "skill:hr/payroll",
+                "local",
+            ))
+            .unwrap();
+        assert!(other.allowed);
+    }
+
+    #[test]
+    fn approval_lifecycle_is_published_on_the_event_bus() {
+        let tmp = TempDir::new().unwrap();
+        let bus = EventBus::new(16);
+        let mut events = bus.subscribe();
+        let store = ControlPlaneStore::for_workspace(tmp.path()).with_event_bus(bus, "profile-a");
+
+        let decision = store
+            .evaluate_action(request("operator", "skills.install", "skill:a", "local"))
+            .unwrap();
+        let approval_id = decision.approval_id.unwrap();
+        match events.try_recv().unwrap().kind {
+            RuntimeEventKind::ApprovalRequested {
+                approval_id: id, ..
+            } => assert_eq!(id, approval_id),
+            other => panic!("unexpected event {other:?}"),
+        }
+
+        store
+            .resolve_approval(&approval_id, "owner", true, None)
+            .unwrap();
+        assert!(matches!(
+            events.try_recv().unwrap().kind,
+            RuntimeEventKind::ApprovalResolved { approved: true, .. }
+        ));
+    }
+
+    #[test]
+    fn state_at_reconstructs_rules_and_pending_approvals() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
+        let decision = store
+            .evaluate_action(request("operator", "mcp.install", "mcp:github", "local"))
+            .unwrap();
+        let before_changes = Utc::now().to_rfc3339();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+
+        store
+            .resolve_approval(
+                decision.approval_id.as_deref().unwrap(),
+                "owner",
+                true,
+                None,
+            )
+            .unwrap();
+        let mut state = store.get_state().unwrap();
+        state
+            .policy_rules
+            .retain(|rule| rule.id != "viewer-readonly");
+        store.save(&state).unwrap();
+
+        let past = store.state_at(&before_changes).unwrap();
+        assert_eq!(past.policy_revision, Some(1));
+        assert!(past
+            .policy_rules
+            .iter()
+            .any(|rule| rule.id == "viewer-readonly"));
+        assert_eq!(past.approvals.len(), 1);
+        assert_eq!(past.approvals[0].status, ApprovalStatus::Pending);
+        assert_eq!(past.recent_receipts.len(), 1);
+
+        let present = store.state_at(&Utc::now().to_rfc3339()).unwrap();
+        assert_eq!(present.policy_revision, Some(2));
+        assert_eq!(present.approvals[0].status, ApprovalStatus::Approved);
+        assert!(store.state_at("yesterday").is_err());
+    }
+
+    #[test]
+    fn batch_evaluation_opens_one_approval_group() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
+
+        let planned: Vec<ActionPolicyRequest> = ["skill:a", "skill:b", "skill:c"]
+            .into_iter()
+            .map(|skill| request("operator", "skills.install", skill, "local"))
+            .chain([request("operator", "runtime.start", "runtime", "local")])
+            .collect();
+        let batch = store.evaluate_batch(planned.clone()).unwrap();
+        assert_eq!(batch.decisions.len(), 4);
+        assert!(batch.decisions[3].allowed);
+        let group_id = batch.group_approval_id.unwrap();
+
+        let admin = ApproverIdentity {
+            user_id: "u-admin".into(),
+            role: "admin".into(),
+            active: false,
+        };
+        assert!(store
+            .resolve_approval_group(&group_id, &admin, true, None)
+            .is_err());
+        let unnamed = ApproverIdentity {
+            user_id: " ".into(),
+            active: true,
+            ..admin.clone()
+        };
+        assert!(store
+            .resolve_approval_group(&group_id, &unnamed, true, None)
+            .is_err());
+        let admin = ApproverIdentity {
+            active: true,
+            ..admin
+        };
+        let resolved = store
+            .resolve_approval_group(&group_id, &admin, true, None)
+            .unwrap();
+        assert_eq!(resolved.len(), 3);
+        assert!(resolved
+            .iter()
+            .all(|approval| approval.decided_by_user_id.as_deref() == Some("u-admin")));
+
+        let replay = store
+            .evaluate_action(ActionPolicyRequest {
+                approval_id: batch.decisions[1].approval_id.clone(),
+                ..planned[1].clone()
+            })
+            .unwrap();
+        assert!(replay.allowed);
+        assert!(store
+            .resolve_approval_group(&group_id, &admin, true, None)
+            .is_err());
+    }
+
+    #[test]
+    fn retention_covers_each_data_class() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
+        let policy = store
+            .set_retention_policy(RetentionPolicy {
+                diagnostics_logs_days: 0,
+                outcomes_days: 30,
+                ..RetentionPolicy::default()
+            })
+            .unwrap();
+        assert_eq!(policy.diagnostics_logs_days, 1);
+        assert_eq!(store.set_retention(7, 7).unwrap().outcomes_days, 30);
+
+        let logs = tmp.path().join("logs");
+        fs::create_dir_all(&logs).unwrap();
+        fs::write(logs.join("agent-2020-01-01-000.jsonl"), "{}\n").unwrap();
+        let summary = store.purge_with_logs(&logs).unwrap();
+        assert_eq!(summary.removed_log_files, 1);
+
+        let legacy: RetentionPolicy =
+            serde_json::from_str(r#"{"receipts_days":10,"approvals_days":20}"#).unwrap();
+        assert_eq!(legacy.audit_events_days, 365);
+    }
+
+    #[test]
+    fn lapsed_trial_enters_grace_and_downgrades_view() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
+        store.set_active_view(WorkspaceView::Org).unwrap();
+        assert_eq!(store.trial_status().unwrap().phase, TrialPhase::Active);
+
+        let mut state = store.get_state().unwrap();
+        state.access_state.trial_expires_at = Some((Utc::now() - Duration::hours(1)).to_rfc3339());
+        store.save(&state).unwrap();
+
+        let status = store.trial_status().unwrap();
+        assert_eq!(status.phase, TrialPhase::Grace);
+        assert!(status.remaining_secs > 0);
+
+        let state = store.get_state().unwrap();
+        assert_eq!(state.access_state.active_view, WorkspaceView::Personal);
+        assert!(state.access_state.can_access_view(&WorkspaceView::Personal));
+        assert!(!state.access_state.can_access_view(&WorkspaceView::Org));
+        assert_eq!(
+            state
+                .receipts
+                .iter()
+                .filter(|receipt| receipt.action == "access.trial_lapsed")
+                .count(),
+            1
+        );
+
+        store.trial_status().unwrap();
+        let mut state = store.get_state().unwrap();
+        state.access_state.trial_expires_at = Some((Utc::now() - Duration::days(5)).to_rfc3339());
+        store.save(&state).unwrap();
+        assert_eq!(store.trial_status().unwrap().phase, TrialPhase::Expired);
+        assert!(!store
+            .get_state()
+            .unwrap()
+            .access_state
+            .can_access_view(&WorkspaceView::Personal));
+    }
+
+    #[test]
+    fn impersonation_requires_grant_and_is_recorded() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
+        let on_behalf = || ActionPolicyRequest {
+            on_behalf_of: Some("viewer-b".into()),
+            ..request("operator", "runtime.start", "runtime", "local")
+        };
+
+        assert!(!store.evaluate_action(on_behalf()).unwrap().allowed);
+        assert!(store
+            .grant_impersonation("op", "operator", "operator-a", "viewer-b", "support", 30)
+            .is_err());
+
+        let grant = store
+            .grant_impersonation("u-admin", "admin", "operator-a", "viewer-b", "support", 30)
+            .unwrap();
+        assert!(store.evaluate_action(on_behalf()).unwrap().allowed);
+        let receipt = &store.list_receipts(1).unwrap()[0];
+        assert_eq!(receipt.actor_id, "operator-a");
+        assert_eq!(receipt.on_behalf_of.as_deref(), Some("viewer-b"));
+
+        let mut state = store.get_state().unwrap();
+        state.impersonation_grants[0].expires_at = (Utc::now() - Duration::minutes(1)).to_rfc3339();
+        store.save(&state).unwrap();
+        let back_dated = ActionPolicyRequest {
+            occurred_at: Some((Utc::now() - Duration::hours(1)).to_rfc3339()),
+            ..on_behalf()
+        };
+        assert!(!store.evaluate_action(back_dated).unwrap().allowed);
+
+        store.revoke_impersonation(&grant.id).unwrap();
+        assert!(!store.evaluate_action(on_behalf()).unwrap().allowed);
+    }
+
+    #[test]
+    fn decision_cache_journals_receipts_and_drops_on_policy_change() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path())
+            .with_decision_cache(std::time::Duration::from_secs(30));
+        let path = tmp.path().join(CONTROL_PLANE_FILE);
+
+        let first = store
+            .evaluate_action(request("operator", "runtime.start", "agent", "local"))
+            .unwrap();
+        assert!(first.allowed);
+        let written = fs::read_to_string(&path).unwrap();
+
+        let second = store
+            .evaluate_action(request("operator", "runtime.start", "agent", "local"))
+            .unwrap();
+        assert!(second.allowed);
+        assert_ne!(first.receipt_id, second.receipt_id);
+        assert_eq!(fs::read_to_string(&path).unwrap(), written);
+        let reopened = JsonFileControlPlaneBackend::for_workspace(tmp.path())
+            .read_state()
+            .unwrap()
+            .unwrap();
+        assert_eq!(reopened.receipts[0].id, second.receipt_id);
+
+        let receipts = store.list_receipts(10).unwrap();
+        assert_eq!(receipts[0].id, second.receipt_id);
+        assert_eq!(receipts[1].id, first.receipt_id);
+
+        store
+            .set_reauth_policy(ReauthPolicy {
+                enabled: true,
+                freshness_secs: 300,
+                sensitive_action_classes: vec!["runtime".into()],
+            })
+            .unwrap();
+        let denied = store
+            .evaluate_action(request("operator", "runtime.start", "agent", "local"))
+            .unwrap();
+        assert!(!denied.allowed);
+        assert!(denied.requires_reauth);
+        assert!(!path.with_extension("receipts.jsonl").exists());
+    }
+
+    #[test]
+    fn decision_cache_expires_at_staged_policy_switch() {
+        let tmp = TempDir::new().unwrap();
+        let clock = Arc::new(Mutex::new(Utc::now()));
+        let store = ControlPlaneStore::for_workspace(tmp.path())
+            .with_decision_cache(std::time::Duration::from_secs(30))
+            .with_clock({
+                let clock = Arc::clone(&clock);
+                move || *clock.lock()
+            });
+        let owner_only: Vec<PolicyRule> = default_policy_rules()
+            .into_iter()
+            .filter(|rule| rule.id == "owner-full-access")
+            .collect();
+        let soon = (*clock.lock() + Duration::minutes(1)).to_rfc3339();
+        store
+            .stage_policy("admin-a", "lockdown", owner_only, &soon, None)
+            .unwrap();
+
+        let start = || {
+            store
+                .evaluate_action(request("operator", "runtime.start", "agent", "local"))
+                .unwrap()
         };
-        approval.decided_by = Some(approver_role.to_string());
-        approval.decided_at = Some(Utc::now().to_rfc3339());
-        approval.reason = reason;
+        assert!(start().allowed);
+        assert!(start().allowed);
+        *clock.lock() += Duration::minutes(2);
+        assert!(!start().allowed);
+    }
+
+    #[test]
+    fn machine_tokens_are_limited_to_their_allowlist() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
+
+        assert!(store
+            .create_machine_token("op-1", "operator", "ci", vec!["workflow.*".into()], None)
+            .is_err());
+        let issued = store
+            .create_machine_token(
+                "owner-1",
+                "owner",
+                "ci",
+                vec!["workflow.task_upsert".into()],
+                Some(30),
+            )
+            .unwrap();
+        assert!(issued.secret.starts_with("zcmt_"));
+        let raw = fs::read_to_string(tmp.path().join(CONTROL_PLANE_FILE)).unwrap();
+        assert!(!raw.contains(&issued.secret));
+
+        let allowed = store
+            .evaluate_machine_action(&issued.secret, "workflow.task_upsert", "task-1", "local")
+            .unwrap();
+        assert!(allowed.allowed);
+        let denied = store
+            .evaluate_machine_action(&issued.secret, "secrets.read", "vault", "local")
+            .unwrap();
+        assert!(!denied.allowed);
+
+        let receipts = store.list_receipts(10).unwrap();
+        assert_eq!(receipts[0].actor_role, "machine");
+        assert_eq!(receipts[0].actor_id, issued.token.actor_id());
+        assert!(store.list_machine_tokens().unwrap()[0]
+            .last_used_at
+            .is_some());
+
+        store.revoke_machine_token(&issued.token.id).unwrap();
+        assert!(store
+            .evaluate_machine_action(&issued.secret, "workflow.task_upsert", "task-1", "local")
+            .is_err());
+        assert!(store
+            .evaluate_machine_action("zcmt_bogus", "workflow.task_upsert", "task-1", "local")
+            .is_err());
+    }
+
+    #[test]
+    fn allowed_reversible_actions_carry_an_undo_plan() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
+
+        let enabled = store
+            .evaluate_action(request("owner", "integration.enable", "slack", "local"))
+            .unwrap();
+        let denied = store
+            .evaluate_action(request("viewer", "integration.enable", "slack", "local"))
+            .unwrap();
+        let receipts = store.list_receipts(10).unwrap();
+        let receipt_for = |id: &str| receipts.iter().find(|receipt| receipt.id == id).unwrap();
+        let plan = receipt_for(&enabled.receipt_id).undo.clone().unwrap();
+        assert_eq!(plan.action, "integration.disable");
+        assert_eq!(plan.resource, "slack");
+        assert!(receipt_for(&denied.receipt_id).undo.is_none());
+        assert!(store
+            .undo_receipt(&denied.receipt_id, "owner-a", "owner", |_| Ok(()))
+            .is_err());
+
+        let mut ran = Vec::new();
+        let refused = store
+            .undo_receipt(&enabled.receipt_id, "viewer-a", "viewer", |plan| {
+                ran.push(plan.action.clone());
+                Ok(())
+            })
+            .unwrap();
+        assert!(!refused.allowed);
+        assert!(ran.is_empty());
 
-        let out = approval.clone();
-        self.save(&state)?;
-        Ok(out)
+        let undone = store
+            .undo_receipt(&enabled.receipt_id, "owner-a", "owner", |plan| {
+                ran.push(plan.action.clone());
+                Ok(())
+            })
+            .unwrap();
+        assert!(undone.allowed);
+        assert_eq!(ran, vec!["integration.disable".to_string()]);
+        let undo_receipt = store
+            .list_receipts(10)
+            .unwrap()
+            .into_iter()
+            .find(|receipt| receipt.id == undone.receipt_id)
+            .unwrap();
+        assert_eq!(
+            undo_receipt.context.get("undoes"),
+            Some(&Value::String(enabled.receipt_id.clone()))
+        );
     }
 
-    pub fn set_retention(
-        &self,
-        receipts_days: u32,
-        approvals_days: u32,
-    ) -> Result<RetentionPolicy> {
-        let mut state = self.load()?;
-        state.retention = RetentionPolicy {
-            receipts_days: receipts_days.max(1),
-            approvals_days: approvals_days.max(1),
-        };
-        let out = state.retention.clone();
-        self.save(&state)?;
-        Ok(out)
-    }
+    #[test]
+    fn staged_policies_activate_on_schedule_and_tag_receipts() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
 
-    pub fn purge_by_retention(&self) -> Result<PurgeSummary> {
-        let mut state = self.load()?;
-        let now = Utc::now();
+        let before = store
+            .evaluate_action(request("operator", "runtime.start", "runtime", "local"))
+            .unwrap();
+        assert!(before.allowed);
 
-        let receipts_cutoff = now - Duration::days(i64::from(state.retention.receipts_days));
-        let approvals_cutoff = now - Duration::days(i64::from(state.retention.approvals_days));
+        let owner_only: Vec<PolicyRule> = default_policy_rules()
+            .into_iter()
+            .filter(|rule| rule.id == "owner-full-access")
+            .collect();
+        let tomorrow = (Utc::now() + Duration::days(1)).to_rfc3339();
+        let next_week = store
+            .stage_policy("admin-a", "lockdown", owner_only.clone(), &tomorrow, None)
+            .unwrap();
+        assert!(store
+            .stage_policy(
+                "admin-a",
+                "bad",
+                owner_only.clone(),
+                &tomorrow,
+                Some(&tomorrow)
+            )
+            .is_err());
 
-        let receipts_before = state.receipts.len();
-        state.receipts.retain(|receipt| {
-            parse_rfc3339(&receipt.timestamp).is_none_or(|created| created >= receipts_cutoff)
-        });
+        let impacts = store.preview_staged_policy(&next_week.id).unwrap();
+        assert_eq!(impacts.len(), 1);
+        assert_eq!(impacts[0].receipt_id, before.receipt_id);
+        assert_eq!(impacts[0].current, ReceiptResult::Allowed);
+        assert_eq!(impacts[0].staged, ReceiptResult::Denied);
+        assert!(
+            store
+                .evaluate_action(request("operator", "runtime.start", "runtime", "local"))
+                .unwrap()
+                .allowed
+        );
+        store.cancel_staged_policy(&next_week.id).unwrap();
 
-        let approvals_before = state.approvals.len();
-        state.approvals.retain(|request| {
-            parse_rfc3339(&request.created_at).is_none_or(|created| created >= approvals_cutoff)
-        });
+        let started = (Utc::now() - Duration::minutes(1)).to_rfc3339();
+        let ends = (Utc::now() + Duration::hours(1)).to_rfc3339();
+        let window = store
+            .stage_policy("admin-a", "maintenance", owner_only, &started, Some(&ends))
+            .unwrap();
+        let during = store
+            .evaluate_action(request("operator", "runtime.start", "runtime", "local"))
+            .unwrap();
+        assert!(!during.allowed);
 
-        let out = PurgeSummary {
-            removed_receipts: receipts_before.saturating_sub(state.receipts.len()),
-            removed_approvals: approvals_before.saturating_sub(state.approvals.len()),
+        let receipts = store.list_receipts(10).unwrap();
+        let revision_of = |id: &str| {
+            receipts
+                .iter()
+                .find(|receipt| receipt.id == id)
+                .and_then(|receipt| receipt.policy_revision)
         };
-        self.save(&state)?;
-        Ok(out)
+        let staged = store
+            .list_staged_policies()
+            .unwrap()
+            .into_iter()
+            .find(|staged| staged.id == window.id)
+            .unwrap();
+        assert_eq!(revision_of(&before.receipt_id), Some(1));
+        assert_eq!(revision_of(&during.receipt_id), staged.activated_revision);
+        assert_eq!(staged.activated_revision, Some(2));
+
+        let mut state = store.load().unwrap();
+        assert!(apply_staged_policies(
+            &mut state,
+            Utc::now() + Duration::hours(2)
+        ));
+        assert_eq!(state.policy_rules, default_policy_rules());
+        assert!(state.staged_policies[0].ended_at.is_some());
     }
 
-    pub fn export_receipts(&self, output_path: &Path) -> Result<PathBuf> {
-        let state = self.load()?;
-        if let Some(parent) = output_path.parent() {
-            fs::create_dir_all(parent)
-                .with_context(|| format!("failed to create {}", parent.display()))?;
+    #[test]
+    fn audit_verbosity_thins_allowed_receipts_only() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
+        store
+            .set_audit_verbosity(AuditVerbosityPolicy {
+                actions: BTreeMap::from([
+                    ("workflow".into(), AuditVerbosity::Sampled),
+                    ("memory.list".into(), AuditVerbosity::DeniedOnly),
+                ]),
+                sample_every: 3,
+            })
+            .unwrap();
+
+        let mut recorded = 0;
+        for _ in 0..6 {
+            let decision = store
+                .evaluate_action(request("owner", "workflow.move", "task-1", "local"))
+                .unwrap();
+            assert!(decision.allowed);
+            if !decision.receipt_id.is_empty() {
+                recorded += 1;
+            }
         }
+        assert_eq!(recorded, 2);
 
-        let payload = serde_json::to_string_pretty(&state.receipts)
-            .context("failed to serialize control-plane receipts")?;
-        fs::write(output_path, payload)
-            .with_context(|| format!("failed to write {}", output_path.display()))?;
-        Ok(output_path.to_path_buf())
-    }
+        let listed = store
+            .evaluate_action(request("owner", "memory.list", "memory", "local"))
+            .unwrap();
+        assert!(listed.allowed);
+        assert!(listed.receipt_id.is_empty());
+        let denied = store
+            .evaluate_action(request("viewer", "memory.list", "memory", "local"))
+            .unwrap();
+        assert!(!denied.allowed);
 
-    fn normalize(&self, state: &mut ControlPlaneState) {
-        if state.policy_rules.is_empty() {
-            state.policy_rules = default_policy_rules();
-        }
-        if state.access_state.trial_started_at.is_none()
-            && matches!(state.access_state.plan, AccessPlan::Trial)
-        {
-            state.access_state.start_trial();
+        let receipts = store.load().unwrap().receipts;
+        assert_eq!(receipts.len(), 3);
+        assert_eq!(receipts[0].id, denied.receipt_id);
+
+        let mut state = store.get_state().unwrap();
+        let rule = state
+            .policy_rules
+            .iter_mut()
+            .find(|rule| rule.id == "operator-runtime")
+            .unwrap();
+        rule.max_invocations_per_hour = Some(1);
+        state
+            .audit_verbosity
+            .actions
+            .insert("runtime".into(), AuditVerbosity::DeniedOnly);
+        store.save(&state).unwrap();
+        let allowed = (0..5)
+            .filter(|_| {
+                store
+                    .evaluate_action(request("operator", "runtime.start", "runtime", "local"))
+                    .unwrap()
+                    .allowed
+            })
+            .count();
+        assert_eq!(allowed, 1);
+
+        let cached = ControlPlaneStore::for_workspace(tmp.path())
+            .with_decision_cache(std::time::Duration::from_secs(30));
+        for _ in 0..2 {
+            let decision = cached
+                .evaluate_action(request("owner", "memory.list", "memory", "local"))
+                .unwrap();
+            assert!(decision.allowed && decision.receipt_id.is_empty());
         }
     }
-}
 
-fn push_receipt(
-    state: &mut ControlPlaneState,
-    request: &ActionPolicyRequest,
-    result: ReceiptResult,
-    reason: &str,
-) -> String {
-    let receipt_id = uuid::Uuid::new_v4().to_string();
-    state.receipts.insert(
-        0,
-        ActionReceipt {
-            id: receipt_id.clone(),
-            timestamp: Utc::now().to_rfc3339(),
-            actor_id: request.actor_id.clone(),
-            actor_role: request.actor_role.clone(),
-            action: request.action.clone(),
-            resource: request.resource.clone(),
-            destination: request.destination.clone(),
-            result,
-            reason: reason.to_string(),
-            context: request.context.clone(),
-        },
-    );
-    if state.receipts.len() > 10_000 {
-        state.receipts.truncate(10_000);
-    }
-    receipt_id
-}
+    #[test]
+    fn offboarding_cancels_approvals_and_revokes_tokens() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
 
-fn matches_filter(filters: &[String], value: &str) -> bool {
-    filters.is_empty()
-        || filters
-            .iter()
-            .any(|filter| filter == "*" || filter == value)
-}
+        let pending = store
+            .evaluate_action(request(
+                "operator",
+                "integration.enable",
+                "integration:slack",
+                "api.slack.com",
+            ))
+            .unwrap();
+        let issued = store
+            .create_machine_token("admin-a", "admin", "ci", vec!["workflow.*".into()], None)
+            .unwrap();
+        let kept = store
+            .create_machine_token("owner-a", "owner", "ops", vec!["workflow.*".into()], None)
+            .unwrap();
 
-fn parse_rfc3339(raw: &str) -> Option<DateTime<Utc>> {
-    DateTime::parse_from_rfc3339(raw)
-        .ok()
-        .map(|value| value.with_timezone(&Utc))
-}
+        let summary = store.offboard_actor("operator-a", "owner-a").unwrap();
+        assert_eq!(
+            summary.cancelled_approvals,
+            vec![pending.approval_id.unwrap()]
+        );
+        assert!(summary.revoked_machine_tokens.is_empty());
+        assert!(store.list_approvals(true).unwrap().is_empty());
 
-fn default_policy_rules() -> Vec<PolicyRule> {
-    vec![
-        PolicyRule {
-            id: "owner-full-access".into(),
-            actor_roles: vec!["owner".into()],
-            actions: vec!["*".into()],
-            resources: vec!["*".into()],
-            destinations: vec!["*".into()],
-            require_approval: false,
-            enabled: true,
-        },
-        PolicyRule {
-            id: "admin-full-access".into(),
-            actor_roles: vec!["admin".into()],
-            actions: vec!["*".into()],
-            resources: vec!["*".into()],
-            destinations: vec!["*".into()],
-            require_approval: false,
-            enabled: true,
-        },
-        PolicyRule {
-            id: "operator-runtime".into(),
-            actor_roles: vec!["operator".into()],
-            actions: vec![
-                "runtime.start".into(),
-                "runtime.stop".into(),
-                "runtime.send_message".into(),
-                "background.enable".into(),
-                "background.disable".into(),
-                "logs.read".into(),
-                "logs.export".into(),
-                "receipts.read".into(),
-            ],
-            resources: vec!["*".into()],
-            destinations: vec!["local".into(), "provider".into(), "workspace".into()],
-            require_approval: false,
-            enabled: true,
-        },
-        PolicyRule {
-            id: "operator-governed-changes".into(),
-            actor_roles: vec!["operator".into()],
-            actions: vec![
-                "integration.install".into(),
-                "integration.enable".into(),
-                "integration.disable".into(),
-                "skills.install".into(),
-                "skills.enable".into(),
-                "skills.disable".into(),
-                "skills.remove".into(),
-                "mcp.install".into(),
-                "mcp.enable".into(),
-                "mcp.disable".into(),
-                "mcp.update_config".into(),
-                "mcp.remove".into(),
-            ],
-            resources: vec!["*".into()],
-            destinations: vec!["*".into()],
-            require_approval: true,
-            enabled: true,
-        },
-        PolicyRule {
-            id: "viewer-readonly".into(),
-            actor_roles: vec!["viewer".into()],
-            actions: vec![
-                "logs.read".into(),
-                "receipts.read".into(),
-                "profiles.read".into(),
-            ],
-            resources: vec!["*".into()],
-            destinations: vec!["local".into(), "workspace".into()],
-            require_approval: false,
-            enabled: true,
-        },
-    ]
-}
+        let summary = store.offboard_actor("admin-a", "owner-a").unwrap();
+        assert_eq!(summary.revoked_machine_tokens, vec![issued.token.id]);
+        assert!(store
+            .evaluate_machine_action(&issued.secret, "workflow.run", "task-1", "local")
+            .is_err());
+        assert!(
+            store
+                .evaluate_machine_action(&kept.secret, "workflow.run", "task-1", "local")
+                .unwrap()
+                .allowed
+        );
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tempfile::TempDir;
+        let receipts = store.list_receipts(10).unwrap();
+        let receipt = receipts
+            .iter()
+            .find(|receipt| receipt.id == summary.receipt_id)
+            .unwrap();
+        assert_eq!(receipt.action, "rbac.offboard");
+        assert_eq!(receipt.resource, "user:admin-a");
+    }
 
     #[test]
-    fn trial_allows_personal_and_org_views() {
+    fn offboarding_revokes_elevations_and_impersonation() {
         let tmp = TempDir::new().unwrap();
         let store = ControlPlaneStore::for_workspace(tmp.path());
-        let mut access = store.start_trial().unwrap();
+        let role = store
+            .grant_temporary_role("owner-a", "owner", "operator-a", "admin", "audit", 4)
+            .unwrap();
+        let acting = store
+            .grant_impersonation("owner-a", "owner", "operator-a", "viewer-a", "support", 30)
+            .unwrap();
+        let acted_on = store
+            .grant_impersonation("owner-a", "owner", "admin-a", "operator-a", "support", 30)
+            .unwrap();
+        let kept = store
+            .grant_impersonation("owner-a", "owner", "admin-a", "viewer-a", "support", 30)
+            .unwrap();
+        let active = store
+            .break_glass_request("operator-a", "operator", "outage", 30)
+            .unwrap();
+        store
+            .break_glass_activate(&active.id, "operator-a")
+            .unwrap();
+        let pending = store
+            .break_glass_request("operator-a", "operator", "second outage", 30)
+            .unwrap();
+        let delete = || {
+            store
+                .evaluate_action(request("operator", "profiles.delete", "profile:b", "local"))
+                .unwrap()
+        };
+        assert!(delete().allowed);
 
-        assert!(access.can_access_view(&WorkspaceView::Personal));
-        assert!(access.can_access_view(&WorkspaceView::Org));
+        let summary = store.offboard_actor("operator-a", "owner-a").unwrap();
+        assert_eq!(summary.revoked_role_grants, vec![role.id.clone()]);
+        assert_eq!(
+            summary.revoked_impersonation_grants,
+            vec![acting.id.clone(), acted_on.id.clone()]
+        );
+        assert_eq!(
+            summary.revoked_break_glass,
+            vec![active.id.clone(), pending.id.clone()]
+        );
+        assert!(!delete().allowed);
+        assert!(store
+            .break_glass_activate(&pending.id, "operator-a")
+            .is_err());
+        let grants = store.list_impersonation_grants().unwrap();
+        assert!(grants
+            .iter()
+            .find(|grant| grant.id == kept.id)
+            .is_some_and(|grant| grant.revoked_at.is_none()));
 
-        access.set_paid_plan(AccessPlan::Personal).unwrap();
-        assert!(access.can_access_view(&WorkspaceView::Personal));
-        assert!(!access.can_access_view(&WorkspaceView::Org));
+        let receipt = store
+            .list_receipts(20)
+            .unwrap()
+            .into_iter()
+            .find(|receipt| receipt.id == summary.receipt_id)
+            .unwrap();
+        assert_eq!(
+            receipt.context.get("revoked_break_glass"),
+            Some(&Value::from(vec![active.id, pending.id]))
+        );
+        assert_eq!(
+            receipt.context.get("revoked_role_grants"),
+            Some(&Value::from(vec![role.id]))
+        );
     }
 
     #[test]
-    fn operator_enable_actions_require_approval() {
+    fn temporary_roles_elevate_until_swept() {
         let tmp = TempDir::new().unwrap();
         let store = ControlPlaneStore::for_workspace(tmp.path());
-        let _ = store.start_trial().unwrap();
+        let delete = || {
+            store
+                .evaluate_action(request("viewer", "profiles.delete", "profile:b", "local"))
+                .unwrap()
+        };
+        assert!(!delete().allowed);
 
-        let decision = store
-            .evaluate_action(ActionPolicyRequest {
-                actor_id: "operator-a".into(),
-                actor_role: "operator".into(),
-                action: "integration.enable".into(),
-                resource: "integration:slack".into(),
-                destination: "api.slack.com".into(),
-                approval_id: None,
-                occurred_at: None,
-                context: BTreeMap::new(),
+        assert!(store
+            .grant_temporary_role("admin-a", "admin", "viewer-a", "admin", "audit", 4)
+            .is_err());
+        let grant = store
+            .grant_temporary_role("owner-a", "owner", "viewer-a", "admin", "quarter close", 4)
+            .unwrap();
+        assert!(delete().allowed);
+
+        store
+            .update(|state| {
+                state.role_grants[0].expires_at = (Utc::now() - Duration::minutes(1)).to_rfc3339();
+                Ok(())
             })
             .unwrap();
+        assert!(!delete().allowed);
+        let back_dated = ActionPolicyRequest {
+            occurred_at: Some((Utc::now() - Duration::hours(1)).to_rfc3339()),
+            ..request("viewer", "profiles.delete", "profile:b", "local")
+        };
+        assert!(!store.evaluate_action(back_dated).unwrap().allowed);
+        let swept = store.sweep_role_grants().unwrap();
+        assert_eq!(swept.len(), 1);
+        assert!(store.sweep_role_grants().unwrap().is_empty());
+        assert!(store.revoke_role_grant(&grant.id, "owner-a").is_err());
 
-        assert!(!decision.allowed);
-        assert!(decision.requires_approval);
-        assert!(decision.approval_id.is_some());
+        let actions: Vec<String> = store
+            .list_receipts(10)
+            .unwrap()
+            .into_iter()
+            .filter(|receipt| receipt.action.starts_with("rbac.role_"))
+            .map(|receipt| receipt.action)
+            .collect();
+        assert_eq!(actions, vec!["rbac.role_expire", "rbac.role_grant"]);
     }
 
     #[test]
-    fn approved_action_replay_is_allowed() {
+    fn grant_windows_follow_the_store_clock() {
         let tmp = TempDir::new().unwrap();
-        let store = ControlPlaneStore::for_workspace(tmp.path());
-        let _ = store.start_trial().unwrap();
+        let clock = Arc::new(Mutex::new(Utc::now() - Duration::days(10)));
+        let store = ControlPlaneStore::for_workspace(tmp.path()).with_clock({
+            let clock = Arc::clone(&clock);
+            move || *clock.lock()
+        });
+        let at = |offset: Duration| (*clock.lock() + offset).to_rfc3339();
 
-        let initial = store
-            .evaluate_action(ActionPolicyRequest {
-                actor_id: "operator-a".into(),
-                actor_role: "operator".into(),
-                action: "integration.enable".into(),
-                resource: "integration:slack".into(),
-                destination: "api.slack.com".into(),
-                approval_id: None,
-                occurred_at: None,
-                context: BTreeMap::new(),
-            })
+        let grant = store
+            .grant_temporary_role("owner-a", "owner", "viewer-a", "admin", "audit", 4)
             .unwrap();
+        assert_eq!(grant.granted_at, at(Duration::zero()));
+        assert_eq!(grant.expires_at, at(Duration::hours(4)));
+        *clock.lock() += Duration::hours(5);
+        let swept = store.sweep_role_grants().unwrap();
+        assert_eq!(swept[0].expired_at, Some(at(Duration::zero())));
 
-        let approval_id = initial.approval_id.clone().unwrap();
-        let _ = store
-            .resolve_approval(&approval_id, "admin", true, Some("approved".into()))
+        let elevation = store
+            .break_glass_request("operator-a", "operator", "outage", 30)
             .unwrap();
+        assert_eq!(elevation.requested_at, at(Duration::zero()));
+        let active = store
+            .break_glass_activate(&elevation.id, "operator-a")
+            .unwrap();
+        assert_eq!(active.expires_at, Some(at(Duration::minutes(30))));
+        *clock.lock() += Duration::minutes(10);
+        let revoked = store.break_glass_revoke(&elevation.id, "owner-a").unwrap();
+        assert_eq!(revoked.revoked_at, Some(at(Duration::zero())));
+    }
 
-        let replay = store
-            .evaluate_action(ActionPolicyRequest {
-                actor_id: "operator-a".into(),
-                actor_role: "operator".into(),
-                action: "integration.enable".into(),
-                resource: "integration:slack".into(),
-                destination: "api.slack.com".into(),
-                approval_id: Some(approval_id),
-                occurred_at: None,
-                context: BTreeMap::new(),
-            })
+    #[test]
+    fn residency_allowlist_denies_other_network_destinations() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
+        store
+            .set_residency_destinations(vec!["*.eu.example.com".into(), "10.0.0.0/8".into()])
             .unwrap();
 
-        assert!(replay.allowed);
-        assert!(!replay.requires_approval);
+        let sync = |destination: &str| {
+            store
+                .evaluate_action(request("owner", "audit.remote_sync", "audit", destination))
+                .unwrap()
+        };
+        for destination in [
+            "https://audit.eu.example.com/v1",
+            "http://10.2.3.4",
+            "local",
+        ] {
+            assert!(sync(destination).allowed, "{destination} should be allowed");
+        }
+        let denied = sync("https://audit.us.example.com");
+        assert!(!denied.allowed);
+        assert_eq!(
+            denied.reason,
+            "destination 'audit.us.example.com' is outside the data residency allowlist"
+        );
+        assert_eq!(
+            store.list_receipts(1).unwrap()[0].result,
+            ReceiptResult::Denied
+        );
+    }
+
+    #[test]
+    fn corrupt_json_state_is_recovered_from_backup() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
+        let path = tmp.path().join(CONTROL_PLANE_FILE);
+        store.start_trial().unwrap();
+        let mut state = store.load().unwrap();
+        state.policy_rules.clear();
+        store.save(&state).unwrap();
+
+        fs::write(&path, "{ not json").unwrap();
+        assert!(!store.load().unwrap().policy_rules.is_empty());
+        assert!(store
+            .evaluate_action(request("operator", "runtime.start", "agent", "local"))
+            .is_ok());
+        let incidents = crate::recovery::list_recovery_incidents(tmp.path()).unwrap();
+        assert_eq!(incidents.len(), 1);
+        assert_eq!(
+            incidents[0].restored_from,
+            crate::recovery::RecoverySource::Backup
+        );
+        assert!(Path::new(&incidents[0].quarantined_to).exists());
+
+        fs::remove_file(backup_path(&path)).unwrap();
+        fs::write(&path, "{ also not json").unwrap();
+        assert!(!store.load().unwrap().policy_rules.is_empty());
+        let incidents = crate::recovery::list_recovery_incidents(tmp.path()).unwrap();
+        assert_eq!(
+            incidents[0].restored_from,
+            crate::recovery::RecoverySource::Defaults
+        );
+    }
+
+    #[test]
+    fn receipt_journal_tolerates_only_a_torn_last_line() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path())
+            .with_decision_cache(std::time::Duration::from_secs(30));
+        let journal = tmp
+            .path()
+            .join(CONTROL_PLANE_FILE)
+            .with_extension(RECEIPT_JOURNAL_EXTENSION);
+        let evaluate = || {
+            store
+                .evaluate_action(request("operator", "runtime.start", "agent", "local"))
+                .unwrap()
+        };
+        evaluate();
+        evaluate();
+
+        let mut body = fs::read_to_string(&journal).unwrap();
+        body.push_str("{\"id\":\"torn");
+        fs::write(&journal, &body).unwrap();
+        assert_eq!(store.list_receipts(10).unwrap().len(), 2);
+
+        evaluate();
+        assert_eq!(store.list_receipts(10).unwrap().len(), 3);
+        assert!(!fs::read_to_string(&journal).unwrap().contains("torn"));
+
+        let body = fs::read_to_string(&journal).unwrap();
+        fs::write(&journal, format!("not json\n{body}")).unwrap();
+        assert!(store.load().is_err());
     }
 }
@@ -0,0 +1,601 @@
+use crate::control_plane::{
+    ActionReceipt, ControlPlaneBackend, ControlPlaneState, JsonFileControlPlaneBackend,
+    ReceiptQuery,
+};
+use crate::workspace_crypto::WorkspaceCipher;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Transaction};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const CONTROL_PLANE_DB_FILE: &str = "control_plane.sqlite3";
+/// Matches the receipt cap of the JSON backend.
+const MAX_RECEIPTS: i64 = 10_000;
+/// Associated data binding sealed bodies to the column they belong in.
+const META_LABEL: &str = "control_plane_meta";
+const RECEIPT_LABEL: &str = "receipts";
+
+/// Control-plane backend on a sqlite database. Receipts live in their own indexed
+/// table and are appended incrementally; the rest of the state is stored as a
+/// single JSON document.
+#[derive(Debug)]
+pub struct SqliteControlPlaneBackend {
+    path: PathBuf,
+    conn: Mutex<Connection>,
+    cipher: Option<Arc<WorkspaceCipher>>,
+}
+
+impl SqliteControlPlaneBackend {
+    /// Opens (or creates) the workspace database. An existing
+    /// `control_plane.json` is imported the first time the database is created.
+    pub fn for_workspace(workspace_dir: &Path) -> Result<Self> {
+        Self::open(workspace_dir, None)
+    }
+
+    /// Like [`Self::for_workspace`] but seals the state document and every
+    /// receipt body with `cipher`, and imports an encrypted
+    /// `control_plane.json`. The indexed receipt columns (timestamp, actor,
+    /// action and result) stay in clear so queries can use them.
+    pub fn encrypted_for_workspace(
+        workspace_dir: &Path,
+        cipher: Arc<WorkspaceCipher>,
+    ) -> Result<Self> {
+        Self::open(workspace_dir, Some(cipher))
+    }
+
+    fn open(workspace_dir: &Path, cipher: Option<Arc<WorkspaceCipher>>) -> Result<Self> {
+        std::fs::create_dir_all(workspace_dir)
+            .with_context(|| format!("failed to create {}", workspace_dir.display()))?;
+        let path = workspace_dir.join(CONTROL_PLANE_DB_FILE);
+        let conn = Connection::open(&path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             PRAGMA synchronous = NORMAL;
+             CREATE TABLE IF NOT EXISTS control_plane_meta (
+                 id INTEGER PRIMARY KEY CHECK (id = 1),
+                 body TEXT NOT NULL,
+                 updated_at TEXT NOT NULL
+             );
+             CREATE TABLE IF NOT EXISTS receipts (
+                 seq INTEGER PRIMARY KEY AUTOINCREMENT,
+                 id TEXT NOT NULL UNIQUE,
+                 timestamp TEXT NOT NULL,
+                 actor_id TEXT NOT NULL,
+                 action TEXT NOT NULL,
+                 result TEXT NOT NULL,
+                 body TEXT NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS idx_receipts_actor ON receipts(actor_id, seq);
+             CREATE INDEX IF NOT EXISTS idx_receipts_action ON receipts(action, seq);
+             CREATE INDEX IF NOT EXISTS idx_receipts_result ON receipts(result, seq);
+             CREATE INDEX IF NOT EXISTS idx_receipts_timestamp ON receipts(timestamp);",
+        )
+        .context("failed to initialize control plane schema")?;
+
+        let backend = Self {
+            path,
+            conn: Mutex::new(conn),
+            cipher,
+        };
+        backend.import_legacy_json(workspace_dir)?;
+        Ok(backend)
+    }
+
+    fn import_legacy_json(&self, workspace_dir: &Path) -> Result<()> {
+        let has_meta = self
+            .conn
+            .lock()
+            .query_row("SELECT 1 FROM control_plane_meta WHERE id = 1", [], |_| {
+                Ok(())
+            })
+            .optional()
+            .context("failed to inspect control plane meta")?
+            .is_some();
+        if has_meta {
+            return Ok(());
+        }
+
+        let mut legacy = JsonFileControlPlaneBackend::for_workspace(workspace_dir);
+        if let Some(cipher) = &self.cipher {
+            legacy = legacy.with_cipher(Arc::clone(cipher));
+        }
+        if let Some(state) = legacy.read_state()? {
+            self.write_state(&state)?;
+        }
+        Ok(())
+    }
+}
+
+impl ControlPlaneBackend for SqliteControlPlaneBackend {
+    fn backend_name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    fn location(&self) -> &Path {
+        &self.path
+    }
+
+    fn read_state(&self) -> Result<Option<ControlPlaneState>> {
+        let conn = self.conn.lock();
+        let cipher = self.cipher.as_deref();
+        let Some(mut state) = read_meta(&conn, cipher)? else {
+            return Ok(None);
+        };
+        let mut stmt = conn
+            .prepare("SELECT body FROM receipts ORDER BY seq DESC")
+            .context("failed to prepare receipts query")?;
+        state.receipts =
+            collect_receipts(stmt.query_map([], |row| row.get::<_, String>(0))?, cipher)?;
+        Ok(Some(state))
+    }
+
+    fn read_recent_state(&self, since: DateTime<Utc>) -> Result<Option<ControlPlaneState>> {
+        let conn = self.conn.lock();
+        let cipher = self.cipher.as_deref();
+        let Some(mut state) = read_meta(&conn, cipher)? else {
+            return Ok(None);
+        };
+        let mut stmt = conn
+            .prepare("SELECT body FROM receipts WHERE timestamp > ?1 ORDER BY seq DESC")
+            .context("failed to prepare receipts query")?;
+        state.receipts = collect_receipts(
+            stmt.query_map(params![since.to_rfc3339()], |row| row.get::<_, String>(0))?,
+            cipher,
+        )?;
+        Ok(Some(state))
+    }
+
+    fn write_recent_state(&self, state: &ControlPlaneState) -> Result<()> {
+        let mut conn = self.conn.lock();
+        let tx = conn
+            .transaction()
+            .context("failed to begin control plane transaction")?;
+        let cipher = self.cipher.as_deref();
+        write_meta(&tx, state, cipher)?;
+        for receipt in state.receipts.iter().rev() {
+            insert_receipt(&tx, receipt, cipher)?;
+        }
+        tx.execute(
+            "DELETE FROM receipts WHERE seq <= (SELECT MAX(seq) FROM receipts) - ?1",
+            params![MAX_RECEIPTS],
+        )
+        .context("failed to trim receipts")?;
+        tx.commit()
+            .context("failed to commit control plane transaction")?;
+        Ok(())
+    }
+
+    fn write_state(&self, state: &ControlPlaneState) -> Result<()> {
+        let mut conn = self.conn.lock();
+        let tx = conn
+            .transaction()
+            .context("failed to begin control plane transaction")?;
+        let cipher = self.cipher.as_deref();
+        write_meta(&tx, state, cipher)?;
+        sync_receipts(&tx, &state.receipts, cipher)?;
+        tx.commit()
+            .context("failed to commit control plane transaction")?;
+        Ok(())
+    }
+
+    fn append_receipts(&self, receipts: &[ActionReceipt]) -> Result<()> {
+        let mut conn = self.conn.lock();
+        let tx = conn
+            .transaction()
+            .context("failed to begin receipts transaction")?;
+        for receipt in receipts {
+            insert_receipt(&tx, receipt, self.cipher.as_deref())?;
+        }
+        tx.commit().context("failed to commit receipts")
+    }
+
+    fn query_receipts(&self, query: &ReceiptQuery) -> Result<Vec<ActionReceipt>> {
+        let mut clauses = Vec::new();
+        let mut values = Vec::new();
+        if let Some(actor_id) = &query.actor_id {
+            clauses.push("actor_id = ?");
+            values.push(actor_id.clone());
+        }
+        if let Some(action) = &query.action {
+            clauses.push("action = ?");
+            values.push(action.clone());
+        }
+        if let Some(result) = &query.result {
+            clauses.push("result = ?");
+            values.push(result_label(result)?);
+        }
+
+        let where_clause = if clauses.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", clauses.join(" AND "))
+        };
+        let sql = format!(
+            "SELECT body FROM receipts {where_clause} ORDER BY seq DESC LIMIT {}",
+            query.capped_limit()
+        );
+
+        let conn = self.conn.lock();
+        let mut stmt = conn
+            .prepare(&sql)
+            .context("failed to prepare receipts query")?;
+        let rows = stmt.query_map(params_from_iter(values.iter()), |row| {
+            row.get::<_, String>(0)
+        })?;
+        collect_receipts(rows, self.cipher.as_deref())
+    }
+}
+
+/// The state document without its receipts.
+fn read_meta(
+    conn: &Connection,
+    cipher: Option<&WorkspaceCipher>,
+) -> Result<Option<ControlPlaneState>> {
+    let Some(body) = conn
+        .query_row(
+            "SELECT body FROM control_plane_meta WHERE id = 1",
+            [],
+            |row| row.get::<_, String>(0),
+        )
+        .optional()
+        .context("failed to read control plane meta")?
+    else {
+        return Ok(None);
+    };
+
+    let body = open_body(cipher, META_LABEL, body)?;
+    let mut meta: Value =
+        serde_json::from_str(&body).context("failed to parse control plane state")?;
+    if let Some(object) = meta.as_object_mut() {
+        object.insert("receipts".into(), Value::Array(Vec::new()));
+    }
+    serde_json::from_value(meta)
+        .map(Some)
+        .context("failed to parse control plane state")
+}
+
+fn write_meta(
+    tx: &Transaction<'_>,
+    state: &ControlPlaneState,
+    cipher: Option<&WorkspaceCipher>,
+) -> Result<()> {
+    let mut meta =
+        serde_json::to_value(state).context("failed to serialize control plane state")?;
+    if let Some(object) = meta.as_object_mut() {
+        object.remove("receipts");
+    }
+    tx.execute(
+        "INSERT INTO control_plane_meta (id, body, updated_at) VALUES (1, ?1, ?2)
+         ON CONFLICT(id) DO UPDATE SET body = excluded.body, updated_at = excluded.updated_at",
+        params![
+            seal_body(cipher, META_LABEL, meta.to_string())?,
+            Utc::now().to_rfc3339()
+        ],
+    )
+    .context("failed to write control plane meta")?;
+    Ok(())
+}
+
+/// Brings the receipts table in line with `receipts` (newest first). The
+/// common case — a few new receipts at the head — only inserts those rows;
+/// retention purges and truncation delete from the tail.
+fn sync_receipts(
+    tx: &Transaction<'_>,
+    receipts: &[ActionReceipt],
+    cipher: Option<&WorkspaceCipher>,
+) -> Result<()> {
+    let mut new_count = receipts.len();
+    for (idx, receipt) in receipts.iter().enumerate() {
+        let known = tx
+            .query_row(
+                "SELECT 1 FROM receipts WHERE id = ?1",
+                params![receipt.id],
+                |_| Ok(()),
+            )
+            .optional()
+            .context("failed to look up receipt")?
+            .is_some();
+        if known {
+            new_count = idx;
+            break;
+        }
+    }
+
+    for receipt in receipts[..new_count].iter().rev() {
+        insert_receipt(tx, receipt, cipher)?;
+    }
+
+    let stored = count_receipts(tx)?;
+    if stored > receipts.len() {
+        match receipts.last() {
+            Some(oldest) => {
+                tx.execute(
+                    "DELETE FROM receipts WHERE seq < (SELECT seq FROM receipts WHERE id = ?1)",
+                    params![oldest.id],
+                )
+                .context("failed to trim receipts")?;
+            }
+            None => {
+                tx.execute("DELETE FROM receipts", [])
+                    .context("failed to clear receipts")?;
+            }
+        }
+    }
+
+    if count_receipts(tx)? != receipts.len() {
+        tx.execute("DELETE FROM receipts", [])
+            .context("failed to clear receipts")?;
+        for receipt in receipts.iter().rev() {
+            insert_receipt(tx, receipt, cipher)?;
+        }
+    }
+    Ok(())
+}
+
+fn insert_receipt(
+    tx: &Transaction<'_>,
+    receipt: &ActionReceipt,
+    cipher: Option<&WorkspaceCipher>,
+) -> Result<()> {
+    let body = serde_json::to_string(receipt).context("failed to serialize receipt")?;
+    let body = seal_body(cipher, RECEIPT_LABEL, body)?;
+    tx.execute(
+        "INSERT OR IGNORE INTO receipts (id, timestamp, actor_id, action, result, body)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            receipt.id,
+            receipt.timestamp,
+            receipt.actor_id,
+            receipt.action,
+            result_label(&receipt.result)?,
+            body
+        ],
+    )
+    .context("failed to insert receipt")?;
+    Ok(())
+}
+
+fn count_receipts(tx: &Transaction<'_>) -> Result<usize> {
+    let count: i64 = tx
+        .query_row("SELECT COUNT(*) FROM receipts", [], |row| row.get(0))
+        .context("failed to count receipts")?;
+    Ok(usize::try_from(count).unwrap_or(0))
+}
+
+fn result_label(result: &crate::control_plane::ReceiptResult) -> Result<String> {
+    match serde_json::to_value(result).context("failed to serialize receipt result")? {
+        Value::String(label) => Ok(label),
+        other => anyhow::bail!("unexpected receipt result encoding: {other}"),
+    }
+}
+
+fn collect_receipts(
+    rows: impl Iterator<Item = rusqlite::Result<String>>,
+    cipher: Option<&WorkspaceCipher>,
+) -> Result<Vec<ActionReceipt>> {
+    rows.map(|row| {
+        let body = open_body(
+            cipher,
+            RECEIPT_LABEL,
+            row.context("failed to read receipt row")?,
+        )?;
+        serde_json::from_str(&body).context("failed to parse receipt")
+    })
+    .collect()
+}
+
+fn seal_body(cipher: Option<&WorkspaceCipher>, label: &str, body: String) -> Result<String> {
+    match cipher {
+        Some(cipher) => cipher.seal(label, body.as_bytes()),
+        None => Ok(body),
+    }
+}
+
+fn open_body(cipher: Option<&WorkspaceCipher>, label: &str, body: String) -> Result<String> {
+    match cipher {
+        Some(cipher) => cipher.open_to_string(label, body),
+        None if WorkspaceCipher::is_sealed(&body) => {
+            anyhow::bail!("control plane database is encrypted; open it with the workspace key")
+        }
+        None => Ok(body),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control_plane::{ActionPolicyRequest, ControlPlaneStore, ReceiptResult};
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn request(actor_id: &str, action: &str) -> ActionPolicyRequest {
+        ActionPolicyRequest {
+            actor_id: actor_id.into(),
+            actor_role: "operator".into(),
+            action: action.into(),
+            resource: "runtime".into(),
+            destination: "local".into(),
+            approval_id: None,
+            occurred_at: None,
+            authenticated_at: None,
+            on_behalf_of: None,
+            context: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn sqlite_store_appends_and_queries_receipts() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::sqlite_for_workspace(tmp.path()).unwrap();
+        assert_eq!(store.backend_name(), "sqlite");
+
+        store
+            .evaluate_action(request("operator-a", "runtime.start"))
+            .unwrap();
+        store
+            .evaluate_action(request("operator-b", "runtime.stop"))
+            .unwrap();
+        store
+            .evaluate_action(request("operator-a", "secrets.read"))
+            .unwrap();
+
+        let all = store.list_receipts(10).unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].action, "secrets.read");
+
+        let by_actor = store
+            .query_receipts(&ReceiptQuery {
+                actor_id: Some("operator-a".into()),
+                limit: 10,
+                ..ReceiptQuery::default()
+            })
+            .unwrap();
+        assert_eq!(by_actor.len(), 2);
+
+        let denied = store
+            .query_receipts(&ReceiptQuery {
+                result: Some(ReceiptResult::Denied),
+                limit: 10,
+                ..ReceiptQuery::default()
+            })
+            .unwrap();
+        assert_eq!(denied.len(), 1);
+        assert_eq!(denied[0].action, "secrets.read");
+    }
+
+    #[test]
+    fn sqlite_store_applies_retention_purges() {
+        let tmp = TempDir::new().unwrap();
+        let backend = Arc::new(SqliteControlPlaneBackend::for_workspace(tmp.path()).unwrap());
+        let store = ControlPlaneStore::with_backend(backend.clone());
+
+        let mut state = store.load().unwrap();
+        state.receipts.push(ActionReceipt {
+            id: "expired-receipt".into(),
+            timestamp: (Utc::now() - chrono::Duration::days(90)).to_rfc3339(),
+            actor_id: "operator-a".into(),
+            actor_role: "operator".into(),
+            action: "runtime.start".into(),
+            resource: "runtime".into(),
+            destination: "local".into(),
+            result: ReceiptResult::Allowed,
+            reason: "policy allowed".into(),
+            on_behalf_of: None,
+            context: BTreeMap::new(),
+            undo: None,
+            policy_revision: None,
+        });
+        backend.write_state(&state).unwrap();
+
+        store
+            .evaluate_action(request("operator-a", "runtime.stop"))
+            .unwrap();
+        let summary = store.purge_by_retention().unwrap();
+
+        assert_eq!(summary.removed_receipts, 1);
+        let remaining = store.list_receipts(10).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].action, "runtime.stop");
+    }
+
+    #[test]
+    fn sqlite_policy_path_loads_only_recent_receipts() {
+        let tmp = TempDir::new().unwrap();
+        let backend = Arc::new(SqliteControlPlaneBackend::for_workspace(tmp.path()).unwrap());
+        let store = ControlPlaneStore::with_backend(backend.clone());
+
+        let mut state = store.load().unwrap();
+        state.receipts.push(ActionReceipt {
+            id: "old-receipt".into(),
+            timestamp: (Utc::now() - chrono::Duration::hours(3)).to_rfc3339(),
+            actor_id: "operator-a".into(),
+            actor_role: "operator".into(),
+            action: "runtime.start".into(),
+            resource: "runtime".into(),
+            destination: "local".into(),
+            result: ReceiptResult::Allowed,
+            reason: "policy allowed".into(),
+            on_behalf_of: None,
+            context: BTreeMap::new(),
+            undo: None,
+            policy_revision: None,
+        });
+        backend.write_state(&state).unwrap();
+
+        store
+            .evaluate_action(request("operator-a", "runtime.start"))
+            .unwrap();
+        let recent = backend
+            .read_recent_state(Utc::now() - chrono::Duration::hours(1))
+            .unwrap()
+            .unwrap();
+        assert_eq!(recent.receipts.len(), 1);
+        assert_eq!(store.list_receipts(10).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn sqlite_store_imports_existing_json_state() {
+        let tmp = TempDir::new().unwrap();
+        let json_store = ControlPlaneStore::for_workspace(tmp.path());
+        json_store
+            .evaluate_action(request("operator-a", "runtime.start"))
+            .unwrap();
+
+        let store = ControlPlaneStore::sqlite_for_workspace(tmp.path()).unwrap();
+        assert_eq!(store.list_receipts(10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn encrypted_sqlite_store_seals_bodies_and_imports_encrypted_json() {
+        let tmp = TempDir::new().unwrap();
+        let vault = crate::secrets::EncryptedFileSecretVault::new(tmp.path().join("secrets"), true)
+            .unwrap();
+        let workspace = tmp.path().join("workspace");
+        let cipher = Arc::new(WorkspaceCipher::from_vault(&vault, "profile-a").unwrap());
+        let json = ControlPlaneStore::with_backend(Arc::new(
+            JsonFileControlPlaneBackend::for_workspace(&workspace).with_cipher(Arc::clone(&cipher)),
+        ));
+        json.evaluate_action(request("operator-a", "runtime.start"))
+            .unwrap();
+
+        let backend =
+            SqliteControlPlaneBackend::encrypted_for_workspace(&workspace, cipher).unwrap();
+        let store = ControlPlaneStore::with_backend(Arc::new(backend));
+        store
+            .evaluate_action(request("operator-b", "runtime.stop"))
+            .unwrap();
+        let by_actor = store
+            .query_receipts(&ReceiptQuery {
+                actor_id: Some("operator-a".into()),
+                limit: 10,
+                ..ReceiptQuery::default()
+            })
+            .unwrap();
+        assert_eq!(by_actor.len(), 1);
+        assert!(!store.load().unwrap().policy_rules.is_empty());
+        drop(store);
+
+        let conn = Connection::open(workspace.join(CONTROL_PLANE_DB_FILE)).unwrap();
+        let meta: String = conn
+            .query_row("SELECT body FROM control_plane_meta", [], |row| row.get(0))
+            .unwrap();
+        assert!(WorkspaceCipher::is_sealed(&meta));
+        let bodies: Vec<String> = conn
+            .prepare("SELECT body FROM receipts")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(bodies.len(), 2);
+        assert!(bodies.iter().all(|body| WorkspaceCipher::is_sealed(body)));
+
+        let plain = SqliteControlPlaneBackend::for_workspace(&workspace).unwrap();
+        assert!(plain.read_state().is_err());
+    }
+}
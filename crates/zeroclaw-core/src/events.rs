@@ -33,6 +33,40 @@ pub enum RuntimeEventKind {
         from: String,
         to: String,
     },
+    SecurityAlert {
+        category: String,
+        subject: String,
+        message: String,
+    },
+    ApprovalRequested {
+        approval_id: String,
+        actor_id: String,
+        action: String,
+        resource: String,
+    },
+    ApprovalResolved {
+        approval_id: String,
+        approved: bool,
+        decided_by: String,
+    },
+    ConfigReloaded {
+        applied: Vec<String>,
+        requires_restart: Vec<String>,
+    },
+    ReadinessChecked {
+        ready: bool,
+        failed: Vec<String>,
+        summary: String,
+    },
+    /// One tool call (including delegate dispatches) made while handling a
+    /// task, in the shape the wrapper appends to its audit chain.
+    ToolCall {
+        task_id: String,
+        action: String,
+        resource: String,
+        success: bool,
+        duration_ms: u64,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -56,7 +90,7 @@ impl RuntimeEvent {
     }
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct EventBus {
     tx: broadcast::Sender<RuntimeEvent>,
 }
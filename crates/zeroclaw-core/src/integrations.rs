@@ -1,7 +1,7 @@
-use anyhow::{Context, Result};
+use crate::recovery::{read_json_or_recover, write_json_with_backup};
+use anyhow::Result;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use std::fs;
 use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -39,28 +39,11 @@ impl IntegrationRegistryStore {
     }
 
     pub fn load(&self) -> Result<IntegrationRegistry> {
-        if !self.path.exists() {
-            return Ok(IntegrationRegistry::default());
-        }
-
-        let body = fs::read_to_string(&self.path)
-            .with_context(|| format!("failed to read {}", self.path.display()))?;
-        serde_json::from_str(&body).context("failed to parse integration registry")
+        read_json_or_recover(&self.path, "integration registry")
     }
 
     pub fn save(&self, registry: &IntegrationRegistry) -> Result<()> {
-        if let Some(parent) = self.path.parent() {
-            fs::create_dir_all(parent)
-                .with_context(|| format!("failed to create {}", parent.display()))?;
-        }
-
-        let body = serde_json::to_string_pretty(registry)
-            .context("failed to serialize integration registry")?;
-        let tmp = self.path.with_extension("json.tmp");
-        fs::write(&tmp, body).with_context(|| format!("failed to write {}", tmp.display()))?;
-        fs::rename(&tmp, &self.path)
-            .with_context(|| format!("failed to replace {}", self.path.display()))?;
-        Ok(())
+        write_json_with_backup(&self.path, registry, "integration registry")
     }
 
     pub fn install(&self, contract: IntegrationPermissionContract) -> Result<IntegrationRecord> {
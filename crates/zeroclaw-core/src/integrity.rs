@@ -0,0 +1,253 @@
+use crate::events::{EventBus, RuntimeEvent, RuntimeEventKind};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const INTEGRITY_MANIFEST_FILE: &str = "integrity_manifest.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FileChecksum {
+    pub sha256: String,
+    pub size: u64,
+    pub recorded_at: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IntegrityManifest {
+    #[serde(default)]
+    pub files: BTreeMap<String, FileChecksum>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IntegrityViolation {
+    pub file: String,
+    pub expected_sha256: String,
+    /// `None` when the file was deleted out of band.
+    pub actual_sha256: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IntegrityReport {
+    pub checked_at: String,
+    pub verified: Vec<String>,
+    pub violations: Vec<IntegrityViolation>,
+    /// Files present on disk that have never been recorded in the manifest.
+    pub untracked: Vec<String>,
+}
+
+impl IntegrityReport {
+    pub fn is_clean(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Keeps a checksum manifest of critical workspace state files so edits made
+/// outside the app (by hand, by another process, by malware) are noticed.
+///
+/// Stores call [`IntegrityMonitor::record`] after each legitimate write; any
+/// later mismatch between the manifest and the file on disk is tampering.
+#[derive(Debug, Clone)]
+pub struct IntegrityMonitor {
+    workspace_dir: PathBuf,
+    manifest_path: PathBuf,
+    tracked: Vec<String>,
+}
+
+impl IntegrityMonitor {
+    pub fn for_workspace(workspace_dir: &Path, tracked: &[&str]) -> Self {
+        Self {
+            workspace_dir: workspace_dir.to_path_buf(),
+            manifest_path: workspace_dir.join(INTEGRITY_MANIFEST_FILE),
+            tracked: tracked.iter().map(|name| (*name).to_string()).collect(),
+        }
+    }
+
+    pub fn load_manifest(&self) -> Result<IntegrityManifest> {
+        if !self.manifest_path.exists() {
+            return Ok(IntegrityManifest::default());
+        }
+
+        let body = fs::read_to_string(&self.manifest_path)
+            .with_context(|| format!("failed to read {}", self.manifest_path.display()))?;
+        serde_json::from_str(&body).context("failed to parse integrity manifest")
+    }
+
+    fn save_manifest(&self, manifest: &IntegrityManifest) -> Result<()> {
+        fs::create_dir_all(&self.workspace_dir)
+            .with_context(|| format!("failed to create {}", self.workspace_dir.display()))?;
+        let body = serde_json::to_string_pretty(manifest)
+            .context("failed to serialize integrity manifest")?;
+        let tmp = self.manifest_path.with_extension("json.tmp");
+        fs::write(&tmp, body).with_context(|| format!("failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, &self.manifest_path)
+            .with_context(|| format!("failed to replace {}", self.manifest_path.display()))?;
+        Ok(())
+    }
+
+    /// Records the current checksum of `file_name` as trusted.
+    pub fn record(&self, file_name: &str) -> Result<()> {
+        let mut manifest = self.load_manifest()?;
+        let path = self.workspace_dir.join(file_name);
+        match checksum(&path)? {
+            Some((sha256, size)) => {
+                manifest.files.insert(
+                    file_name.to_string(),
+                    FileChecksum {
+                        sha256,
+                        size,
+                        recorded_at: Utc::now().to_rfc3339(),
+                    },
+                );
+            }
+            None => {
+                manifest.files.remove(file_name);
+            }
+        }
+        self.save_manifest(&manifest)
+    }
+
+    /// Trusts the current contents of every tracked file. Used on first run
+    /// and after an operator has reviewed and accepted a reported change.
+    pub fn record_all(&self) -> Result<()> {
+        for name in &self.tracked {
+            self.record(name)?;
+        }
+        Ok(())
+    }
+
+    pub fn verify(&self) -> Result<IntegrityReport> {
+        let manifest = self.load_manifest()?;
+        let mut report = IntegrityReport {
+            checked_at: Utc::now().to_rfc3339(),
+            ..IntegrityReport::default()
+        };
+
+        for name in &self.tracked {
+            let actual = checksum(&self.workspace_dir.join(name))?;
+            match (manifest.files.get(name), actual) {
+                (Some(expected), Some((sha256, _))) if expected.sha256 == sha256 => {
+                    report.verified.push(name.clone());
+                }
+                (Some(expected), actual) => report.violations.push(IntegrityViolation {
+                    file: name.clone(),
+                    expected_sha256: expected.sha256.clone(),
+                    actual_sha256: actual.map(|(sha256, _)| sha256),
+                }),
+                (None, Some(_)) => report.untracked.push(name.clone()),
+                (None, None) => {}
+            }
+        }
+        Ok(report)
+    }
+
+    /// Verifies the workspace and publishes a security alert for every
+    /// tampered file.
+    pub fn verify_and_publish(&self, bus: &EventBus, profile_id: &str) -> Result<IntegrityReport> {
+        let report = self.verify()?;
+        for violation in &report.violations {
+            let message = if violation.actual_sha256.is_some() {
+                "file changed outside the app"
+            } else {
+                "file deleted outside the app"
+            };
+            bus.publish(RuntimeEvent::new(
+                profile_id,
+                RuntimeEventKind::SecurityAlert {
+                    category: "integrity".into(),
+                    subject: violation.file.clone(),
+                    message: message.into(),
+                },
+            ));
+        }
+        Ok(report)
+    }
+
+    /// Re-verifies the workspace on a fixed interval until the returned task
+    /// is aborted.
+    pub fn spawn_periodic(
+        self,
+        bus: EventBus,
+        profile_id: String,
+        every: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                ticker.tick().await;
+                if let Err(error) = self.verify_and_publish(&bus, &profile_id) {
+                    bus.publish(RuntimeEvent::new(
+                        &profile_id,
+                        RuntimeEventKind::Error {
+                            component: "integrity".into(),
+                            message: error.to_string(),
+                        },
+                    ));
+                }
+            }
+        })
+    }
+}
+
+fn checksum(path: &Path) -> Result<Option<(String, u64)>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let bytes = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let digest = Sha256::digest(&bytes);
+    Ok(Some((format!("{digest:x}"), bytes.len() as u64)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn out_of_band_edits_raise_security_alerts() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("control_plane.json"), "{}").unwrap();
+        fs::write(tmp.path().join("rbac.json"), "[]").unwrap();
+
+        let monitor =
+            IntegrityMonitor::for_workspace(tmp.path(), &["control_plane.json", "rbac.json"]);
+        monitor.record_all().unwrap();
+        assert!(monitor.verify().unwrap().is_clean());
+
+        fs::write(tmp.path().join("control_plane.json"), r#"{"receipts":[]}"#).unwrap();
+        fs::remove_file(tmp.path().join("rbac.json")).unwrap();
+
+        let bus = EventBus::new(16);
+        let mut sub = bus.subscribe();
+        let report = monitor.verify_and_publish(&bus, "profile-a").unwrap();
+        assert_eq!(report.violations.len(), 2);
+        assert!(report.violations[1].actual_sha256.is_none());
+
+        let event = sub.recv().await.unwrap();
+        assert!(matches!(
+            event.kind,
+            RuntimeEventKind::SecurityAlert { ref subject, .. } if subject == "control_plane.json"
+        ));
+
+        monitor.record_all().unwrap();
+        assert!(monitor.verify().unwrap().is_clean());
+    }
+
+    #[test]
+    fn store_writes_keep_manifest_in_sync() {
+        let tmp = TempDir::new().unwrap();
+        let monitor = IntegrityMonitor::for_workspace(tmp.path(), &["control_plane.json"]);
+        let store = crate::control_plane::ControlPlaneStore::for_workspace(tmp.path())
+            .with_integrity_monitor(monitor.clone());
+
+        store.set_retention(30, 30).unwrap();
+        assert!(monitor.verify().unwrap().is_clean());
+
+        fs::write(tmp.path().join("control_plane.json"), "{}").unwrap();
+        assert!(!monitor.verify().unwrap().is_clean());
+    }
+}
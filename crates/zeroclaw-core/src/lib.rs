@@ -8,39 +8,67 @@
     clippy::too_many_lines
 )]
 
+pub mod audit_timeline;
 pub mod background;
 pub mod control_plane;
+#[cfg(feature = "control-plane-sqlite")]
+pub mod control_plane_sqlite;
 pub mod events;
 pub mod integrations;
+pub mod integrity;
 pub mod lifecycle;
 pub mod logs;
 pub mod mcp;
+pub mod metrics;
 pub mod pairing_mode;
 pub mod profiles;
 pub mod protocol;
+pub mod provider_regions;
+pub mod readiness;
+pub mod recovery;
 pub mod runtime;
+pub mod secret_refs;
 pub mod secrets;
+#[cfg(feature = "external-vaults")]
+pub mod secrets_external;
 pub mod skills;
+pub mod tags;
+pub mod tool_grants;
+pub mod workspace_crypto;
 
+pub use audit_timeline::{
+    actor_activity, unified_audit_view, ActorActivity, AuditSource, AuditTimelineEntry,
+};
 pub use background::{
     AndroidBackgroundAdapter, BackgroundCapabilities, DesktopBackgroundAdapter,
     IosBackgroundAdapter, PlatformBackground,
 };
 pub use control_plane::{
     AccessPlan, AccessState, ActionPolicyDecision, ActionPolicyRequest, ActionReceipt,
-    ApprovalRequest, ApprovalStatus, ControlPlaneState, ControlPlaneStore, PolicyRule,
-    PurgeSummary, ReceiptResult, RetentionPolicy, WorkspaceView,
+    AllowedHours, ApprovalRequest, ApprovalStatus, ApproverIdentity, AuditVerbosity,
+    AuditVerbosityPolicy, BatchPolicyDecision, BreakGlassGrant, BreakGlassStatus, CompactionReport,
+    ControlPlaneBackend, ControlPlaneState, ControlPlaneStateAt, ControlPlaneStore,
+    ImpersonationGrant, IssuedMachineToken, JsonFileControlPlaneBackend, MachineToken,
+    OffboardSummary, PolicyRevision, PolicyRule, PolicySimulation, PurgeSummary, ReauthPolicy,
+    ReceiptMetadata, ReceiptQuery, ReceiptResult, RetentionPolicy, RoleGrant, StagedPolicy,
+    StagedPolicyImpact, TrialPhase, TrialStatus, UndoPlan, WorkspaceView,
 };
+#[cfg(feature = "control-plane-sqlite")]
+pub use control_plane_sqlite::SqliteControlPlaneBackend;
 pub use events::{EventBus, RuntimeEvent, RuntimeEventKind};
 pub use integrations::{
     IntegrationPermissionContract, IntegrationRecord, IntegrationRegistry, IntegrationRegistryStore,
 };
+pub use integrity::{
+    FileChecksum, IntegrityManifest, IntegrityMonitor, IntegrityReport, IntegrityViolation,
+};
 pub use lifecycle::{AgentState, LifecycleController, LifecycleSnapshot};
-pub use logs::{JsonlLogSink, LogLine, LogSink, LogSinkConfig};
+pub use logs::{purge_logs_older_than, JsonlLogSink, LogLine, LogSink, LogSinkConfig};
 pub use mcp::{
     McpConnectorConfig, McpConnectorInstallRequest, McpConnectorRecord, McpConnectorRegistry,
     McpConnectorStore,
 };
+pub use metrics::{CommandMetrics, CommandMetricsEntry};
 pub use pairing_mode::{
     create_pairing_bundle, PairingBundle, PairingRequest, PairingTransport, SnapshotSyncMode,
 };
@@ -49,9 +77,33 @@ pub use protocol::{
     protocol_handshake, ProtocolHandshake, CONFIG_SCHEMA_VERSION, CORE_PROTOCOL_VERSION,
     EVENT_SCHEMA_VERSION,
 };
+pub use provider_regions::{
+    effective_provider_region, provider_endpoint, provider_region_context,
+    provider_region_violations, ProviderRegionViolation, PROVIDER_ENDPOINT_CONTEXT_KEY,
+    PROVIDER_REGION_CONTEXT_KEY,
+};
+pub use readiness::{run_self_test, ReadinessCheck, ReadinessReport};
+pub use recovery::{list_recovery_incidents, RecoveryIncident, RecoverySource};
 pub use runtime::{
-    AgentRuntime, AgentSession, AgentSessionFactory, LocalAgentRuntime, RuntimeStartConfig,
-    ZeroclawAgentSessionFactory,
+    AgentRuntime, AgentSession, AgentSessionFactory, ConfigReloadReport, DrainReport, InFlightTask,
+    LocalAgentRuntime, RuntimeStartConfig, ToolCallRecord, ZeroclawAgentSessionFactory,
+    TOOL_CALL_ACTION,
+};
+pub use secret_refs::{migrate_plaintext_secrets, resolve_secret_refs, SECRET_REF_PREFIX};
+pub use secrets::{
+    AdaptiveSecretVault, EncryptedFileSecretVault, InventoriedSecretVault, KeyringSecretVault,
+    SecretInventory, SecretMetadata, SecretRotationStatus, SecretVault, SecretsInventoryExport,
+    VaultExportBundle,
+};
+#[cfg(feature = "external-vaults")]
+pub use secrets_external::{
+    open_external_vault, AwsSecretsManagerSecretVault, ExternalVaultConfig,
+    HashicorpVaultSecretVault, OnePasswordConnectSecretVault,
 };
-pub use secrets::{AdaptiveSecretVault, EncryptedFileSecretVault, KeyringSecretVault, SecretVault};
 pub use skills::{SkillInstallRequest, SkillRecord, SkillsRegistry, SkillsRegistryStore};
+pub use tags::{TagAssignment, TagIndex, TagStore, TaggedEntityKind};
+pub use tool_grants::{
+    GrantCategory, GrantOwnerKind, ToolGrant, ToolGrantCheck, ToolGrantRegistry, ToolGrantRequest,
+    ToolGrantStore,
+};
+pub use workspace_crypto::{EncryptionMigrationReport, WorkspaceCipher, WORKSPACE_DATA_KEY_ID};
@@ -1,5 +1,5 @@
 use anyhow::{Context, Result};
-use chrono::{Datelike, Utc};
+use chrono::{Datelike, NaiveDate, Utc};
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -243,6 +243,28 @@ fn open_append(path: &Path) -> Result<File> {
         .with_context(|| format!("failed to open log file {}", path.display()))
 }
 
+/// Deletes rotated log files whose day is more than `days` days old and
+/// returns how many were removed. Files that do not follow the
+/// `agent-YYYY-MM-DD-NNN.jsonl` naming are left alone.
+pub fn purge_logs_older_than(dir: &Path, days: u32) -> Result<usize> {
+    let cutoff = (Utc::now() - chrono::Duration::days(i64::from(days))).date_naive();
+    let mut removed = 0;
+    for path in list_log_files(dir)? {
+        let day = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("agent-"))
+            .and_then(|rest| rest.get(..10))
+            .and_then(|raw| NaiveDate::parse_from_str(raw, "%Y-%m-%d").ok());
+        if day.is_some_and(|day| day < cutoff) {
+            fs::remove_file(&path)
+                .with_context(|| format!("failed to remove {}", path.display()))?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
 fn current_day() -> String {
     let now = Utc::now();
     format!("{:04}-{:02}-{:02}", now.year(), now.month(), now.day())
@@ -369,4 +391,16 @@ mod tests {
         assert!(body.contains("[REDACTED]"));
         assert!(!body.contains("sk-real-key"));
     }
+
+    #[test]
+    fn purges_logs_past_retention() {
+        let tmp = TempDir::new().unwrap();
+        let sink = JsonlLogSink::new(LogSinkConfig::new(tmp.path().to_path_buf())).unwrap();
+        sink.write(&LogLine::new("info", "agent", "today")).unwrap();
+        fs::write(tmp.path().join("agent-2020-01-01-000.jsonl"), "{}\n").unwrap();
+        fs::write(tmp.path().join("notes.jsonl"), "{}\n").unwrap();
+
+        assert_eq!(purge_logs_older_than(tmp.path(), 14).unwrap(), 1);
+        assert_eq!(list_log_files(tmp.path()).unwrap().len(), 2);
+    }
 }
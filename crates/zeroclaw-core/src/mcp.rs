@@ -1,8 +1,8 @@
 use crate::integrations::IntegrationPermissionContract;
-use anyhow::{Context, Result};
+use crate::recovery::{read_json_or_recover, write_json_with_backup};
+use anyhow::Result;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use std::fs;
 use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -58,28 +58,11 @@ impl McpConnectorStore {
     }
 
     pub fn load(&self) -> Result<McpConnectorRegistry> {
-        if !self.path.exists() {
-            return Ok(McpConnectorRegistry::default());
-        }
-
-        let body = fs::read_to_string(&self.path)
-            .with_context(|| format!("failed to read {}", self.path.display()))?;
-        serde_json::from_str(&body).context("failed to parse mcp connector registry")
+        read_json_or_recover(&self.path, "mcp connector registry")
     }
 
     fn save(&self, registry: &McpConnectorRegistry) -> Result<()> {
-        if let Some(parent) = self.path.parent() {
-            fs::create_dir_all(parent)
-                .with_context(|| format!("failed to create {}", parent.display()))?;
-        }
-
-        let body = serde_json::to_string_pretty(registry)
-            .context("failed to serialize mcp connector registry")?;
-        let tmp = self.path.with_extension("json.tmp");
-        fs::write(&tmp, body).with_context(|| format!("failed to write {}", tmp.display()))?;
-        fs::rename(&tmp, &self.path)
-            .with_context(|| format!("failed to replace {}", self.path.display()))?;
-        Ok(())
+        write_json_with_backup(&self.path, registry, "mcp connector registry")
     }
 
     pub fn install(&self, request: McpConnectorInstallRequest) -> Result<McpConnectorRecord> {
@@ -0,0 +1,168 @@
+use crate::logs::{LogLine, LogSink};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Default)]
+struct CommandStat {
+    count: u64,
+    errors: u64,
+    total: Duration,
+    max: Duration,
+    last: Duration,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CommandMetricsEntry {
+    pub command: String,
+    pub count: u64,
+    pub errors: u64,
+    pub error_rate: f64,
+    pub avg_ms: f64,
+    pub max_ms: u64,
+    pub last_ms: u64,
+}
+
+/// Per-command execution counters for the shell's invoke handlers. Calls
+/// slower than the threshold are also written to the log sink as warnings.
+#[derive(Clone)]
+pub struct CommandMetrics {
+    stats: Arc<Mutex<BTreeMap<String, CommandStat>>>,
+    slow_threshold: Duration,
+    sink: Option<Arc<dyn LogSink>>,
+}
+
+impl CommandMetrics {
+    pub fn new(slow_threshold: Duration) -> Self {
+        Self {
+            stats: Arc::new(Mutex::new(BTreeMap::new())),
+            slow_threshold,
+            sink: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_log_sink(mut self, sink: Arc<dyn LogSink>) -> Self {
+        self.sink = Some(sink);
+        self
+    }
+
+    pub fn record(&self, command: &str, elapsed: Duration, success: bool) {
+        {
+            let mut stats = self.stats.lock();
+            let stat = stats.entry(command.to_string()).or_default();
+            stat.count += 1;
+            if !success {
+                stat.errors += 1;
+            }
+            stat.total += elapsed;
+            stat.max = stat.max.max(elapsed);
+            stat.last = elapsed;
+        }
+
+        if elapsed >= self.slow_threshold {
+            if let Some(sink) = &self.sink {
+                let mut line = LogLine::new("warn", "commands", format!("slow command {command}"));
+                line.fields
+                    .insert("command".into(), Value::String(command.to_string()));
+                line.fields
+                    .insert("duration_ms".into(), Value::from(duration_ms(elapsed)));
+                line.fields.insert("success".into(), Value::Bool(success));
+                let _ = sink.write(&line);
+            }
+        }
+    }
+
+    /// Runs a handler future and records its duration and outcome.
+    pub async fn observe<T, E, F>(&self, command: &str, handler: F) -> Result<T, E>
+    where
+        F: Future<Output = Result<T, E>>,
+    {
+        let started = Instant::now();
+        let result = handler.await;
+        self.record(command, started.elapsed(), result.is_ok());
+        result
+    }
+
+    pub fn snapshot(&self) -> Vec<CommandMetricsEntry> {
+        self.stats
+            .lock()
+            .iter()
+            .map(|(command, stat)| {
+                #[allow(clippy::cast_precision_loss)]
+                let (error_rate, avg_ms) = if stat.count == 0 {
+                    (0.0, 0.0)
+                } else {
+                    (
+                        stat.errors as f64 / stat.count as f64,
+                        stat.total.as_secs_f64() * 1000.0 / stat.count as f64,
+                    )
+                };
+                CommandMetricsEntry {
+                    command: command.clone(),
+                    count: stat.count,
+                    errors: stat.errors,
+                    error_rate,
+                    avg_ms,
+                    max_ms: duration_ms(stat.max),
+                    last_ms: duration_ms(stat.last),
+                }
+            })
+            .collect()
+    }
+
+    pub fn reset(&self) {
+        self.stats.lock().clear();
+    }
+}
+
+impl Default for CommandMetrics {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(500))
+    }
+}
+
+fn duration_ms(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logs::{JsonlLogSink, LogSinkConfig};
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn records_counts_errors_and_slow_calls() {
+        let tmp = TempDir::new().unwrap();
+        let sink: Arc<dyn LogSink> =
+            Arc::new(JsonlLogSink::new(LogSinkConfig::new(tmp.path().to_path_buf())).unwrap());
+        let metrics =
+            CommandMetrics::new(Duration::from_millis(20)).with_log_sink(Arc::clone(&sink));
+
+        let ok: Result<u8, String> = metrics.observe("runtime_status", async { Ok(1) }).await;
+        assert!(ok.is_ok());
+        let failed: Result<u8, String> = metrics
+            .observe("runtime_status", async { Err("boom".to_string()) })
+            .await;
+        assert!(failed.is_err());
+        metrics.record("logs_export", Duration::from_millis(50), true);
+
+        let snapshot = metrics.snapshot();
+        let status = snapshot
+            .iter()
+            .find(|entry| entry.command == "runtime_status")
+            .unwrap();
+        assert_eq!(status.count, 2);
+        assert_eq!(status.errors, 1);
+        assert!((status.error_rate - 0.5).abs() < f64::EPSILON);
+
+        let lines = sink.tail(10).unwrap();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].message, "slow command logs_export");
+    }
+}
@@ -0,0 +1,280 @@
+use crate::control_plane::ActionPolicyRequest;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Context key carrying the effective provider region on provider-bound
+/// action requests, and therefore on their receipts.
+pub const PROVIDER_REGION_CONTEXT_KEY: &str = "provider_region";
+
+/// Context key carrying the provider endpoint the call goes to. The control
+/// plane derives the enforced region from it, so a caller cannot move a call
+/// into an allowed region by relabelling it.
+pub const PROVIDER_ENDPOINT_CONTEXT_KEY: &str = "provider_endpoint";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProviderRegionViolation {
+    /// `default_provider` or `agents.<name>`.
+    pub target: String,
+    pub provider: String,
+    pub endpoint: String,
+    pub region: Option<String>,
+    pub reason: String,
+}
+
+/// Region pinned for the default provider (`agent = None`) or for a delegate
+/// agent, which falls back to the default provider's pin.
+pub fn effective_provider_region(config: &zeroclaw::Config, agent: Option<&str>) -> Option<String> {
+    agent
+        .and_then(|name| config.agents.get(name))
+        .and_then(|agent| agent.region.clone())
+        .or_else(|| config.provider_region.clone())
+}
+
+/// Endpoint the default provider (`agent = None`) or a delegate agent talks
+/// to: the host of the configured `api_url` for the default provider, and
+/// otherwise the provider id itself.
+pub fn provider_endpoint(config: &zeroclaw::Config, agent: Option<&str>) -> String {
+    if let Some(agent) = agent.and_then(|name| config.agents.get(name)) {
+        return provider_endpoint_key(&agent.provider);
+    }
+    match config.api_url.as_deref().map(str::trim) {
+        Some(url) if !url.is_empty() => provider_endpoint_key(url),
+        _ => provider_endpoint_key(config.default_provider.as_deref().unwrap_or("openrouter")),
+    }
+}
+
+/// Request context for a provider-bound action, so the receipt records the
+/// endpoint the call goes to and the region it was pinned to.
+pub fn provider_region_context(
+    config: &zeroclaw::Config,
+    agent: Option<&str>,
+) -> BTreeMap<String, Value> {
+    let mut context = BTreeMap::new();
+    if let Some(agent) = agent {
+        context.insert("agent".into(), Value::String(agent.to_string()));
+    }
+    context.insert(
+        PROVIDER_ENDPOINT_CONTEXT_KEY.into(),
+        Value::String(provider_endpoint(config, agent)),
+    );
+    if let Some(region) = effective_provider_region(config, agent) {
+        context.insert(PROVIDER_REGION_CONTEXT_KEY.into(), Value::String(region));
+    }
+    context
+}
+
+/// Checks the default provider and every delegate agent against the
+/// workspace's allowed regions, using the region registered for each
+/// endpoint. An empty allow-list means no restriction.
+pub fn provider_region_violations(
+    config: &zeroclaw::Config,
+    allowed_regions: &[String],
+    endpoint_regions: &BTreeMap<String, String>,
+) -> Vec<ProviderRegionViolation> {
+    if allowed_regions.is_empty() {
+        return Vec::new();
+    }
+
+    let mut targets = vec![(
+        "default_provider".to_string(),
+        config
+            .default_provider
+            .clone()
+            .unwrap_or_else(|| "openrouter".into()),
+        None,
+    )];
+    let mut agents: Vec<_> = config.agents.iter().collect();
+    agents.sort_by(|a, b| a.0.cmp(b.0));
+    for (name, agent) in agents {
+        targets.push((
+            format!("agents.{name}"),
+            agent.provider.clone(),
+            Some(name.as_str()),
+        ));
+    }
+
+    targets
+        .into_iter()
+        .filter_map(|(target, provider, agent)| {
+            let endpoint = provider_endpoint(config, agent);
+            let region = effective_provider_region(config, agent);
+            let reason = region_denial(
+                allowed_regions,
+                endpoint_regions,
+                Some(&endpoint),
+                region.as_deref(),
+            )?;
+            Some(ProviderRegionViolation {
+                target,
+                provider,
+                endpoint,
+                region,
+                reason,
+            })
+        })
+        .collect()
+}
+
+/// Denial reason for a provider-bound request whose endpoint has no allowed
+/// region, or whose claimed region disagrees with its endpoint.
+pub(crate) fn provider_request_denial(
+    allowed_regions: &[String],
+    endpoint_regions: &BTreeMap<String, String>,
+    request: &ActionPolicyRequest,
+) -> Option<String> {
+    if allowed_regions.is_empty() || request.destination != "provider" {
+        return None;
+    }
+    let endpoint = request
+        .context
+        .get(PROVIDER_ENDPOINT_CONTEXT_KEY)
+        .and_then(Value::as_str)
+        .map(provider_endpoint_key);
+    let claimed = request
+        .context
+        .get(PROVIDER_REGION_CONTEXT_KEY)
+        .and_then(Value::as_str);
+    region_denial(
+        allowed_regions,
+        endpoint_regions,
+        endpoint.as_deref(),
+        claimed,
+    )
+}
+
+fn region_denial(
+    allowed_regions: &[String],
+    endpoint_regions: &BTreeMap<String, String>,
+    endpoint: Option<&str>,
+    claimed: Option<&str>,
+) -> Option<String> {
+    let Some(endpoint) = endpoint else {
+        return Some("provider endpoint is not recorded".into());
+    };
+    let Some(region) = endpoint_regions.get(endpoint) else {
+        return Some(format!(
+            "provider endpoint '{endpoint}' has no registered region"
+        ));
+    };
+    if let Some(claimed) = claimed.filter(|claimed| claimed != region) {
+        return Some(format!(
+            "provider region '{claimed}' does not match endpoint '{endpoint}' in '{region}'"
+        ));
+    }
+    if allowed_regions.iter().any(|allowed| allowed == region) {
+        None
+    } else {
+        Some(format!("provider region '{region}' is not allowed"))
+    }
+}
+
+/// Normalizes a provider URL or id to the key used in the endpoint registry:
+/// the lowercased host for URLs, the trimmed id otherwise.
+pub(crate) fn provider_endpoint_key(raw: &str) -> String {
+    let raw = raw.trim();
+    let Some((_, rest)) = raw.split_once("://") else {
+        return raw.to_ascii_lowercase();
+    };
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority.rsplit('@').next().unwrap_or_default();
+    let host = match host.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next().unwrap_or_default(),
+        None => host.split(':').next().unwrap_or_default(),
+    };
+    host.to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control_plane::tests::request;
+    use crate::control_plane::{ControlPlaneStore, ReceiptResult};
+    use tempfile::TempDir;
+    use zeroclaw::config::DelegateAgentConfig;
+
+    fn agent(region: &str) -> DelegateAgentConfig {
+        toml::from_str(&format!(
+            "provider = \"openrouter\"\nmodel = \"model-test\"\n{region}"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn pinned_regions_are_enforced_and_recorded() {
+        let tmp = TempDir::new().unwrap();
+        let store = ControlPlaneStore::for_workspace(tmp.path());
+        store
+            .set_allowed_provider_regions(vec![" EU ".into()])
+            .unwrap();
+        let allowed = store.load().unwrap().allowed_provider_regions;
+        assert_eq!(allowed, vec!["eu".to_string()]);
+
+        let endpoints = store
+            .set_provider_endpoint_regions(BTreeMap::from([
+                ("OpenRouter".into(), "eu".into()),
+                ("https://api.us.example.com:8443/v1".into(), "us".into()),
+            ]))
+            .unwrap();
+        assert_eq!(endpoints["api.us.example.com"], "us");
+
+        let mut config = zeroclaw::Config {
+            provider_region: Some("eu".into()),
+            ..zeroclaw::Config::default()
+        };
+        config.agents.insert("researcher".into(), agent(""));
+        config
+            .agents
+            .insert("coder".into(), agent("region = \"us\""));
+
+        let violations = provider_region_violations(&config, &allowed, &endpoints);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].target, "agents.coder");
+        assert_eq!(violations[0].endpoint, "openrouter");
+
+        let evaluate = |context: BTreeMap<String, Value>| {
+            store
+                .evaluate_action(ActionPolicyRequest {
+                    context,
+                    ..request("owner", "agent.delegate", "agent", "provider")
+                })
+                .unwrap()
+        };
+        assert!(evaluate(provider_region_context(&config, Some("researcher"))).allowed);
+        let denied = evaluate(provider_region_context(&config, Some("coder")));
+        assert!(!denied.allowed);
+        assert_eq!(
+            denied.reason,
+            "provider region 'us' does not match endpoint 'openrouter' in 'eu'"
+        );
+
+        config.api_url = Some("https://api.us.example.com/v1".into());
+        let relabelled = evaluate(provider_region_context(&config, None));
+        assert_eq!(
+            relabelled.reason,
+            "provider region 'eu' does not match endpoint 'api.us.example.com' in 'us'"
+        );
+        config.provider_region = None;
+        let derived = evaluate(provider_region_context(&config, None));
+        assert_eq!(derived.reason, "provider region 'us' is not allowed");
+        let unrecorded = evaluate(BTreeMap::from([(
+            PROVIDER_REGION_CONTEXT_KEY.to_string(),
+            Value::String("eu".into()),
+        )]));
+        assert_eq!(unrecorded.reason, "provider endpoint is not recorded");
+
+        let receipts = store.load().unwrap().receipts;
+        let allowed = receipts
+            .iter()
+            .find(|receipt| receipt.result == ReceiptResult::Allowed)
+            .unwrap();
+        assert_eq!(
+            allowed.context.get(PROVIDER_REGION_CONTEXT_KEY),
+            Some(&Value::String("eu".into()))
+        );
+        assert_eq!(
+            allowed.context.get(PROVIDER_ENDPOINT_CONTEXT_KEY),
+            Some(&Value::String("openrouter".into()))
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use crate::protocol::{protocol_handshake, ProtocolHandshake};
+use crate::secrets::SecretVault;
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+const READINESS_PROBE_FILE: &str = ".readiness-probe";
+const READINESS_VAULT_PROFILE: &str = "__readiness";
+
+/// Allowed drift, in seconds, between the clock and the newest workspace file
+/// before the clock is considered to have gone backwards.
+const CLOCK_SKEW_TOLERANCE_SECS: i64 = 300;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReadinessCheck {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReadinessReport {
+    pub ready: bool,
+    pub checked_at: String,
+    pub checks: Vec<ReadinessCheck>,
+}
+
+impl ReadinessReport {
+    pub fn failed(&self) -> Vec<String> {
+        self.checks
+            .iter()
+            .filter(|check| !check.passed)
+            .map(|check| check.name.clone())
+            .collect()
+    }
+
+    /// One line naming every failed check, e.g. for a "not ready" banner.
+    pub fn summary(&self) -> String {
+        if self.ready {
+            return "ready".into();
+        }
+        let reasons: Vec<String> = self
+            .checks
+            .iter()
+            .filter(|check| !check.passed)
+            .map(|check| format!("{}: {}", check.name, check.detail))
+            .collect();
+        format!("not ready: {}", reasons.join("; "))
+    }
+}
+
+/// Startup self-test. The vault and protocol checks only run when the caller
+/// supplies a vault or the handshake it was built against.
+pub fn run_self_test(
+    workspace_dir: &Path,
+    config_path: &Path,
+    vault: Option<&dyn SecretVault>,
+    expected_protocol: Option<&ProtocolHandshake>,
+) -> ReadinessReport {
+    let mut checks = vec![
+        check("workspace_writable", workspace_writable(workspace_dir)),
+        check("config_parses", config_parses(config_path)),
+        check("clock_sane", clock_sane(workspace_dir, Utc::now())),
+    ];
+    if let Some(vault) = vault {
+        checks.push(check("vault_accessible", vault_accessible(vault)));
+    }
+    if let Some(expected) = expected_protocol {
+        checks.push(check("protocol_consistent", protocol_consistent(expected)));
+    }
+
+    ReadinessReport {
+        ready: checks.iter().all(|check| check.passed),
+        checked_at: Utc::now().to_rfc3339(),
+        checks,
+    }
+}
+
+fn check(name: &str, outcome: Result<String, String>) -> ReadinessCheck {
+    let (passed, detail) = match outcome {
+        Ok(detail) => (true, detail),
+        Err(detail) => (false, detail),
+    };
+    ReadinessCheck {
+        name: name.into(),
+        passed,
+        detail,
+    }
+}
+
+fn workspace_writable(workspace_dir: &Path) -> Result<String, String> {
+    let probe = workspace_dir.join(READINESS_PROBE_FILE);
+    fs::create_dir_all(workspace_dir)
+        .and_then(|()| fs::write(&probe, b"ok"))
+        .and_then(|()| fs::remove_file(&probe))
+        .map(|()| workspace_dir.display().to_string())
+        .map_err(|error| format!("{}: {error}", workspace_dir.display()))
+}
+
+fn config_parses(config_path: &Path) -> Result<String, String> {
+    if !config_path.exists() {
+        return Ok("no config file; defaults apply".into());
+    }
+    let body = fs::read_to_string(config_path)
+        .map_err(|error| format!("{}: {error}", config_path.display()))?;
+    toml::from_str::<zeroclaw::Config>(&body)
+        .map(|_| config_path.display().to_string())
+        .map_err(|error| format!("{}: {error}", config_path.display()))
+}
+
+fn clock_sane(workspace_dir: &Path, now: DateTime<Utc>) -> Result<String, String> {
+    let floor = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    if now < floor {
+        return Err(format!("system clock reads {}", now.to_rfc3339()));
+    }
+
+    let newest = fs::read_dir(workspace_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok()?.metadata().ok()?.modified().ok())
+        .map(DateTime::<Utc>::from)
+        .max();
+    match newest {
+        Some(newest) if (newest - now).num_seconds() > CLOCK_SKEW_TOLERANCE_SECS => Err(format!(
+            "system clock ({}) is behind workspace files written at {}",
+            now.to_rfc3339(),
+            newest.to_rfc3339()
+        )),
+        _ => Ok(now.to_rfc3339()),
+    }
+}
+
+/// Looks up a key that normally does not exist, so the check never writes to
+/// the vault: a missing secret is a successful read.
+fn vault_accessible(vault: &dyn SecretVault) -> Result<String, String> {
+    vault
+        .get_secret(READINESS_VAULT_PROFILE, "probe")
+        .map(|_| vault.backend_name().to_string())
+        .map_err(|error| format!("{}: {error}", vault.backend_name()))
+}
+
+fn protocol_consistent(expected: &ProtocolHandshake) -> Result<String, String> {
+    let actual = protocol_handshake();
+    if &actual == expected {
+        Ok(actual.core_protocol_version)
+    } else {
+        Err(format!(
+            "core speaks protocol {} (events v{}, config v{}), caller expects {} (events v{}, config v{})",
+            actual.core_protocol_version,
+            actual.event_schema_version,
+            actual.config_schema_version,
+            expected.core_protocol_version,
+            expected.event_schema_version,
+            expected.config_schema_version
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::EncryptedFileSecretVault;
+    use anyhow::Result;
+    use tempfile::TempDir;
+
+    struct ReadOnlyVault;
+
+    impl SecretVault for ReadOnlyVault {
+        fn backend_name(&self) -> &'static str {
+            "read-only"
+        }
+
+        fn set_secret(&self, _profile_id: &str, _key: &str, _value: &str) -> Result<()> {
+            anyhow::bail!("read-only vault")
+        }
+
+        fn get_secret(&self, _profile_id: &str, _key: &str) -> Result<Option<String>> {
+            Ok(None)
+        }
+
+        fn delete_secret(&self, _profile_id: &str, _key: &str) -> Result<()> {
+            anyhow::bail!("read-only vault")
+        }
+    }
+
+    #[test]
+    fn self_test_names_each_failed_check() {
+        let tmp = TempDir::new().unwrap();
+        let workspace = tmp.path().join("workspace");
+        let config_path = workspace.join("config.toml");
+        let vault = EncryptedFileSecretVault::new(tmp.path().join("vault"), true).unwrap();
+
+        let report = run_self_test(
+            &workspace,
+            &config_path,
+            Some(&vault),
+            Some(&protocol_handshake()),
+        );
+        assert!(report.ready, "{}", report.summary());
+        assert_eq!(report.checks.len(), 5);
+
+        fs::write(&config_path, "default_temperature = \"hot\"").unwrap();
+        let mut stale = protocol_handshake();
+        stale.event_schema_version += 1;
+        let report = run_self_test(&workspace, &config_path, None, Some(&stale));
+        assert!(!report.ready);
+        assert_eq!(
+            report.failed(),
+            vec!["config_parses", "protocol_consistent"]
+        );
+        assert!(report.summary().starts_with("not ready: config_parses: "));
+
+        fs::remove_file(&config_path).unwrap();
+        let report = run_self_test(&workspace, &config_path, Some(&ReadOnlyVault), None);
+        assert!(report.ready, "{}", report.summary());
+        fs::write(tmp.path().join("vault").join("vault.json"), "not json").unwrap();
+        let report = run_self_test(&workspace, &config_path, Some(&vault), None);
+        assert_eq!(report.failed(), vec!["vault_accessible"]);
+    }
+}
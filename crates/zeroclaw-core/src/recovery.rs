@@ -0,0 +1,194 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub const RECOVERY_LOG_FILE: &str = "state_recovery.jsonl";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoverySource {
+    Backup,
+    Defaults,
+}
+
+/// A state file that failed to parse and was replaced automatically.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecoveryIncident {
+    pub occurred_at: String,
+    pub file: String,
+    pub error: String,
+    /// Where the unreadable file was moved, kept for inspection.
+    pub quarantined_to: String,
+    pub restored_from: RecoverySource,
+}
+
+/// Reads a JSON state file. A file that fails to parse is moved aside as
+/// `<name>.corrupt-<timestamp>`, replaced by its `.bak` copy when that still
+/// parses (defaults otherwise), and the incident is appended to
+/// `state_recovery.jsonl` beside it.
+pub fn read_json_or_recover<T>(path: &Path, label: &str) -> Result<T>
+where
+    T: DeserializeOwned + Serialize + Default,
+{
+    if !path.exists() {
+        return Ok(T::default());
+    }
+
+    let body =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let error = match serde_json::from_str(&body) {
+        Ok(value) => return Ok(value),
+        Err(error) => error,
+    };
+
+    let backup = quarantine_corrupt(path, label, &error, |backup| {
+        fs::read_to_string(backup)
+            .ok()
+            .and_then(|body| serde_json::from_str::<T>(&body).ok())
+    })?;
+    let value = backup.unwrap_or_default();
+    write_json_with_backup(path, &value, label)?;
+    Ok(value)
+}
+
+/// Moves a state file that failed to parse aside as
+/// `<name>.corrupt-<timestamp>` and logs the incident. Returns the `.bak` copy
+/// as parsed by `read_backup`; the caller writes it back, or defaults when
+/// there is none.
+pub(crate) fn quarantine_corrupt<T>(
+    path: &Path,
+    label: &str,
+    error: &dyn std::fmt::Display,
+    read_backup: impl FnOnce(&Path) -> Option<T>,
+) -> Result<Option<T>> {
+    let quarantined_to = sibling(
+        path,
+        &format!("corrupt-{}", Utc::now().format("%Y%m%dT%H%M%S%.3fZ")),
+    );
+    fs::rename(path, &quarantined_to)
+        .with_context(|| format!("failed to quarantine {}", path.display()))?;
+
+    let backup = read_backup(&backup_path(path));
+    let restored_from = if backup.is_some() {
+        RecoverySource::Backup
+    } else {
+        RecoverySource::Defaults
+    };
+    let incident = RecoveryIncident {
+        occurred_at: Utc::now().to_rfc3339(),
+        file: path.display().to_string(),
+        error: format!("failed to parse {label}: {error}"),
+        quarantined_to: quarantined_to.display().to_string(),
+        restored_from,
+    };
+    tracing::warn!(
+        file = %incident.file,
+        quarantined_to = %incident.quarantined_to,
+        restored_from = ?incident.restored_from,
+        "recovered corrupt {label}: {error}"
+    );
+    if let Some(parent) = path.parent() {
+        append_incident(&parent.join(RECOVERY_LOG_FILE), &incident)?;
+    }
+    Ok(backup)
+}
+
+/// Writes `value` as pretty JSON through a temp file, keeping the previous
+/// version as `<name>.bak` for [`read_json_or_recover`].
+pub fn write_json_with_backup<T: Serialize>(path: &Path, value: &T, label: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+
+    let body = serde_json::to_string_pretty(value)
+        .with_context(|| format!("failed to serialize {label}"))?;
+    if path.exists() {
+        fs::copy(path, backup_path(path))
+            .with_context(|| format!("failed to back up {}", path.display()))?;
+    }
+    let tmp = sibling(path, "tmp");
+    fs::write(&tmp, body).with_context(|| format!("failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, path).with_context(|| format!("failed to replace {}", path.display()))?;
+    Ok(())
+}
+
+/// Incidents recorded in `dir`, newest first.
+pub fn list_recovery_incidents(dir: &Path) -> Result<Vec<RecoveryIncident>> {
+    let path = dir.join(RECOVERY_LOG_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let body =
+        fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    let mut incidents: Vec<RecoveryIncident> = body
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    incidents.reverse();
+    Ok(incidents)
+}
+
+fn append_incident(log: &Path, incident: &RecoveryIncident) -> Result<()> {
+    let line = serde_json::to_string(incident).context("failed to serialize recovery incident")?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log)
+        .with_context(|| format!("failed to open {}", log.display()))?;
+    writeln!(file, "{line}").with_context(|| format!("failed to write {}", log.display()))
+}
+
+pub(crate) fn backup_path(path: &Path) -> PathBuf {
+    sibling(path, "bak")
+}
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tags::{TagStore, TaggedEntityKind};
+    use tempfile::TempDir;
+
+    #[test]
+    fn corrupt_state_is_quarantined_and_restored_from_backup() {
+        let tmp = TempDir::new().unwrap();
+        let store = TagStore::for_workspace(tmp.path());
+        store
+            .add_tags(TaggedEntityKind::Skill, "invoice-parser", &["q3".into()])
+            .unwrap();
+        store
+            .add_tags(TaggedEntityKind::Skill, "invoice-parser", &["audit".into()])
+            .unwrap();
+
+        fs::write(tmp.path().join("tags.json"), "{ not json").unwrap();
+        let tags = store
+            .tags_for(TaggedEntityKind::Skill, "invoice-parser")
+            .unwrap();
+        assert!(tags.contains("q3"));
+        assert!(!tags.contains("audit"));
+
+        let incidents = list_recovery_incidents(tmp.path()).unwrap();
+        assert_eq!(incidents.len(), 1);
+        assert_eq!(incidents[0].restored_from, RecoverySource::Backup);
+        assert!(Path::new(&incidents[0].quarantined_to).exists());
+
+        fs::remove_file(tmp.path().join("tags.json.bak")).unwrap();
+        fs::write(tmp.path().join("tags.json"), "{ also not json").unwrap();
+        assert!(store.load().unwrap().assignments.is_empty());
+        let incidents = list_recovery_incidents(tmp.path()).unwrap();
+        assert_eq!(incidents.len(), 2);
+        assert_eq!(incidents[0].restored_from, RecoverySource::Defaults);
+    }
+}
@@ -1,13 +1,28 @@
 use crate::events::{EventBus, RuntimeEvent, RuntimeEventKind};
 use crate::lifecycle::{AgentState, LifecycleController};
 use crate::logs::{LogLine, LogSink};
+use crate::protocol::ProtocolHandshake;
+use crate::readiness::{run_self_test, ReadinessReport};
+use crate::secret_refs::resolve_secret_refs;
+use crate::secrets::SecretVault;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::{broadcast, oneshot, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{broadcast, oneshot, Mutex, Notify};
+use zeroclaw::observability::traits::ObserverMetric;
+use zeroclaw::observability::{Observer, ObserverEvent};
+
+const DRAIN_REPORT_FILE: &str = "runtime_drain.json";
+
+/// Audit action recorded for every tool the agent invokes.
+pub const TOOL_CALL_ACTION: &str = "runtime.tool_call";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RuntimeStartConfig {
@@ -16,6 +31,40 @@ pub struct RuntimeStartConfig {
     pub workspace_dir: PathBuf,
 }
 
+/// Config keys that a running session picks up on its next turn. Any other
+/// change only takes effect after a restart.
+const LIVE_CONFIG_KEYS: &[&str] = &[
+    "default_temperature",
+    "agent.max_history_messages",
+    "agent.max_tool_iterations",
+    "agent.parallel_tools",
+    "skills.prompt_injection_mode",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ConfigReloadReport {
+    pub applied: Vec<String>,
+    pub requires_restart: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InFlightTask {
+    pub task_id: String,
+    pub message: String,
+    pub started_at: String,
+}
+
+/// Outcome of [`LocalAgentRuntime::drain_and_stop`], also written to the
+/// workspace so interrupted work can be resubmitted after maintenance.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DrainReport {
+    pub reason: String,
+    pub started_at: String,
+    pub finished_at: String,
+    pub completed: Vec<String>,
+    pub interrupted: Vec<InFlightTask>,
+}
+
 #[async_trait]
 pub trait AgentRuntime: Send + Sync {
     async fn start(&self, config: RuntimeStartConfig) -> Result<()>;
@@ -28,6 +77,58 @@ pub trait AgentRuntime: Send + Sync {
 #[async_trait]
 pub trait AgentSession: Send + Sync {
     async fn run_message(&mut self, message: &str) -> Result<String>;
+
+    /// Applies the live subset of `config` (see `LIVE_CONFIG_KEYS`) to the
+    /// running session.
+    fn apply_config(&mut self, _config: &zeroclaw::Config) -> Result<()> {
+        Ok(())
+    }
+
+    /// Tool calls made since the last call, oldest first.
+    fn take_tool_calls(&mut self) -> Vec<ToolCallRecord> {
+        Vec::new()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolCallRecord {
+    pub tool: String,
+    pub success: bool,
+    pub duration_ms: u64,
+}
+
+/// Observer attached to the zeroclaw agent that buffers completed tool calls
+/// until the runtime turns them into events.
+#[derive(Default)]
+struct ToolCallRecorder {
+    calls: parking_lot::Mutex<Vec<ToolCallRecord>>,
+}
+
+impl Observer for ToolCallRecorder {
+    fn record_event(&self, event: &ObserverEvent) {
+        if let ObserverEvent::ToolCall {
+            tool,
+            duration,
+            success,
+        } = event
+        {
+            self.calls.lock().push(ToolCallRecord {
+                tool: tool.clone(),
+                success: *success,
+                duration_ms: u64::try_from(duration.as_millis()).unwrap_or(u64::MAX),
+            });
+        }
+    }
+
+    fn record_metric(&self, _metric: &ObserverMetric) {}
+
+    fn name(&self) -> &'static str {
+        "zeroclaw-core-tool-calls"
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
 }
 
 pub trait AgentSessionFactory: Send + Sync {
@@ -36,6 +137,7 @@ pub trait AgentSessionFactory: Send + Sync {
 
 pub struct ZeroclawAgentSession {
     inner: zeroclaw::agent::Agent,
+    tool_calls: Arc<ToolCallRecorder>,
 }
 
 #[async_trait]
@@ -43,15 +145,32 @@ impl AgentSession for ZeroclawAgentSession {
     async fn run_message(&mut self, message: &str) -> Result<String> {
         self.inner.run_single(message).await
     }
+
+    fn apply_config(&mut self, config: &zeroclaw::Config) -> Result<()> {
+        self.inner.set_temperature(config.default_temperature);
+        self.inner.set_agent_config(config.agent.clone());
+        self.inner
+            .set_skills_prompt_mode(config.skills.prompt_injection_mode);
+        Ok(())
+    }
+
+    fn take_tool_calls(&mut self) -> Vec<ToolCallRecord> {
+        std::mem::take(&mut *self.tool_calls.calls.lock())
+    }
 }
 
 pub struct ZeroclawAgentSessionFactory;
 
 impl AgentSessionFactory for ZeroclawAgentSessionFactory {
     fn create_session(&self, config: &zeroclaw::Config) -> Result<Box<dyn AgentSession>> {
-        let agent = zeroclaw::agent::Agent::from_config(config)
+        let mut agent = zeroclaw::agent::Agent::from_config(config)
             .context("failed to create zeroclaw agent session")?;
-        Ok(Box::new(ZeroclawAgentSession { inner: agent }))
+        let tool_calls = Arc::new(ToolCallRecorder::default());
+        agent.add_observer(tool_calls.clone());
+        Ok(Box::new(ZeroclawAgentSession {
+            inner: agent,
+            tool_calls,
+        }))
     }
 }
 
@@ -60,6 +179,8 @@ struct RuntimeInner {
     session: Option<Box<dyn AgentSession>>,
     health_shutdown: Option<oneshot::Sender<()>>,
     health_task: Option<tokio::task::JoinHandle<()>>,
+    start_config: Option<RuntimeStartConfig>,
+    active_config: Option<zeroclaw::Config>,
 }
 
 impl RuntimeInner {
@@ -69,6 +190,8 @@ impl RuntimeInner {
             session: None,
             health_shutdown: None,
             health_task: None,
+            start_config: None,
+            active_config: None,
         }
     }
 }
@@ -79,6 +202,12 @@ pub struct LocalAgentRuntime {
     log_sink: Arc<dyn LogSink>,
     factory: Arc<dyn AgentSessionFactory>,
     inner: Mutex<RuntimeInner>,
+    draining: AtomicBool,
+    in_flight: parking_lot::Mutex<BTreeMap<String, InFlightTask>>,
+    interrupt: Notify,
+    readiness: parking_lot::Mutex<Option<ReadinessReport>>,
+    secret_vault: Option<Arc<dyn SecretVault>>,
+    expected_protocol: Option<ProtocolHandshake>,
 }
 
 impl LocalAgentRuntime {
@@ -93,13 +222,59 @@ impl LocalAgentRuntime {
             log_sink,
             factory,
             inner: Mutex::new(RuntimeInner::new()),
+            draining: AtomicBool::new(false),
+            in_flight: parking_lot::Mutex::new(BTreeMap::new()),
+            interrupt: Notify::new(),
+            readiness: parking_lot::Mutex::new(None),
+            secret_vault: None,
+            expected_protocol: None,
         }
     }
 
+    /// Vault that `secret://` references in the profile config resolve from.
+    #[must_use]
+    pub fn with_secret_vault(mut self, vault: Arc<dyn SecretVault>) -> Self {
+        self.secret_vault = Some(vault);
+        self
+    }
+
+    /// Protocol handshake the embedding app was built against; `start`
+    /// refuses to run when the core speaks a different one.
+    #[must_use]
+    pub fn with_expected_protocol(mut self, handshake: ProtocolHandshake) -> Self {
+        self.expected_protocol = Some(handshake);
+        self
+    }
+
+    /// Result of the self-test run by the last `start`.
+    pub fn readiness(&self) -> Option<ReadinessReport> {
+        self.readiness.lock().clone()
+    }
+
     fn publish(&self, event: RuntimeEvent) {
         self.event_bus.publish(event);
     }
 
+    fn publish_tool_call(&self, profile_id: &str, task_id: &str, call: ToolCallRecord) {
+        let outcome = if call.success { "succeeded" } else { "failed" };
+        self.write_log(
+            profile_id,
+            "info",
+            "agent",
+            &format!("tool {} {outcome} in {}ms", call.tool, call.duration_ms),
+        );
+        self.publish(RuntimeEvent::new(
+            profile_id,
+            RuntimeEventKind::ToolCall {
+                task_id: task_id.to_string(),
+                action: TOOL_CALL_ACTION.to_string(),
+                resource: call.tool,
+                success: call.success,
+                duration_ms: call.duration_ms,
+            },
+        ));
+    }
+
     fn write_log(&self, profile_id: &str, level: &str, component: &str, message: &str) {
         let mut line = LogLine::new(level, component, message);
         line.fields.insert(
@@ -120,6 +295,157 @@ impl LocalAgentRuntime {
         ));
     }
 
+    /// Stops accepting new messages, gives in-flight tasks up to `timeout` to
+    /// finish, interrupts whatever is still running and then stops the
+    /// runtime. The report is persisted next to the workspace state.
+    pub async fn drain_and_stop(&self, reason: &str, timeout: Duration) -> Result<DrainReport> {
+        let started_at = Utc::now().to_rfc3339();
+        self.draining.store(true, Ordering::SeqCst);
+        let initial: Vec<String> = self.in_flight.lock().keys().cloned().collect();
+
+        let deadline = Instant::now() + timeout;
+        while !self.in_flight.lock().is_empty() && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        let interrupted: Vec<InFlightTask> = self.in_flight.lock().values().cloned().collect();
+        if !interrupted.is_empty() {
+            self.interrupt.notify_waiters();
+        }
+        let completed = initial
+            .into_iter()
+            .filter(|task_id| !interrupted.iter().any(|task| &task.task_id == task_id))
+            .collect();
+
+        let workspace_dir = {
+            let guard = self.inner.lock().await;
+            guard
+                .start_config
+                .as_ref()
+                .map(|config| config.workspace_dir.clone())
+        };
+        let result = self.stop(reason).await;
+        self.draining.store(false, Ordering::SeqCst);
+        result?;
+
+        let report = DrainReport {
+            reason: reason.to_string(),
+            started_at,
+            finished_at: Utc::now().to_rfc3339(),
+            completed,
+            interrupted,
+        };
+        if let Some(dir) = workspace_dir {
+            let body = serde_json::to_string_pretty(&report)
+                .context("failed to serialize drain report")?;
+            let path = dir.join(DRAIN_REPORT_FILE);
+            std::fs::write(&path, body)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+        }
+        Ok(report)
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    /// Re-reads the profile config and applies the live subset to the running
+    /// session. Everything else that changed is reported back as needing a
+    /// restart and keeps its old value until then.
+    pub async fn reload_config(&self) -> Result<ConfigReloadReport> {
+        let mut guard = self.inner.lock().await;
+        let (Some(start), Some(active)) = (guard.start_config.clone(), guard.active_config.clone())
+        else {
+            anyhow::bail!("runtime is not running");
+        };
+
+        let next = load_profile_config(
+            &start.config_path,
+            &start.workspace_dir,
+            self.secret_vault.as_deref(),
+            &start.profile_id,
+        )?;
+        let before = serde_json::to_value(&active).context("failed to serialize active config")?;
+        let after = serde_json::to_value(&next).context("failed to serialize profile config")?;
+        let mut changed = Vec::new();
+        changed_config_keys(&before, &after, "", &mut changed);
+
+        let (applied, requires_restart): (Vec<String>, Vec<String>) = changed
+            .into_iter()
+            .partition(|key| LIVE_CONFIG_KEYS.contains(&key.as_str()));
+        let report = ConfigReloadReport {
+            applied,
+            requires_restart,
+        };
+
+        if !report.applied.is_empty() {
+            let mut updated = active;
+            updated.default_temperature = next.default_temperature;
+            updated.agent.max_history_messages = next.agent.max_history_messages;
+            updated.agent.max_tool_iterations = next.agent.max_tool_iterations;
+            updated.agent.parallel_tools = next.agent.parallel_tools;
+            updated.skills.prompt_injection_mode = next.skills.prompt_injection_mode;
+            if let Some(session) = guard.session.as_mut() {
+                session.apply_config(&updated)?;
+            }
+            guard.active_config = Some(updated);
+        }
+        drop(guard);
+
+        if !report.applied.is_empty() || !report.requires_restart.is_empty() {
+            self.publish(RuntimeEvent::new(
+                &start.profile_id,
+                RuntimeEventKind::ConfigReloaded {
+                    applied: report.applied.clone(),
+                    requires_restart: report.requires_restart.clone(),
+                },
+            ));
+            self.write_log(
+                &start.profile_id,
+                "info",
+                "runtime",
+                &format!(
+                    "config reloaded: {} applied, {} pending restart",
+                    report.applied.len(),
+                    report.requires_restart.len()
+                ),
+            );
+        }
+        Ok(report)
+    }
+
+    /// Polls the profile config file and reloads it whenever it changes on
+    /// disk, until the returned task is aborted.
+    pub fn spawn_config_watch(self: Arc<Self>, every: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            let mut last_modified: Option<SystemTime> = None;
+            loop {
+                ticker.tick().await;
+                let path = {
+                    let guard = self.inner.lock().await;
+                    guard
+                        .start_config
+                        .as_ref()
+                        .map(|config| config.config_path.clone())
+                };
+                let Some(path) = path else {
+                    last_modified = None;
+                    continue;
+                };
+                let modified = std::fs::metadata(&path)
+                    .and_then(|meta| meta.modified())
+                    .ok();
+                if last_modified.is_some() && modified != last_modified {
+                    if let Err(error) = self.reload_config().await {
+                        tracing::warn!("config reload failed: {error}");
+                    }
+                }
+                last_modified = modified;
+            }
+        })
+    }
+
     fn transition_state(
         &self,
         profile_id: &str,
@@ -146,6 +472,27 @@ impl AgentRuntime for LocalAgentRuntime {
             anyhow::bail!("runtime is already active");
         }
 
+        let report = run_self_test(
+            &config.workspace_dir,
+            &config.config_path,
+            self.secret_vault.as_deref(),
+            self.expected_protocol.as_ref(),
+        );
+        self.publish(RuntimeEvent::new(
+            &config.profile_id,
+            RuntimeEventKind::ReadinessChecked {
+                ready: report.ready,
+                failed: report.failed(),
+                summary: report.summary(),
+            },
+        ));
+        *self.readiness.lock() = Some(report.clone());
+        if !report.ready {
+            let message = report.summary();
+            self.write_log(&config.profile_id, "error", "runtime", &message);
+            anyhow::bail!(message);
+        }
+
         self.transition_state(&config.profile_id, AgentState::Starting, None)?;
         self.write_log(
             &config.profile_id,
@@ -154,7 +501,12 @@ impl AgentRuntime for LocalAgentRuntime {
             "starting runtime session",
         );
 
-        let loaded = load_profile_config(&config.config_path, &config.workspace_dir)?;
+        let loaded = load_profile_config(
+            &config.config_path,
+            &config.workspace_dir,
+            self.secret_vault.as_deref(),
+            &config.profile_id,
+        )?;
         let session = match self.factory.create_session(&loaded) {
             Ok(session) => session,
             Err(error) => {
@@ -204,6 +556,8 @@ impl AgentRuntime for LocalAgentRuntime {
         inner.session = Some(session);
         inner.health_shutdown = Some(shutdown_tx);
         inner.health_task = Some(handle);
+        inner.start_config = Some(config.clone());
+        inner.active_config = Some(loaded);
         drop(inner);
 
         self.transition_state(&config.profile_id, AgentState::Running, None)?;
@@ -232,6 +586,8 @@ impl AgentRuntime for LocalAgentRuntime {
             let mut guard = self.inner.lock().await;
             guard.session = None;
             guard.profile_id = None;
+            guard.start_config = None;
+            guard.active_config = None;
             (guard.health_shutdown.take(), guard.health_task.take())
         };
 
@@ -259,6 +615,9 @@ impl AgentRuntime for LocalAgentRuntime {
         if !matches!(state, AgentState::Running | AgentState::Degraded) {
             anyhow::bail!("runtime is not running");
         }
+        if self.is_draining() {
+            anyhow::bail!("runtime is draining and not accepting new messages");
+        }
 
         let task_id = uuid::Uuid::new_v4().to_string();
 
@@ -268,6 +627,9 @@ impl AgentRuntime for LocalAgentRuntime {
                 .profile_id
                 .clone()
                 .unwrap_or_else(|| "unknown-profile".into());
+            if self.is_draining() {
+                anyhow::bail!("runtime is draining and not accepting new messages");
+            }
             let Some(session) = guard.session.as_mut() else {
                 anyhow::bail!("runtime session not initialized");
             };
@@ -281,7 +643,24 @@ impl AgentRuntime for LocalAgentRuntime {
             ));
             self.write_log(&profile_id, "info", "agent", "task started");
 
-            let response = session.run_message(message).await;
+            self.in_flight.lock().insert(
+                task_id.clone(),
+                InFlightTask {
+                    task_id: task_id.clone(),
+                    message: message.to_string(),
+                    started_at: Utc::now().to_rfc3339(),
+                },
+            );
+            let response = tokio::select! {
+                response = session.run_message(message) => response,
+                () = self.interrupt.notified() => {
+                    Err(anyhow::anyhow!("task interrupted by runtime shutdown"))
+                }
+            };
+            self.in_flight.lock().remove(&task_id);
+            for call in session.take_tool_calls() {
+                self.publish_tool_call(&profile_id, &task_id, call);
+            }
             (profile_id, response)
         };
 
@@ -323,12 +702,20 @@ impl AgentRuntime for LocalAgentRuntime {
     }
 }
 
-fn load_profile_config(config_path: &Path, workspace_dir: &Path) -> Result<zeroclaw::Config> {
+fn load_profile_config(
+    config_path: &Path,
+    workspace_dir: &Path,
+    vault: Option<&dyn SecretVault>,
+    profile_id: &str,
+) -> Result<zeroclaw::Config> {
     if config_path.exists() {
         let data = std::fs::read_to_string(config_path)
             .with_context(|| format!("failed to read {}", config_path.display()))?;
-        let mut cfg: zeroclaw::Config =
+        let mut table: toml::Table =
             toml::from_str(&data).context("failed to parse profile config")?;
+        resolve_secret_refs(&mut table, vault, profile_id)?;
+        let mut cfg: zeroclaw::Config =
+            table.try_into().context("failed to parse profile config")?;
         cfg.config_path = config_path.to_path_buf();
         cfg.workspace_dir = workspace_dir.to_path_buf();
         cfg.apply_env_overrides();
@@ -342,6 +729,32 @@ fn load_profile_config(config_path: &Path, workspace_dir: &Path) -> Result<zeroc
     Ok(cfg)
 }
 
+/// Collects the dotted paths of every leaf that differs between two configs.
+fn changed_config_keys(before: &Value, after: &Value, prefix: &str, out: &mut Vec<String>) {
+    match (before, after) {
+        (Value::Object(old), Value::Object(new)) => {
+            let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                changed_config_keys(
+                    old.get(key).unwrap_or(&Value::Null),
+                    new.get(key).unwrap_or(&Value::Null),
+                    &path,
+                    out,
+                );
+            }
+        }
+        (old, new) if old != new => out.push(prefix.to_string()),
+        _ => {}
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -372,6 +785,49 @@ mod tests {
         }
     }
 
+    struct SlowSession;
+
+    #[async_trait]
+    impl AgentSession for SlowSession {
+        async fn run_message(&mut self, _message: &str) -> Result<String> {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok("done".into())
+        }
+    }
+
+    struct SlowFactory;
+
+    impl AgentSessionFactory for SlowFactory {
+        fn create_session(&self, _config: &zeroclaw::Config) -> Result<Box<dyn AgentSession>> {
+            Ok(Box::new(SlowSession))
+        }
+    }
+
+    struct ToolSession;
+
+    #[async_trait]
+    impl AgentSession for ToolSession {
+        async fn run_message(&mut self, _message: &str) -> Result<String> {
+            Ok("done".into())
+        }
+
+        fn take_tool_calls(&mut self) -> Vec<ToolCallRecord> {
+            vec![ToolCallRecord {
+                tool: "delegate".into(),
+                success: true,
+                duration_ms: 12,
+            }]
+        }
+    }
+
+    struct ToolFactory;
+
+    impl AgentSessionFactory for ToolFactory {
+        fn create_session(&self, _config: &zeroclaw::Config) -> Result<Box<dyn AgentSession>> {
+            Ok(Box::new(ToolSession))
+        }
+    }
+
     fn runtime_with_factory(tmp: &TempDir, fail: bool) -> LocalAgentRuntime {
         let sink =
             Arc::new(JsonlLogSink::new(LogSinkConfig::new(tmp.path().join("logs"))).unwrap());
@@ -399,6 +855,28 @@ mod tests {
         assert_eq!(runtime.state(), AgentState::Stopped);
     }
 
+    #[tokio::test]
+    async fn start_self_test_checks_vault_and_protocol() {
+        let tmp = TempDir::new().unwrap();
+        let vault = Arc::new(
+            crate::secrets::EncryptedFileSecretVault::new(tmp.path().join("vault"), true).unwrap(),
+        );
+        let mut stale = crate::protocol::protocol_handshake();
+        stale.config_schema_version += 1;
+        let runtime = runtime_with_factory(&tmp, false)
+            .with_secret_vault(vault)
+            .with_expected_protocol(stale);
+
+        assert!(runtime.start(start_config(&tmp)).await.is_err());
+        let report = runtime.readiness().unwrap();
+        assert!(report
+            .checks
+            .iter()
+            .any(|check| check.name == "vault_accessible" && check.passed));
+        assert_eq!(report.failed(), vec!["protocol_consistent"]);
+        assert!(!tmp.path().join("vault").join("vault.json").exists());
+    }
+
     #[tokio::test]
     async fn runtime_moves_to_degraded_on_task_error() {
         let tmp = TempDir::new().unwrap();
@@ -410,4 +888,104 @@ mod tests {
         assert!(err.to_string().contains("simulated session failure"));
         assert_eq!(runtime.state(), AgentState::Degraded);
     }
+
+    #[tokio::test]
+    async fn reload_applies_live_settings_and_reports_the_rest() {
+        let tmp = TempDir::new().unwrap();
+        let runtime = runtime_with_factory(&tmp, false);
+        let config = start_config(&tmp);
+        runtime.start(config.clone()).await.unwrap();
+        let mut events = runtime.subscribe_events();
+
+        let mut edited = load_profile_config(
+            &config.config_path,
+            &config.workspace_dir,
+            None,
+            "profile-a",
+        )
+        .unwrap();
+        edited.default_temperature = 0.2;
+        edited.default_model = Some("changed-model".into());
+        std::fs::write(&config.config_path, toml::to_string(&edited).unwrap()).unwrap();
+
+        let report = runtime.reload_config().await.unwrap();
+        assert_eq!(report.applied, vec!["default_temperature".to_string()]);
+        assert_eq!(report.requires_restart, vec!["default_model".to_string()]);
+        assert_eq!(runtime.state(), AgentState::Running);
+
+        let again = runtime.reload_config().await.unwrap();
+        assert!(again.applied.is_empty());
+        assert_eq!(again.requires_restart, vec!["default_model".to_string()]);
+
+        let event = events.recv().await.unwrap();
+        assert!(matches!(
+            event.kind,
+            RuntimeEventKind::ConfigReloaded { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn drain_interrupts_tasks_past_the_timeout() {
+        let tmp = TempDir::new().unwrap();
+        let sink =
+            Arc::new(JsonlLogSink::new(LogSinkConfig::new(tmp.path().join("logs"))).unwrap());
+        let runtime = Arc::new(LocalAgentRuntime::with_factory(sink, Arc::new(SlowFactory)));
+        runtime.start(start_config(&tmp)).await.unwrap();
+
+        let worker = Arc::clone(&runtime);
+        let pending = tokio::spawn(async move { worker.send_user_message("long job").await });
+        while runtime.in_flight.lock().is_empty() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let report = runtime
+            .drain_and_stop("maintenance", Duration::from_millis(50))
+            .await
+            .unwrap();
+        assert!(report.completed.is_empty());
+        assert_eq!(report.interrupted.len(), 1);
+        assert_eq!(report.interrupted[0].message, "long job");
+
+        let err = pending.await.unwrap().unwrap_err();
+        assert!(err.to_string().contains("interrupted"));
+        assert_eq!(runtime.state(), AgentState::Stopped);
+        assert!(!runtime.is_draining());
+        assert!(tmp
+            .path()
+            .join("workspace")
+            .join(DRAIN_REPORT_FILE)
+            .exists());
+    }
+
+    #[tokio::test]
+    async fn tool_calls_are_published_as_audit_events() {
+        let tmp = TempDir::new().unwrap();
+        let sink =
+            Arc::new(JsonlLogSink::new(LogSinkConfig::new(tmp.path().join("logs"))).unwrap());
+        let runtime = LocalAgentRuntime::with_factory(sink, Arc::new(ToolFactory));
+        runtime.start(start_config(&tmp)).await.unwrap();
+        let mut events = runtime.subscribe_events();
+
+        runtime.send_user_message("plan the trip").await.unwrap();
+
+        let mut task = None;
+        let mut call = None;
+        while let Ok(event) = events.try_recv() {
+            match event.kind {
+                RuntimeEventKind::TaskStarted { task_id, .. } => task = Some(task_id),
+                RuntimeEventKind::ToolCall { .. } => call = Some(event.kind),
+                _ => {}
+            }
+        }
+        assert_eq!(
+            call,
+            Some(RuntimeEventKind::ToolCall {
+                task_id: task.unwrap(),
+                action: TOOL_CALL_ACTION.into(),
+                resource: "delegate".into(),
+                success: true,
+                duration_ms: 12,
+            })
+        );
+    }
 }
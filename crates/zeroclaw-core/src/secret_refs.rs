@@ -0,0 +1,260 @@
+use crate::secrets::SecretVault;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+
+/// Config strings of the form `secret://<key_id>` are replaced with the
+/// profile's vault entry `<key_id>` when the runtime loads the config.
+pub const SECRET_REF_PREFIX: &str = "secret://";
+
+/// Key id prefix used for values moved out of the config file.
+const MIGRATED_KEY_PREFIX: &str = "config/";
+
+/// Replaces every `secret://` reference in a parsed profile config with its
+/// vault value and returns the paths that were resolved. A reference to a
+/// missing secret is an error rather than an empty credential.
+pub fn resolve_secret_refs(
+    table: &mut toml::Table,
+    vault: Option<&dyn SecretVault>,
+    profile_id: &str,
+) -> Result<Vec<String>> {
+    let mut resolved = Vec::new();
+    let mut path = Vec::new();
+    for (key, value) in table.iter_mut() {
+        path.push(key.clone());
+        resolve_value(value, &mut path, vault, profile_id, &mut resolved)?;
+        path.pop();
+    }
+    Ok(resolved)
+}
+
+fn resolve_value(
+    value: &mut toml::Value,
+    path: &mut Vec<String>,
+    vault: Option<&dyn SecretVault>,
+    profile_id: &str,
+    resolved: &mut Vec<String>,
+) -> Result<()> {
+    match value {
+        toml::Value::String(raw) => {
+            let Some(key_id) = raw.strip_prefix(SECRET_REF_PREFIX) else {
+                return Ok(());
+            };
+            let shown = display_path(path);
+            let Some(vault) = vault else {
+                anyhow::bail!("{shown} references a vault secret but no vault is configured");
+            };
+            let secret = vault
+                .get_secret(profile_id, key_id)?
+                .with_context(|| format!("{shown} references missing secret '{key_id}'"))?;
+            *raw = secret;
+            resolved.push(shown);
+        }
+        toml::Value::Table(table) => {
+            for (key, child) in table.iter_mut() {
+                path.push(key.clone());
+                resolve_value(child, path, vault, profile_id, resolved)?;
+                path.pop();
+            }
+        }
+        toml::Value::Array(items) => {
+            for (index, child) in items.iter_mut().enumerate() {
+                path.push(index.to_string());
+                resolve_value(child, path, vault, profile_id, resolved)?;
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Moves plaintext credentials out of the config file into the vault and
+/// rewrites them in place as `secret://config/<path>` references, keeping the
+/// file's comments and layout. Values that are already references or
+/// encrypted with the config's own secret store are left alone. Returns the
+/// migrated paths.
+pub fn migrate_plaintext_secrets(
+    config_path: &Path,
+    vault: &dyn SecretVault,
+    profile_id: &str,
+) -> Result<Vec<String>> {
+    let body = fs::read_to_string(config_path)
+        .with_context(|| format!("failed to read {}", config_path.display()))?;
+    let mut doc: toml_edit::DocumentMut = body.parse().context("failed to parse profile config")?;
+
+    let mut migrated = Vec::new();
+    let mut path = Vec::new();
+    for (key, item) in doc.as_table_mut().iter_mut() {
+        path.push(key.get().to_string());
+        migrate_item(item, &mut path, vault, profile_id, &mut migrated)?;
+        path.pop();
+    }
+    if migrated.is_empty() {
+        return Ok(migrated);
+    }
+
+    let tmp = config_path.with_extension("toml.tmp");
+    fs::write(&tmp, doc.to_string())
+        .with_context(|| format!("failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, config_path)
+        .with_context(|| format!("failed to replace {}", config_path.display()))?;
+    Ok(migrated)
+}
+
+fn migrate_item(
+    item: &mut toml_edit::Item,
+    path: &mut Vec<String>,
+    vault: &dyn SecretVault,
+    profile_id: &str,
+    migrated: &mut Vec<String>,
+) -> Result<()> {
+    match item {
+        toml_edit::Item::Value(value) => migrate_value(value, path, vault, profile_id, migrated),
+        toml_edit::Item::Table(table) => {
+            for (key, child) in table.iter_mut() {
+                path.push(key.get().to_string());
+                migrate_item(child, path, vault, profile_id, migrated)?;
+                path.pop();
+            }
+            Ok(())
+        }
+        toml_edit::Item::ArrayOfTables(tables) => {
+            for (index, table) in tables.iter_mut().enumerate() {
+                path.push(index.to_string());
+                for (key, child) in table.iter_mut() {
+                    path.push(key.get().to_string());
+                    migrate_item(child, path, vault, profile_id, migrated)?;
+                    path.pop();
+                }
+                path.pop();
+            }
+            Ok(())
+        }
+        toml_edit::Item::None => Ok(()),
+    }
+}
+
+fn migrate_value(
+    value: &mut toml_edit::Value,
+    path: &mut Vec<String>,
+    vault: &dyn SecretVault,
+    profile_id: &str,
+    migrated: &mut Vec<String>,
+) -> Result<()> {
+    match value {
+        toml_edit::Value::String(raw) => {
+            let field = path.last().map_or("", String::as_str);
+            let secret = raw.value();
+            if !is_secret_field(field)
+                || secret.trim().is_empty()
+                || secret.starts_with(SECRET_REF_PREFIX)
+                || secret.starts_with("enc:")
+                || secret.starts_with("enc2:")
+            {
+                return Ok(());
+            }
+            let shown = display_path(path);
+            let key_id = format!("{MIGRATED_KEY_PREFIX}{shown}");
+            vault
+                .set_secret(profile_id, &key_id, secret)
+                .with_context(|| format!("failed to store {shown} in the vault"))?;
+            let decor = value.decor().clone();
+            *value = toml_edit::Value::from(format!("{SECRET_REF_PREFIX}{key_id}"));
+            *value.decor_mut() = decor;
+            migrated.push(shown);
+        }
+        toml_edit::Value::InlineTable(table) => {
+            for (key, child) in table.iter_mut() {
+                path.push(key.get().to_string());
+                migrate_value(child, path, vault, profile_id, migrated)?;
+                path.pop();
+            }
+        }
+        toml_edit::Value::Array(items) => {
+            for (index, child) in items.iter_mut().enumerate() {
+                if child.is_inline_table() {
+                    path.push(index.to_string());
+                    migrate_value(child, path, vault, profile_id, migrated)?;
+                    path.pop();
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn is_secret_field(name: &str) -> bool {
+    matches!(name, "password" | "secret")
+        || ["_key", "_token", "_secret", "_password"]
+            .iter()
+            .any(|suffix| name.ends_with(suffix))
+}
+
+/// Dotted TOML path, quoting segments that are not bare keys.
+fn display_path(path: &[String]) -> String {
+    path.iter()
+        .map(|segment| {
+            let bare = !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            if bare {
+                segment.clone()
+            } else {
+                format!("{segment:?}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::EncryptedFileSecretVault;
+    use tempfile::TempDir;
+
+    #[test]
+    fn plaintext_secrets_migrate_to_vault_references() {
+        let tmp = TempDir::new().unwrap();
+        let vault = EncryptedFileSecretVault::new(tmp.path().join("secrets"), true).unwrap();
+        let config_path = tmp.path().join("config.toml");
+        fs::write(
+            &config_path,
+            "# provider settings\n\
+             default_provider = \"openrouter\"\n\
+             api_key = \"sk-plain\" # rotate quarterly\n\
+             [agents.\"team.coder\"]\n\
+             provider = \"openrouter\"\n\
+             model = \"model-test\"\n\
+             api_key = \"sk-agent\"\n",
+        )
+        .unwrap();
+
+        let migrated = migrate_plaintext_secrets(&config_path, &vault, "profile-a").unwrap();
+        assert_eq!(migrated, vec!["api_key", "agents.\"team.coder\".api_key"]);
+        let body = fs::read_to_string(&config_path).unwrap();
+        assert!(!body.contains("sk-plain") && !body.contains("sk-agent"));
+        assert!(body.starts_with("# provider settings\n"));
+        assert!(body.contains("api_key = \"secret://config/api_key\" # rotate quarterly"));
+        assert!(migrate_plaintext_secrets(&config_path, &vault, "profile-a")
+            .unwrap()
+            .is_empty());
+
+        let mut table: toml::Table = toml::from_str(&body).unwrap();
+        assert!(resolve_secret_refs(&mut table, None, "profile-a").is_err());
+        let resolved = resolve_secret_refs(&mut table, Some(&vault), "profile-a").unwrap();
+        assert_eq!(resolved.len(), 2);
+        let config: zeroclaw::Config = table.try_into().unwrap();
+        assert_eq!(
+            config.agents["team.coder"].api_key.as_deref(),
+            Some("sk-agent")
+        );
+
+        vault.delete_secret("profile-a", "config/api_key").unwrap();
+        let mut table: toml::Table = toml::from_str(&body).unwrap();
+        assert!(resolve_secret_refs(&mut table, Some(&vault), "profile-a").is_err());
+    }
+}
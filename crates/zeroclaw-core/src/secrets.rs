@@ -1,9 +1,22 @@
+use crate::control_plane::{ActionPolicyRequest, ControlPlaneStore};
+use crate::mcp::McpConnectorRegistry;
+use crate::workspace_crypto::WorkspaceCipher;
 use anyhow::{Context, Result};
+use base64::Engine;
+use chrono::Utc;
 use parking_lot::Mutex;
+use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const SECRETS_INVENTORY_FILE: &str = "secrets_inventory.json";
+const PASSPHRASE_CHECK_LABEL: &str = "__passphrase_check";
+const MIN_PASSPHRASE_LEN: usize = 12;
+const VAULT_EXPORT_LABEL: &str = "zeroclaw-vault-export";
 
 pub trait SecretVault: Send + Sync {
     fn backend_name(&self) -> &str;
@@ -60,6 +73,24 @@ impl SecretVault for KeyringSecretVault {
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 struct FileSecretMap {
     values: HashMap<String, String>,
+    /// Present once a master passphrase is set; every value is then also
+    /// sealed with the Argon2id key derived from it.
+    #[serde(default)]
+    passphrase: Option<PassphraseParams>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PassphraseParams {
+    salt: String,
+    /// A known value sealed with the derived key, to tell a wrong passphrase
+    /// from a corrupt entry.
+    check: String,
+}
+
+#[derive(Debug)]
+struct UnlockedKey {
+    cipher: WorkspaceCipher,
+    last_used: Instant,
 }
 
 #[derive(Debug, Clone)]
@@ -67,6 +98,8 @@ pub struct EncryptedFileSecretVault {
     data_path: PathBuf,
     store: zeroclaw::security::SecretStore,
     lock: std::sync::Arc<Mutex<()>>,
+    unlocked: Arc<Mutex<Option<UnlockedKey>>>,
+    auto_lock: Option<Duration>,
 }
 
 impl EncryptedFileSecretVault {
@@ -81,9 +114,128 @@ impl EncryptedFileSecretVault {
             data_path,
             store,
             lock: std::sync::Arc::new(Mutex::new(())),
+            unlocked: Arc::new(Mutex::new(None)),
+            auto_lock: None,
         })
     }
 
+    /// Locks the vault again after `idle` without a secret being read or
+    /// written. Only matters once a master passphrase is set.
+    #[must_use]
+    pub fn with_auto_lock(mut self, idle: Duration) -> Self {
+        self.auto_lock = Some(idle);
+        self
+    }
+
+    pub fn has_master_passphrase(&self) -> Result<bool> {
+        let _guard = self.lock.lock();
+        Ok(self.load_map()?.passphrase.is_some())
+    }
+
+    pub fn is_locked(&self) -> Result<bool> {
+        Ok(self.has_master_passphrase()? && !self.expire_unlock())
+    }
+
+    /// Sets or changes the master passphrase and re-seals every stored value
+    /// with the new key. Changing it requires the vault to be unlocked, and
+    /// the old key stays in use until the re-sealed file is saved.
+    pub fn set_master_passphrase(&self, passphrase: &str) -> Result<()> {
+        if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+            anyhow::bail!("master passphrase must be at least {MIN_PASSPHRASE_LEN} characters");
+        }
+
+        let _guard = self.lock.lock();
+        let mut map = self.load_map()?;
+        if map.passphrase.is_some() && !self.expire_unlock() {
+            anyhow::bail!("vault is locked");
+        }
+        let mut unlocked = self.unlocked.lock();
+        let current = match map.passphrase {
+            Some(_) => Some(&unlocked.as_ref().context("vault is locked")?.cipher),
+            None => None,
+        };
+
+        let mut salt = [0_u8; 16];
+        rand::rng().fill_bytes(&mut salt);
+        let cipher = WorkspaceCipher::from_key(&derive_passphrase_key(passphrase, &salt)?);
+        for (entry_key, value) in &mut map.values {
+            let inner = match current {
+                Some(current) => unseal_value(current, entry_key, value)?,
+                None => value.clone(),
+            };
+            *value = cipher.seal(entry_key, inner.as_bytes())?;
+        }
+        map.passphrase = Some(PassphraseParams {
+            salt: base64::engine::general_purpose::STANDARD.encode(salt),
+            check: cipher.seal(PASSPHRASE_CHECK_LABEL, b"ok")?,
+        });
+        self.save_map(&map)?;
+
+        *unlocked = Some(UnlockedKey {
+            cipher,
+            last_used: Instant::now(),
+        });
+        Ok(())
+    }
+
+    pub fn unlock(&self, passphrase: &str) -> Result<()> {
+        let _guard = self.lock.lock();
+        let Some(params) = self.load_map()?.passphrase else {
+            anyhow::bail!("vault has no master passphrase");
+        };
+        let salt = base64::engine::general_purpose::STANDARD
+            .decode(&params.salt)
+            .context("vault passphrase salt is not valid base64")?;
+        let cipher = WorkspaceCipher::from_key(&derive_passphrase_key(passphrase, &salt)?);
+        if cipher.open(PASSPHRASE_CHECK_LABEL, &params.check).is_err() {
+            anyhow::bail!("wrong master passphrase");
+        }
+
+        *self.unlocked.lock() = Some(UnlockedKey {
+            cipher,
+            last_used: Instant::now(),
+        });
+        Ok(())
+    }
+
+    pub fn lock(&self) {
+        *self.unlocked.lock() = None;
+    }
+
+    /// Drops the unlocked key once the auto-lock window has passed. Returns
+    /// whether the vault is still unlocked.
+    fn expire_unlock(&self) -> bool {
+        let mut unlocked = self.unlocked.lock();
+        let idle = unlocked
+            .as_ref()
+            .map(|unlocked| unlocked.last_used.elapsed());
+        if let (Some(idle), Some(limit)) = (idle, self.auto_lock) {
+            if idle >= limit {
+                *unlocked = None;
+            }
+        }
+        unlocked.is_some()
+    }
+
+    /// Runs `f` with the passphrase cipher when the map has one, failing if
+    /// the vault is locked.
+    fn with_passphrase<T>(
+        &self,
+        map: &FileSecretMap,
+        f: impl FnOnce(Option<&WorkspaceCipher>) -> Result<T>,
+    ) -> Result<T> {
+        if map.passphrase.is_none() {
+            return f(None);
+        }
+        if !self.expire_unlock() {
+            anyhow::bail!("vault is locked");
+        }
+        let mut unlocked = self.unlocked.lock();
+        let unlocked = unlocked.as_mut().context("vault is locked")?;
+        unlocked.last_used = Instant::now();
+        f(Some(&unlocked.cipher))
+    }
+
     fn entry_key(profile_id: &str, key: &str) -> String {
         format!("{profile_id}::{key}")
     }
@@ -123,25 +275,34 @@ impl SecretVault for EncryptedFileSecretVault {
     fn set_secret(&self, profile_id: &str, key: &str, value: &str) -> Result<()> {
         let _guard = self.lock.lock();
         let mut map = self.load_map()?;
+        let entry_key = Self::entry_key(profile_id, key);
         let encrypted = self
             .store
             .encrypt(value)
             .with_context(|| format!("failed to encrypt secret {key}"))?;
-        map.values
-            .insert(Self::entry_key(profile_id, key), encrypted);
+        let stored = self.with_passphrase(&map, |cipher| match cipher {
+            Some(cipher) => cipher.seal(&entry_key, encrypted.as_bytes()),
+            None => Ok(encrypted),
+        })?;
+        map.values.insert(entry_key, stored);
         self.save_map(&map)
     }
 
     fn get_secret(&self, profile_id: &str, key: &str) -> Result<Option<String>> {
         let _guard = self.lock.lock();
         let map = self.load_map()?;
-        let Some(raw) = map.values.get(&Self::entry_key(profile_id, key)) else {
+        let entry_key = Self::entry_key(profile_id, key);
+        let Some(raw) = map.values.get(&entry_key) else {
             return Ok(None);
         };
 
+        let raw = self.with_passphrase(&map, |cipher| match cipher {
+            Some(cipher) => unseal_value(cipher, &entry_key, raw),
+            None => Ok(raw.clone()),
+        })?;
         let value = self
             .store
-            .decrypt(raw)
+            .decrypt(&raw)
             .with_context(|| format!("failed to decrypt secret {key}"))?;
         Ok(Some(value))
     }
@@ -149,11 +310,24 @@ impl SecretVault for EncryptedFileSecretVault {
     fn delete_secret(&self, profile_id: &str, key: &str) -> Result<()> {
         let _guard = self.lock.lock();
         let mut map = self.load_map()?;
+        self.with_passphrase(&map, |_| Ok(()))?;
         map.values.remove(&Self::entry_key(profile_id, key));
         self.save_map(&map)
     }
 }
 
+fn derive_passphrase_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32]> {
+    let mut key = [0_u8; 32];
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|error| anyhow::anyhow!("failed to derive vault key: {error}"))?;
+    Ok(key)
+}
+
+fn unseal_value(cipher: &WorkspaceCipher, entry_key: &str, sealed: &str) -> Result<String> {
+    String::from_utf8(cipher.open(entry_key, sealed)?).context("vault entry is not valid UTF-8")
+}
+
 #[derive(Debug, Clone)]
 pub struct AdaptiveSecretVault {
     keyring: KeyringSecretVault,
@@ -207,11 +381,450 @@ impl SecretVault for AdaptiveSecretVault {
     }
 }
 
+/// What is known about a stored secret without reading it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SecretMetadata {
+    pub profile_id: String,
+    pub key: String,
+    pub backend: String,
+    pub created_at: String,
+    pub updated_at: String,
+    #[serde(default)]
+    pub last_rotated_at: Option<String>,
+    /// Integrations, connectors or providers that use this secret, e.g.
+    /// `mcp:github` or `provider:openrouter`.
+    #[serde(default)]
+    pub linked_to: Vec<String>,
+    /// Rotation policy: the value should be replaced at least this often.
+    #[serde(default)]
+    pub max_age_days: Option<u32>,
+}
+
+impl SecretMetadata {
+    /// When the current value was stored.
+    pub fn value_set_at(&self) -> &str {
+        self.last_rotated_at.as_deref().unwrap_or(&self.created_at)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SecretRotationStatus {
+    pub key: String,
+    pub max_age_days: u32,
+    pub value_set_at: String,
+    pub age_days: i64,
+    pub overdue: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SecretInventory {
+    pub entries: Vec<SecretMetadata>,
+}
+
+/// Names-only view of a profile's credentials for compliance reviews.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SecretsInventoryExport {
+    pub generated_at: String,
+    pub profile_id: String,
+    pub receipt_id: String,
+    pub entries: Vec<SecretMetadata>,
+}
+
+/// One profile's secrets sealed with a key derived from an export password,
+/// for moving a profile to another host.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VaultExportBundle {
+    pub version: u32,
+    pub profile_id: String,
+    pub exported_at: String,
+    pub keys: Vec<String>,
+    /// Inventoried keys whose value was no longer in the vault, so they are
+    /// not in the bundle.
+    #[serde(default)]
+    pub missing: Vec<String>,
+    pub salt: String,
+    pub sealed: String,
+}
+
+/// Keeps a names-only index beside any vault, since keyrings cannot list
+/// what they hold. Values pass straight through to the inner vault.
+#[derive(Clone)]
+pub struct InventoriedSecretVault {
+    inner: Arc<dyn SecretVault>,
+    index_path: PathBuf,
+    lock: Arc<Mutex<()>>,
+}
+
+impl InventoriedSecretVault {
+    pub fn new(inner: Arc<dyn SecretVault>, index_dir: &Path) -> Self {
+        Self {
+            inner,
+            index_path: index_dir.join(SECRETS_INVENTORY_FILE),
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    fn load_index(&self) -> Result<SecretInventory> {
+        if !self.index_path.exists() {
+            return Ok(SecretInventory::default());
+        }
+
+        let body = fs::read_to_string(&self.index_path)
+            .with_context(|| format!("failed to read {}", self.index_path.display()))?;
+        serde_json::from_str(&body).context("failed to parse secrets inventory")
+    }
+
+    fn save_index(&self, index: &SecretInventory) -> Result<()> {
+        if let Some(parent) = self.index_path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+
+        let body =
+            serde_json::to_string_pretty(index).context("failed to serialize secrets inventory")?;
+        let tmp = self.index_path.with_extension("json.tmp");
+        fs::write(&tmp, body).with_context(|| format!("failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, &self.index_path)
+            .with_context(|| format!("failed to replace {}", self.index_path.display()))?;
+        Ok(())
+    }
+
+    pub fn inventory(&self, profile_id: &str) -> Result<Vec<SecretMetadata>> {
+        let _guard = self.lock.lock();
+        let mut entries: Vec<SecretMetadata> = self
+            .load_index()?
+            .entries
+            .into_iter()
+            .filter(|entry| entry.profile_id == profile_id)
+            .collect();
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(entries)
+    }
+
+    /// Metadata for the profile's keys that start with `prefix`, e.g.
+    /// `provider/` for a namespace. An empty prefix lists every key.
+    pub fn list(&self, profile_id: &str, prefix: &str) -> Result<Vec<SecretMetadata>> {
+        let mut entries = self.inventory(profile_id)?;
+        entries.retain(|entry| entry.key.starts_with(prefix));
+        Ok(entries)
+    }
+
+    /// Records that `target` (e.g. `provider:openrouter`) uses `key`.
+    pub fn link(&self, profile_id: &str, key: &str, target: &str) -> Result<SecretMetadata> {
+        let _guard = self.lock.lock();
+        let mut index = self.load_index()?;
+        let Some(entry) = index
+            .entries
+            .iter_mut()
+            .find(|entry| entry.profile_id == profile_id && entry.key == key)
+        else {
+            anyhow::bail!("secret '{key}' is not in the inventory");
+        };
+        if !entry.linked_to.iter().any(|linked| linked == target) {
+            entry.linked_to.push(target.to_string());
+            entry.linked_to.sort();
+        }
+        let out = entry.clone();
+        self.save_index(&index)?;
+        Ok(out)
+    }
+
+    /// Sets or clears the maximum age for `key` before it is due for rotation.
+    pub fn set_rotation_policy(
+        &self,
+        profile_id: &str,
+        key: &str,
+        max_age_days: Option<u32>,
+    ) -> Result<SecretMetadata> {
+        if max_age_days == Some(0) {
+            anyhow::bail!("max_age_days must be at least 1");
+        }
+
+        let _guard = self.lock.lock();
+        let mut index = self.load_index()?;
+        let Some(entry) = index
+            .entries
+            .iter_mut()
+            .find(|entry| entry.profile_id == profile_id && entry.key == key)
+        else {
+            anyhow::bail!("secret '{key}' is not in the inventory");
+        };
+        entry.max_age_days = max_age_days;
+        let out = entry.clone();
+        self.save_index(&index)?;
+        Ok(out)
+    }
+
+    /// Age of every key that has a rotation policy, overdue keys first.
+    pub fn rotation_report(&self, profile_id: &str) -> Result<Vec<SecretRotationStatus>> {
+        let now = Utc::now();
+        let mut report: Vec<SecretRotationStatus> = self
+            .inventory(profile_id)?
+            .into_iter()
+            .filter_map(|entry| {
+                let max_age_days = entry.max_age_days?;
+                let set_at = chrono::DateTime::parse_from_rfc3339(entry.value_set_at()).ok()?;
+                let age_days = (now - set_at.with_timezone(&Utc)).num_days();
+                Some(SecretRotationStatus {
+                    value_set_at: entry.value_set_at().to_string(),
+                    key: entry.key,
+                    max_age_days,
+                    age_days,
+                    overdue: age_days >= i64::from(max_age_days),
+                })
+            })
+            .collect();
+        report.sort_by(|a, b| b.overdue.cmp(&a.overdue).then_with(|| a.key.cmp(&b.key)));
+        Ok(report)
+    }
+
+    /// Replaces the value of an existing key after the control plane allows
+    /// `secrets.rotate`; the decision receipt records the rotation.
+    pub fn rotate(
+        &self,
+        profile_id: &str,
+        key: &str,
+        new_value: &str,
+        control_plane: &ControlPlaneStore,
+        request: ActionPolicyRequest,
+    ) -> Result<SecretMetadata> {
+        if new_value.is_empty() {
+            anyhow::bail!("new secret value must not be empty");
+        }
+        if !self
+            .inventory(profile_id)?
+            .iter()
+            .any(|entry| entry.key == key)
+        {
+            anyhow::bail!("secret '{key}' is not in the inventory");
+        }
+
+        let decision = control_plane.evaluate_action(ActionPolicyRequest {
+            action: "secrets.rotate".into(),
+            resource: format!("secret:{key}"),
+            ..request
+        })?;
+        if !decision.allowed {
+            anyhow::bail!("secret rotation denied: {}", decision.reason);
+        }
+
+        self.set_secret(profile_id, key, new_value)?;
+        self.inventory(profile_id)?
+            .into_iter()
+            .find(|entry| entry.key == key)
+            .context("rotated secret vanished from the inventory")
+    }
+
+    /// Seals every inventoried secret of `profile_id` with `password` after
+    /// the control plane allows `secrets.export`. Only keys in the inventory
+    /// can be exported; inventoried keys without a value are listed in
+    /// `missing` rather than silently dropped.
+    pub fn export_encrypted(
+        &self,
+        profile_id: &str,
+        password: &str,
+        control_plane: &ControlPlaneStore,
+        request: ActionPolicyRequest,
+    ) -> Result<VaultExportBundle> {
+        if password.chars().count() < MIN_PASSPHRASE_LEN {
+            anyhow::bail!("export password must be at least {MIN_PASSPHRASE_LEN} characters");
+        }
+        let keys: Vec<String> = self
+            .inventory(profile_id)?
+            .into_iter()
+            .map(|entry| entry.key)
+            .collect();
+
+        let mut context = request.context.clone();
+        context.insert("secret_keys".into(), serde_json::Value::from(keys.clone()));
+        let decision = control_plane.evaluate_action(ActionPolicyRequest {
+            action: "secrets.export".into(),
+            resource: profile_id.to_string(),
+            context,
+            ..request
+        })?;
+        if !decision.allowed {
+            anyhow::bail!("vault export denied: {}", decision.reason);
+        }
+
+        let mut values = std::collections::BTreeMap::new();
+        let mut missing = Vec::new();
+        for key in keys {
+            match self.get_secret(profile_id, &key)? {
+                Some(value) => {
+                    values.insert(key, value);
+                }
+                None => missing.push(key),
+            }
+        }
+        let payload = serde_json::to_vec(&values).context("failed to serialize vault export")?;
+
+        let mut salt = [0_u8; 16];
+        rand::rng().fill_bytes(&mut salt);
+        let cipher = WorkspaceCipher::from_key(&derive_passphrase_key(password, &salt)?);
+        Ok(VaultExportBundle {
+            version: 1,
+            profile_id: profile_id.to_string(),
+            exported_at: Utc::now().to_rfc3339(),
+            keys: values.keys().cloned().collect(),
+            missing,
+            salt: base64::engine::general_purpose::STANDARD.encode(salt),
+            sealed: cipher.seal(VAULT_EXPORT_LABEL, &payload)?,
+        })
+    }
+
+    /// Restores a bundle into `profile_id` after the control plane allows
+    /// `secrets.import`, overwriting keys that already exist. Returns the
+    /// imported keys.
+    pub fn import_encrypted(
+        &self,
+        profile_id: &str,
+        bundle: &VaultExportBundle,
+        password: &str,
+        control_plane: &ControlPlaneStore,
+        request: ActionPolicyRequest,
+    ) -> Result<Vec<String>> {
+        if bundle.version != 1 {
+            anyhow::bail!("unsupported vault export version {}", bundle.version);
+        }
+        let salt = base64::engine::general_purpose::STANDARD
+            .decode(&bundle.salt)
+            .context("vault export salt is not valid base64")?;
+        let cipher = WorkspaceCipher::from_key(&derive_passphrase_key(password, &salt)?);
+        let payload = cipher
+            .open(VAULT_EXPORT_LABEL, &bundle.sealed)
+            .map_err(|_| anyhow::anyhow!("wrong export password or tampered bundle"))?;
+        let values: std::collections::BTreeMap<String, String> =
+            serde_json::from_slice(&payload).context("failed to parse vault export")?;
+
+        let keys: Vec<String> = values.keys().cloned().collect();
+        let mut context = request.context.clone();
+        context.insert("secret_keys".into(), serde_json::Value::from(keys.clone()));
+        context.insert(
+            "source_profile_id".into(),
+            serde_json::Value::String(bundle.profile_id.clone()),
+        );
+        let decision = control_plane.evaluate_action(ActionPolicyRequest {
+            action: "secrets.import".into(),
+            resource: profile_id.to_string(),
+            context,
+            ..request
+        })?;
+        if !decision.allowed {
+            anyhow::bail!("vault import denied: {}", decision.reason);
+        }
+
+        for (key, value) in &values {
+            self.set_secret(profile_id, key, value)?;
+        }
+        Ok(keys)
+    }
+
+    /// Exports the inventory for `request.actor_id` after the control plane
+    /// allows `secrets.inventory_export`. MCP connectors that reference a
+    /// secret through `env_secret_ids` are added to its links.
+    pub fn export_inventory(
+        &self,
+        profile_id: &str,
+        control_plane: &ControlPlaneStore,
+        request: ActionPolicyRequest,
+        connectors: &McpConnectorRegistry,
+    ) -> Result<SecretsInventoryExport> {
+        let decision = control_plane.evaluate_action(ActionPolicyRequest {
+            action: "secrets.inventory_export".into(),
+            resource: profile_id.to_string(),
+            ..request
+        })?;
+        if !decision.allowed {
+            anyhow::bail!("secrets inventory export denied: {}", decision.reason);
+        }
+
+        let mut entries = self.inventory(profile_id)?;
+        for entry in &mut entries {
+            for connector in &connectors.records {
+                let target = format!("mcp:{}", connector.connector_id);
+                if connector.config.env_secret_ids.contains(&entry.key)
+                    && !entry.linked_to.contains(&target)
+                {
+                    entry.linked_to.push(target);
+                }
+            }
+            entry.linked_to.sort();
+        }
+
+        Ok(SecretsInventoryExport {
+            generated_at: Utc::now().to_rfc3339(),
+            profile_id: profile_id.to_string(),
+            receipt_id: decision.receipt_id,
+            entries,
+        })
+    }
+}
+
+impl SecretVault for InventoriedSecretVault {
+    fn backend_name(&self) -> &str {
+        self.inner.backend_name()
+    }
+
+    fn set_secret(&self, profile_id: &str, key: &str, value: &str) -> Result<()> {
+        self.inner.set_secret(profile_id, key, value)?;
+
+        let _guard = self.lock.lock();
+        let mut index = self.load_index()?;
+        let now = Utc::now().to_rfc3339();
+        if let Some(entry) = index
+            .entries
+            .iter_mut()
+            .find(|entry| entry.profile_id == profile_id && entry.key == key)
+        {
+            entry.updated_at.clone_from(&now);
+            entry.last_rotated_at = Some(now);
+            entry.backend = self.inner.backend_name().to_string();
+        } else {
+            index.entries.push(SecretMetadata {
+                profile_id: profile_id.to_string(),
+                key: key.to_string(),
+                backend: self.inner.backend_name().to_string(),
+                created_at: now.clone(),
+                updated_at: now,
+                last_rotated_at: None,
+                linked_to: Vec::new(),
+                max_age_days: None,
+            });
+        }
+        self.save_index(&index)
+    }
+
+    fn get_secret(&self, profile_id: &str, key: &str) -> Result<Option<String>> {
+        self.inner.get_secret(profile_id, key)
+    }
+
+    fn delete_secret(&self, profile_id: &str, key: &str) -> Result<()> {
+        self.inner.delete_secret(profile_id, key)?;
+
+        let _guard = self.lock.lock();
+        let mut index = self.load_index()?;
+        index
+            .entries
+            .retain(|entry| !(entry.profile_id == profile_id && entry.key == key));
+        self.save_index(&index)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    /// A freshly authenticated `role` user; the vault call fills in the
+    /// action and resource.
+    fn request(role: &str) -> ActionPolicyRequest {
+        ActionPolicyRequest {
+            authenticated_at: Some(Utc::now().to_rfc3339()),
+            ..crate::control_plane::tests::request(role, "", "", "local")
+        }
+    }
+
     #[test]
     fn encrypted_file_vault_roundtrip() {
         let tmp = TempDir::new().unwrap();
@@ -233,4 +846,269 @@ mod tests {
             .unwrap()
             .is_none());
     }
+
+    #[test]
+    fn master_passphrase_locks_file_vault() {
+        let tmp = TempDir::new().unwrap();
+        let vault = EncryptedFileSecretVault::new(tmp.path(), true).unwrap();
+        vault
+            .set_secret("profile-a", "openai_api_key", "sk-test-value")
+            .unwrap();
+        assert!(vault.set_master_passphrase("short").is_err());
+        vault
+            .set_master_passphrase("correct horse battery")
+            .unwrap();
+        assert!(!vault.is_locked().unwrap());
+
+        let reopened = EncryptedFileSecretVault::new(tmp.path(), true).unwrap();
+        assert!(reopened.is_locked().unwrap());
+        assert!(reopened.get_secret("profile-a", "openai_api_key").is_err());
+        assert!(reopened.unlock("wrong horse battery").is_err());
+        reopened.unlock("correct horse battery").unwrap();
+        assert_eq!(
+            reopened.get_secret("profile-a", "openai_api_key").unwrap(),
+            Some("sk-test-value".into())
+        );
+
+        reopened.lock();
+        assert!(reopened
+            .delete_secret("profile-a", "openai_api_key")
+            .is_err());
+        reopened.unlock("correct horse battery").unwrap();
+
+        let blocked = tmp.path().join("vault.json.tmp");
+        fs::create_dir(&blocked).unwrap();
+        assert!(reopened
+            .set_master_passphrase("failed horse battery")
+            .is_err());
+        fs::remove_dir(&blocked).unwrap();
+        assert_eq!(
+            reopened.get_secret("profile-a", "openai_api_key").unwrap(),
+            Some("sk-test-value".into())
+        );
+
+        reopened
+            .set_master_passphrase("staple horse battery")
+            .unwrap();
+        reopened.lock();
+        assert!(reopened.unlock("correct horse battery").is_err());
+        let expiring = reopened.with_auto_lock(Duration::ZERO);
+        expiring.unlock("staple horse battery").unwrap();
+        assert!(expiring.is_locked().unwrap());
+        assert!(expiring.set_secret("profile-a", "other", "value").is_err());
+    }
+
+    #[test]
+    fn inventory_tracks_names_rotation_and_links_without_values() {
+        let tmp = TempDir::new().unwrap();
+        let file_vault: Arc<dyn SecretVault> =
+            Arc::new(EncryptedFileSecretVault::new(tmp.path().join("secrets"), true).unwrap());
+        let vault = InventoriedSecretVault::new(file_vault, tmp.path());
+        let control_plane = ControlPlaneStore::for_workspace(&tmp.path().join("workspace"));
+
+        vault
+            .set_secret("profile-a", "github_token", "ghp-secret-value")
+            .unwrap();
+        vault
+            .set_secret("profile-a", "openrouter_api_key", "sk-first")
+            .unwrap();
+        vault
+            .set_secret("profile-a", "openrouter_api_key", "sk-second")
+            .unwrap();
+        vault
+            .link("profile-a", "openrouter_api_key", "provider:openrouter")
+            .unwrap();
+
+        let inventory = vault.inventory("profile-a").unwrap();
+        assert_eq!(inventory.len(), 2);
+        assert!(inventory[0].last_rotated_at.is_none());
+        assert!(inventory[1].last_rotated_at.is_some());
+
+        let connectors: McpConnectorRegistry = serde_json::from_value(serde_json::json!({
+            "records": [{
+                "connector_id": "github",
+                "display_name": "GitHub",
+                "installed_at": "2026-01-01T00:00:00Z",
+                "updated_at": "2026-01-01T00:00:00Z",
+                "enabled": true,
+                "enabled_at": null,
+                "config": { "transport": "stdio", "command": "gh-mcp", "env_secret_ids": ["github_token"] },
+                "contract": {
+                    "integration_id": "mcp:github",
+                    "can_access": [],
+                    "can_do": [],
+                    "data_destinations": []
+                }
+            }]
+        }))
+        .unwrap();
+
+        assert!(vault
+            .export_inventory("profile-a", &control_plane, request("viewer"), &connectors)
+            .is_err());
+
+        let export = vault
+            .export_inventory("profile-a", &control_plane, request("owner"), &connectors)
+            .unwrap();
+        assert_eq!(export.entries[0].linked_to, vec!["mcp:github".to_string()]);
+        let body = serde_json::to_string(&export).unwrap();
+        assert!(!body.contains("ghp-secret-value"));
+        assert!(!body.contains("sk-second"));
+
+        vault.delete_secret("profile-a", "github_token").unwrap();
+        assert_eq!(vault.inventory("profile-a").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn list_filters_keys_by_namespace_prefix() {
+        let tmp = TempDir::new().unwrap();
+        let file_vault: Arc<dyn SecretVault> =
+            Arc::new(EncryptedFileSecretVault::new(tmp.path().join("secrets"), true).unwrap());
+        let vault = InventoriedSecretVault::new(file_vault, tmp.path());
+        for key in [
+            "provider/openrouter",
+            "provider/anthropic",
+            "channel/telegram",
+        ] {
+            vault.set_secret("profile-a", key, "value").unwrap();
+        }
+        vault
+            .set_secret("profile-b", "provider/openai", "value")
+            .unwrap();
+
+        let providers = vault.list("profile-a", "provider/").unwrap();
+        let keys: Vec<&str> = providers.iter().map(|entry| entry.key.as_str()).collect();
+        assert_eq!(keys, vec!["provider/anthropic", "provider/openrouter"]);
+        assert_eq!(providers[0].backend, "encrypted-file");
+        assert_eq!(vault.list("profile-a", "").unwrap().len(), 3);
+    }
+
+    #[test]
+    fn rotation_report_flags_overdue_keys_and_rotate_is_audited() {
+        let tmp = TempDir::new().unwrap();
+        let file_vault: Arc<dyn SecretVault> =
+            Arc::new(EncryptedFileSecretVault::new(tmp.path().join("secrets"), true).unwrap());
+        let vault = InventoriedSecretVault::new(file_vault, tmp.path());
+        let control_plane = ControlPlaneStore::for_workspace(&tmp.path().join("workspace"));
+        vault
+            .set_secret("profile-a", "github_token", "ghp-1")
+            .unwrap();
+        vault
+            .set_secret("profile-a", "slack_token", "xoxb-1")
+            .unwrap();
+        vault
+            .set_rotation_policy("profile-a", "slack_token", Some(90))
+            .unwrap();
+        vault
+            .set_rotation_policy("profile-a", "github_token", Some(30))
+            .unwrap();
+
+        let mut index = vault.load_index().unwrap();
+        index.entries[0].created_at = (Utc::now() - chrono::Duration::days(45)).to_rfc3339();
+        vault.save_index(&index).unwrap();
+
+        let report = vault.rotation_report("profile-a").unwrap();
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].key, "github_token");
+        assert!(report[0].overdue);
+        assert!(!report[1].overdue);
+
+        let rotated = vault
+            .rotate(
+                "profile-a",
+                "github_token",
+                "ghp-2",
+                &control_plane,
+                request("owner"),
+            )
+            .unwrap();
+        assert!(rotated.last_rotated_at.is_some());
+        assert_eq!(
+            vault.get_secret("profile-a", "github_token").unwrap(),
+            Some("ghp-2".into())
+        );
+        assert!(vault
+            .rotation_report("profile-a")
+            .unwrap()
+            .iter()
+            .all(|status| !status.overdue));
+        let receipt = &control_plane.list_receipts(1).unwrap()[0];
+        assert_eq!(receipt.action, "secrets.rotate");
+        assert_eq!(receipt.resource, "secret:github_token");
+    }
+
+    #[test]
+    fn vault_export_moves_secrets_between_hosts() {
+        let tmp = TempDir::new().unwrap();
+        let inventoried = |dir: &str| {
+            let inner: Arc<dyn SecretVault> =
+                Arc::new(EncryptedFileSecretVault::new(tmp.path().join(dir), true).unwrap());
+            InventoriedSecretVault::new(inner, &tmp.path().join(dir))
+        };
+        let source = inventoried("old-host");
+        let target = inventoried("new-host");
+        let control_plane = ControlPlaneStore::for_workspace(&tmp.path().join("workspace"));
+
+        source
+            .set_secret("profile-a", "openrouter_api_key", "sk-moved")
+            .unwrap();
+        source
+            .set_secret("profile-a", "github_token", "ghp-moved")
+            .unwrap();
+        source
+            .set_secret("profile-a", "stale_token", "removed-elsewhere")
+            .unwrap();
+        EncryptedFileSecretVault::new(tmp.path().join("old-host"), true)
+            .unwrap()
+            .delete_secret("profile-a", "stale_token")
+            .unwrap();
+        let password = "export password 1";
+        assert!(source
+            .export_encrypted("profile-a", password, &control_plane, request("viewer"))
+            .is_err());
+        let bundle = source
+            .export_encrypted("profile-a", password, &control_plane, request("owner"))
+            .unwrap();
+        assert!(!serde_json::to_string(&bundle).unwrap().contains("sk-moved"));
+        assert_eq!(bundle.keys, vec!["github_token", "openrouter_api_key"]);
+        assert_eq!(bundle.missing, vec!["stale_token"]);
+
+        assert!(target
+            .import_encrypted(
+                "profile-a",
+                &bundle,
+                "wrong password 1",
+                &control_plane,
+                request("owner")
+            )
+            .is_err());
+        let imported = target
+            .import_encrypted(
+                "profile-a",
+                &bundle,
+                password,
+                &control_plane,
+                request("owner"),
+            )
+            .unwrap();
+        assert_eq!(imported, vec!["github_token", "openrouter_api_key"]);
+        assert_eq!(
+            target
+                .get_secret("profile-a", "openrouter_api_key")
+                .unwrap(),
+            Some("sk-moved".into())
+        );
+        assert_eq!(target.inventory("profile-a").unwrap().len(), 2);
+
+        let actions: Vec<String> = control_plane
+            .list_receipts(10)
+            .unwrap()
+            .into_iter()
+            .map(|receipt| receipt.action)
+            .collect();
+        assert_eq!(
+            actions,
+            vec!["secrets.import", "secrets.export", "secrets.export"]
+        );
+    }
 }
@@ -0,0 +1,839 @@
+use crate::secrets::SecretVault;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Arc;
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Managed secret store that holds a profile's secrets. Credentials for the
+/// store itself are read from the local vault by id, never from config.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "backend", rename_all = "snake_case")]
+pub enum ExternalVaultConfig {
+    HashicorpVault {
+        address: String,
+        #[serde(default = "default_kv_mount")]
+        mount: String,
+        #[serde(default = "default_path_prefix")]
+        path_prefix: String,
+        token_secret_id: String,
+    },
+    AwsSecretsManager {
+        region: String,
+        #[serde(default = "default_path_prefix")]
+        name_prefix: String,
+        access_key_id_secret_id: String,
+        secret_access_key_secret_id: String,
+        #[serde(default)]
+        session_token_secret_id: Option<String>,
+        /// Overrides `https://secretsmanager.<region>.amazonaws.com`, e.g. for
+        /// a VPC endpoint.
+        #[serde(default)]
+        endpoint: Option<String>,
+    },
+    OnePasswordConnect {
+        host: String,
+        vault_id: String,
+        token_secret_id: String,
+    },
+}
+
+fn default_kv_mount() -> String {
+    "secret".into()
+}
+
+fn default_path_prefix() -> String {
+    "zeroclaw".into()
+}
+
+/// Builds the configured backend, reading its credentials from `bootstrap`.
+pub fn open_external_vault(
+    config: &ExternalVaultConfig,
+    bootstrap: &dyn SecretVault,
+    profile_id: &str,
+) -> Result<Arc<dyn SecretVault>> {
+    let credential = |id: &str| -> Result<String> {
+        bootstrap
+            .get_secret(profile_id, id)?
+            .with_context(|| format!("bootstrap secret '{id}' is missing"))
+    };
+
+    Ok(match config {
+        ExternalVaultConfig::HashicorpVault {
+            address,
+            mount,
+            path_prefix,
+            token_secret_id,
+        } => Arc::new(HashicorpVaultSecretVault {
+            address: address.trim_end_matches('/').to_string(),
+            mount: mount.trim_matches('/').to_string(),
+            path_prefix: path_prefix.trim_matches('/').to_string(),
+            token: credential(token_secret_id)?,
+        }),
+        ExternalVaultConfig::AwsSecretsManager {
+            region,
+            name_prefix,
+            access_key_id_secret_id,
+            secret_access_key_secret_id,
+            session_token_secret_id,
+            endpoint,
+        } => Arc::new(AwsSecretsManagerSecretVault {
+            endpoint: endpoint.as_deref().map_or_else(
+                || format!("https://secretsmanager.{region}.amazonaws.com"),
+                |endpoint| endpoint.trim_end_matches('/').to_string(),
+            ),
+            region: region.clone(),
+            name_prefix: name_prefix.trim_matches('/').to_string(),
+            access_key_id: credential(access_key_id_secret_id)?,
+            secret_access_key: credential(secret_access_key_secret_id)?,
+            session_token: session_token_secret_id
+                .as_deref()
+                .map(credential)
+                .transpose()?,
+        }),
+        ExternalVaultConfig::OnePasswordConnect {
+            host,
+            vault_id,
+            token_secret_id,
+        } => Arc::new(OnePasswordConnectSecretVault {
+            host: host.trim_end_matches('/').to_string(),
+            vault_id: vault_id.clone(),
+            token: credential(token_secret_id)?,
+        }),
+    })
+}
+
+/// Sends one request and returns its status and body. The vault API is
+/// synchronous and may be called from inside a tokio runtime, where the
+/// blocking client cannot run, so each call gets its own thread.
+fn send<F>(build: F) -> Result<(u16, String)>
+where
+    F: FnOnce(&reqwest::blocking::Client) -> reqwest::blocking::RequestBuilder + Send,
+{
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                let client = reqwest::blocking::Client::builder()
+                    .timeout(REQUEST_TIMEOUT)
+                    .build()
+                    .context("failed to build secret store client")?;
+                let response = build(&client)
+                    .send()
+                    .context("secret store request failed")?;
+                let status = response.status().as_u16();
+                let body = response
+                    .text()
+                    .context("failed to read secret store response")?;
+                Ok((status, body))
+            })
+            .join()
+            .map_err(|_| anyhow::anyhow!("secret store request panicked"))?
+    })
+}
+
+/// Percent-encodes a profile id, key or item id for use as one URL path
+/// segment, keeping only RFC 3986 unreserved characters. Dot segments are
+/// rejected since URL parsers resolve them even when encoded.
+fn path_segment(raw: &str) -> Result<String> {
+    if matches!(raw, "" | "." | "..") {
+        anyhow::bail!("'{raw}' cannot be used as a secret store path segment");
+    }
+    let mut encoded = String::with_capacity(raw.len());
+    for byte in raw.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            encoded.push(char::from(byte));
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    Ok(encoded)
+}
+
+fn ensure_success(backend: &str, status: u16, body: &str) -> Result<()> {
+    if (200..300).contains(&status) {
+        Ok(())
+    } else {
+        anyhow::bail!("{backend} returned HTTP {status}: {body}")
+    }
+}
+
+/// KV version 2 secrets engine; each secret is a `value` field at
+/// `<mount>/data/<path_prefix>/<profile_id>/<key>`.
+pub struct HashicorpVaultSecretVault {
+    address: String,
+    mount: String,
+    path_prefix: String,
+    token: String,
+}
+
+impl HashicorpVaultSecretVault {
+    fn url(&self, kind: &str, profile_id: &str, key: &str) -> Result<String> {
+        Ok(format!(
+            "{}/v1/{}/{kind}/{}/{}/{}",
+            self.address,
+            self.mount,
+            self.path_prefix,
+            path_segment(profile_id)?,
+            path_segment(key)?
+        ))
+    }
+}
+
+impl SecretVault for HashicorpVaultSecretVault {
+    fn backend_name(&self) -> &'static str {
+        "hashicorp-vault"
+    }
+
+    fn set_secret(&self, profile_id: &str, key: &str, value: &str) -> Result<()> {
+        let url = self.url("data", profile_id, key)?;
+        let (status, body) = send(|client| {
+            client
+                .post(&url)
+                .header("X-Vault-Token", &self.token)
+                .json(&json!({ "data": { "value": value } }))
+        })?;
+        ensure_success(self.backend_name(), status, &body)
+    }
+
+    fn get_secret(&self, profile_id: &str, key: &str) -> Result<Option<String>> {
+        let url = self.url("data", profile_id, key)?;
+        let (status, body) = send(|client| client.get(&url).header("X-Vault-Token", &self.token))?;
+        if status == 404 {
+            return Ok(None);
+        }
+        ensure_success(self.backend_name(), status, &body)?;
+        let body: Value = serde_json::from_str(&body).context("invalid Vault response")?;
+        Ok(body["data"]["data"]["value"].as_str().map(str::to_string))
+    }
+
+    fn delete_secret(&self, profile_id: &str, key: &str) -> Result<()> {
+        let url = self.url("metadata", profile_id, key)?;
+        let (status, body) =
+            send(|client| client.delete(&url).header("X-Vault-Token", &self.token))?;
+        if status == 404 {
+            return Ok(());
+        }
+        ensure_success(self.backend_name(), status, &body)
+    }
+}
+
+/// Secrets named `<name_prefix>/<profile_id>/<key>`, called through the
+/// JSON API with SigV4-signed requests.
+pub struct AwsSecretsManagerSecretVault {
+    endpoint: String,
+    region: String,
+    name_prefix: String,
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+}
+
+impl AwsSecretsManagerSecretVault {
+    fn secret_name(&self, profile_id: &str, key: &str) -> String {
+        format!("{}/{profile_id}/{key}", self.name_prefix)
+    }
+
+    fn call(&self, operation: &str, payload: &Value) -> Result<(u16, String)> {
+        let host = self
+            .endpoint
+            .split_once("://")
+            .map_or(self.endpoint.as_str(), |(_, rest)| rest)
+            .to_string();
+        let body = serde_json::to_vec(payload).context("failed to encode request")?;
+        let amz_date = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+
+        let mut headers = BTreeMap::new();
+        headers.insert(
+            "content-type".to_string(),
+            "application/x-amz-json-1.1".to_string(),
+        );
+        headers.insert("host".to_string(), host.clone());
+        headers.insert("x-amz-date".to_string(), amz_date.clone());
+        headers.insert(
+            "x-amz-target".to_string(),
+            format!("secretsmanager.{operation}"),
+        );
+        if let Some(token) = &self.session_token {
+            headers.insert("x-amz-security-token".to_string(), token.clone());
+        }
+        let authorization = sigv4_authorization(
+            &self.access_key_id,
+            &self.secret_access_key,
+            &self.region,
+            "secretsmanager",
+            "POST",
+            "/",
+            &headers,
+            &body,
+        );
+
+        let url = format!("{}/", self.endpoint);
+        send(|client| {
+            let mut request = client
+                .post(&url)
+                .header("Authorization", authorization)
+                .body(body);
+            for (name, value) in &headers {
+                if name != "host" {
+                    request = request.header(name.as_str(), value.as_str());
+                }
+            }
+            request
+        })
+    }
+}
+
+fn is_not_found(body: &str) -> bool {
+    body.contains("ResourceNotFoundException")
+}
+
+impl SecretVault for AwsSecretsManagerSecretVault {
+    fn backend_name(&self) -> &'static str {
+        "aws-secrets-manager"
+    }
+
+    fn set_secret(&self, profile_id: &str, key: &str, value: &str) -> Result<()> {
+        let name = self.secret_name(profile_id, key);
+        let (status, body) = self.call(
+            "PutSecretValue",
+            &json!({ "SecretId": name, "SecretString": value }),
+        )?;
+        if status == 400 && is_not_found(&body) {
+            let (status, body) = self.call(
+                "CreateSecret",
+                &json!({ "Name": name, "SecretString": value }),
+            )?;
+            return ensure_success(self.backend_name(), status, &body);
+        }
+        ensure_success(self.backend_name(), status, &body)
+    }
+
+    fn get_secret(&self, profile_id: &str, key: &str) -> Result<Option<String>> {
+        let name = self.secret_name(profile_id, key);
+        let (status, body) = self.call("GetSecretValue", &json!({ "SecretId": name }))?;
+        if status == 400 && is_not_found(&body) {
+            return Ok(None);
+        }
+        ensure_success(self.backend_name(), status, &body)?;
+        let body: Value =
+            serde_json::from_str(&body).context("invalid Secrets Manager response")?;
+        Ok(body["SecretString"].as_str().map(str::to_string))
+    }
+
+    fn delete_secret(&self, profile_id: &str, key: &str) -> Result<()> {
+        let name = self.secret_name(profile_id, key);
+        let (status, body) = self.call(
+            "DeleteSecret",
+            &json!({ "SecretId": name, "ForceDeleteWithoutRecovery": true }),
+        )?;
+        if status == 400 && is_not_found(&body) {
+            return Ok(());
+        }
+        ensure_success(self.backend_name(), status, &body)
+    }
+}
+
+/// AWS Signature Version 4 `Authorization` header for a request without a
+/// query string. `headers` must use lowercase names and include `host` and
+/// `x-amz-date`; all of them are signed.
+#[allow(clippy::too_many_arguments)]
+fn sigv4_authorization(
+    access_key_id: &str,
+    secret_access_key: &str,
+    region: &str,
+    service: &str,
+    method: &str,
+    path: &str,
+    headers: &BTreeMap<String, String>,
+    payload: &[u8],
+) -> String {
+    let amz_date = headers.get("x-amz-date").map_or("", String::as_str);
+    let date = amz_date.get(..8).unwrap_or_default();
+
+    let mut canonical_headers = String::new();
+    for (name, value) in headers {
+        let _ = writeln!(canonical_headers, "{name}:{}", value.trim());
+    }
+    let signed_headers = headers.keys().cloned().collect::<Vec<_>>().join(";");
+    let canonical_request = format!(
+        "{method}\n{path}\n\n{canonical_headers}\n{signed_headers}\n{}",
+        hex::encode(Sha256::digest(payload))
+    );
+
+    let scope = format!("{date}/{region}/{service}/aws4_request");
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let mut key = format!("AWS4{secret_access_key}").into_bytes();
+    for part in [date, region, service, "aws4_request"] {
+        key = hmac_sha256(&key, part.as_bytes());
+    }
+    let signature = hex::encode(hmac_sha256(&key, string_to_sign.as_bytes()));
+    format!(
+        "AWS4-HMAC-SHA256 Credential={access_key_id}/{scope}, SignedHeaders={signed_headers}, Signature={signature}"
+    )
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// One `PASSWORD` item per secret, titled `zeroclaw:<profile_id>:<key>`.
+pub struct OnePasswordConnectSecretVault {
+    host: String,
+    vault_id: String,
+    token: String,
+}
+
+impl OnePasswordConnectSecretVault {
+    fn items_url(&self) -> Result<String> {
+        Ok(format!(
+            "{}/v1/vaults/{}/items",
+            self.host,
+            path_segment(&self.vault_id)?
+        ))
+    }
+
+    fn item_url(&self, id: &str) -> Result<String> {
+        Ok(format!("{}/{}", self.items_url()?, path_segment(id)?))
+    }
+
+    fn bearer(&self) -> String {
+        format!("Bearer {}", self.token)
+    }
+
+    /// Looks an item up by exact title. The title is quoted and escaped for
+    /// the Connect filter syntax, and results are re-checked against it.
+    fn find_item_id(&self, title: &str) -> Result<Option<String>> {
+        let url = self.items_url()?;
+        let escaped = title.replace('\\', "\\\\").replace('"', "\\\"");
+        let filter = format!("title eq \"{escaped}\"");
+        let (status, body) = send(|client| {
+            client
+                .get(&url)
+                .header("Authorization", self.bearer())
+                .query(&[("filter", filter.as_str())])
+        })?;
+        ensure_success(self.backend_name(), status, &body)?;
+        let items: Value = serde_json::from_str(&body).context("invalid 1Password response")?;
+        Ok(items
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|item| item["title"] == title)
+            .and_then(|item| item["id"].as_str())
+            .map(str::to_string))
+    }
+
+    fn title(profile_id: &str, key: &str) -> String {
+        format!("zeroclaw:{profile_id}:{key}")
+    }
+}
+
+impl SecretVault for OnePasswordConnectSecretVault {
+    fn backend_name(&self) -> &'static str {
+        "1password-connect"
+    }
+
+    fn set_secret(&self, profile_id: &str, key: &str, value: &str) -> Result<()> {
+        let title = Self::title(profile_id, key);
+        let existing = self.find_item_id(&title)?;
+        let mut item = json!({
+            "title": title,
+            "category": "PASSWORD",
+            "vault": { "id": self.vault_id },
+            "fields": [{
+                "id": "password",
+                "type": "CONCEALED",
+                "purpose": "PASSWORD",
+                "label": "password",
+                "value": value
+            }]
+        });
+
+        let (status, body) = if let Some(id) = existing {
+            let url = self.item_url(&id)?;
+            item["id"] = Value::String(id);
+            send(|client| {
+                client
+                    .put(&url)
+                    .header("Authorization", self.bearer())
+                    .json(&item)
+            })?
+        } else {
+            let url = self.items_url()?;
+            send(|client| {
+                client
+                    .post(&url)
+                    .header("Authorization", self.bearer())
+                    .json(&item)
+            })?
+        };
+        ensure_success(self.backend_name(), status, &body)
+    }
+
+    fn get_secret(&self, profile_id: &str, key: &str) -> Result<Option<String>> {
+        let Some(id) = self.find_item_id(&Self::title(profile_id, key))? else {
+            return Ok(None);
+        };
+        let url = self.item_url(&id)?;
+        let (status, body) =
+            send(|client| client.get(&url).header("Authorization", self.bearer()))?;
+        ensure_success(self.backend_name(), status, &body)?;
+        let item: Value = serde_json::from_str(&body).context("invalid 1Password response")?;
+        Ok(item["fields"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|field| field["purpose"] == "PASSWORD" || field["id"] == "password")
+            .and_then(|field| field["value"].as_str())
+            .map(str::to_string))
+    }
+
+    fn delete_secret(&self, profile_id: &str, key: &str) -> Result<()> {
+        let Some(id) = self.find_item_id(&Self::title(profile_id, key))? else {
+            return Ok(());
+        };
+        let url = self.item_url(&id)?;
+        let (status, body) =
+            send(|client| client.delete(&url).header("Authorization", self.bearer()))?;
+        ensure_success(self.backend_name(), status, &body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::EncryptedFileSecretVault;
+    use parking_lot::Mutex;
+    use std::io::{BufRead, BufReader, Read};
+    use std::net::{TcpListener, TcpStream};
+    use tempfile::TempDir;
+
+    #[derive(Debug, Clone)]
+    struct RecordedRequest {
+        method: String,
+        target: String,
+        headers: BTreeMap<String, String>,
+        body: String,
+    }
+
+    /// Minimal HTTP/1.1 server answering each request with `respond`.
+    fn mock_server(
+        respond: impl Fn(&RecordedRequest) -> (u16, String) + Send + 'static,
+    ) -> (String, Arc<Mutex<Vec<RecordedRequest>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&requests);
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let Some(request) = read_request(&stream) else {
+                    continue;
+                };
+                let (status, body) = respond(&request);
+                recorded.lock().push(request);
+                let _ = std::io::Write::write_all(
+                    &mut stream,
+                    format!(
+                        "HTTP/1.1 {status} Mock\r\nContent-Type: application/json\r\n\
+                         Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    )
+                    .as_bytes(),
+                );
+            }
+        });
+        (url, requests)
+    }
+
+    fn read_request(stream: &TcpStream) -> Option<RecordedRequest> {
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        reader.read_line(&mut line).ok()?;
+        let mut parts = line.split_whitespace();
+        let method = parts.next()?.to_string();
+        let target = parts.next()?.to_string();
+        let mut headers = BTreeMap::new();
+        loop {
+            let mut header = String::new();
+            reader.read_line(&mut header).ok()?;
+            let Some((name, value)) = header.trim_end().split_once(':') else {
+                break;
+            };
+            headers.insert(name.to_ascii_lowercase(), value.trim().to_string());
+        }
+        let length = headers
+            .get("content-length")
+            .and_then(|length| length.parse().ok())
+            .unwrap_or(0);
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).ok()?;
+        Some(RecordedRequest {
+            method,
+            target,
+            headers,
+            body: String::from_utf8_lossy(&body).into_owned(),
+        })
+    }
+
+    fn percent_decode(raw: &str) -> String {
+        let mut bytes = Vec::new();
+        let mut input = raw.bytes();
+        while let Some(byte) = input.next() {
+            match byte {
+                b'%' => {
+                    let hex: String = input.by_ref().take(2).map(char::from).collect();
+                    bytes.push(u8::from_str_radix(&hex, 16).unwrap());
+                }
+                b'+' => bytes.push(b' '),
+                other => bytes.push(other),
+            }
+        }
+        String::from_utf8(bytes).unwrap()
+    }
+
+    /// Not-found read, create, update, read, delete, and delete again.
+    fn exercise(vault: &dyn SecretVault, key: &str) {
+        assert_eq!(vault.get_secret("profile a", key).unwrap(), None);
+        vault.set_secret("profile a", key, "first").unwrap();
+        vault.set_secret("profile a", key, "second").unwrap();
+        assert_eq!(
+            vault.get_secret("profile a", key).unwrap(),
+            Some("second".into())
+        );
+        vault.delete_secret("profile a", key).unwrap();
+        assert_eq!(vault.get_secret("profile a", key).unwrap(), None);
+        vault.delete_secret("profile a", key).unwrap();
+    }
+
+    fn bootstrap(tmp: &TempDir, secrets: &[&str]) -> EncryptedFileSecretVault {
+        let vault = EncryptedFileSecretVault::new(tmp.path(), true).unwrap();
+        for id in secrets {
+            vault.set_secret("profile-a", id, "credential").unwrap();
+        }
+        vault
+    }
+
+    #[test]
+    fn sigv4_matches_reference_vector_and_config_needs_bootstrap() {
+        let mut headers = BTreeMap::new();
+        headers.insert("host".to_string(), "example.amazonaws.com".to_string());
+        headers.insert("x-amz-date".to_string(), "20150830T123600Z".to_string());
+        let authorization = sigv4_authorization(
+            "AKIDEXAMPLE",
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "us-east-1",
+            "service",
+            "GET",
+            "/",
+            &headers,
+            b"",
+        );
+        assert_eq!(
+            authorization,
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=host;x-amz-date, \
+             Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+
+        let tmp = TempDir::new().unwrap();
+        let bootstrap = EncryptedFileSecretVault::new(tmp.path(), true).unwrap();
+        let config: ExternalVaultConfig = serde_json::from_value(json!({
+            "backend": "hashicorp_vault",
+            "address": "https://vault.internal:8200/",
+            "token_secret_id": "vault_token"
+        }))
+        .unwrap();
+        assert!(open_external_vault(&config, &bootstrap, "profile-a").is_err());
+
+        bootstrap
+            .set_secret("profile-a", "vault_token", "hvs.test")
+            .unwrap();
+        let vault = open_external_vault(&config, &bootstrap, "profile-a").unwrap();
+        assert_eq!(vault.backend_name(), "hashicorp-vault");
+    }
+
+    #[test]
+    fn hashicorp_vault_round_trips_with_encoded_paths() {
+        let store = Mutex::new(BTreeMap::new());
+        let (address, requests) = mock_server(move |request| {
+            let path = request
+                .target
+                .split_once("/zeroclaw/")
+                .map(|(_, path)| path.to_string())
+                .unwrap_or_default();
+            let mut store = store.lock();
+            match request.method.as_str() {
+                "POST" => {
+                    let body: Value = serde_json::from_str(&request.body).unwrap();
+                    store.insert(path, body["data"]["value"].clone());
+                    (200, "{}".into())
+                }
+                "GET" => match store.get(&path) {
+                    Some(value) => (
+                        200,
+                        json!({ "data": { "data": { "value": value } } }).to_string(),
+                    ),
+                    None => (404, r#"{"errors":[]}"#.into()),
+                },
+                "DELETE" if store.remove(&path).is_some() => (204, String::new()),
+                _ => (404, r#"{"errors":[]}"#.into()),
+            }
+        });
+        let tmp = TempDir::new().unwrap();
+        let config: ExternalVaultConfig = serde_json::from_value(json!({
+            "backend": "hashicorp_vault",
+            "address": address,
+            "token_secret_id": "vault_token"
+        }))
+        .unwrap();
+        let vault =
+            open_external_vault(&config, &bootstrap(&tmp, &["vault_token"]), "profile-a").unwrap();
+
+        exercise(vault.as_ref(), "team/api key?");
+        assert!(vault.get_secret("profile a", "..").is_err());
+        let requests = requests.lock();
+        assert_eq!(
+            requests[1].target,
+            "/v1/secret/data/zeroclaw/profile%20a/team%2Fapi%20key%3F"
+        );
+        assert_eq!(requests[1].headers["x-vault-token"], "credential");
+        assert!(requests
+            .iter()
+            .any(|request| request.target.starts_with("/v1/secret/metadata/")));
+    }
+
+    #[test]
+    fn aws_secrets_manager_round_trips_against_an_endpoint() {
+        let store = Mutex::new(BTreeMap::new());
+        let (endpoint, requests) = mock_server(move |request| {
+            let body: Value = serde_json::from_str(&request.body).unwrap();
+            let name = body["SecretId"]
+                .as_str()
+                .or(body["Name"].as_str())
+                .unwrap()
+                .to_string();
+            let not_found = (400, r#"{"__type":"ResourceNotFoundException"}"#.to_string());
+            let mut store = store.lock();
+            match request.headers["x-amz-target"].as_str() {
+                "secretsmanager.CreateSecret" => {
+                    store.insert(name, body["SecretString"].clone());
+                    (200, "{}".into())
+                }
+                "secretsmanager.PutSecretValue" if store.contains_key(&name) => {
+                    store.insert(name, body["SecretString"].clone());
+                    (200, "{}".into())
+                }
+                "secretsmanager.GetSecretValue" => match store.get(&name) {
+                    Some(value) => (200, json!({ "SecretString": value }).to_string()),
+                    None => not_found,
+                },
+                "secretsmanager.DeleteSecret" if store.remove(&name).is_some() => {
+                    (200, "{}".into())
+                }
+                _ => not_found,
+            }
+        });
+        let tmp = TempDir::new().unwrap();
+        let config: ExternalVaultConfig = serde_json::from_value(json!({
+            "backend": "aws_secrets_manager",
+            "region": "eu-west-1",
+            "endpoint": endpoint,
+            "access_key_id_secret_id": "aws_key_id",
+            "secret_access_key_secret_id": "aws_secret"
+        }))
+        .unwrap();
+        let vault = open_external_vault(
+            &config,
+            &bootstrap(&tmp, &["aws_key_id", "aws_secret"]),
+            "profile-a",
+        )
+        .unwrap();
+
+        exercise(vault.as_ref(), "openrouter_api_key");
+        let requests = requests.lock();
+        assert!(requests[0].headers["authorization"]
+            .starts_with("AWS4-HMAC-SHA256 Credential=credential/"));
+        assert!(requests[0]
+            .body
+            .contains("zeroclaw/profile a/openrouter_api_key"));
+    }
+
+    #[test]
+    fn one_password_escapes_titles_and_round_trips() {
+        let item: Mutex<Option<(String, Value)>> = Mutex::new(None);
+        let (host, requests) = mock_server(move |request| {
+            let mut item = item.lock();
+            let (path, query) = request
+                .target
+                .split_once('?')
+                .unwrap_or((request.target.as_str(), ""));
+            match (request.method.as_str(), path) {
+                ("GET", "/v1/vaults/vault%201/items") => {
+                    let filter = percent_decode(query.trim_start_matches("filter="));
+                    let found = item.as_ref().filter(|(title, _)| {
+                        let escaped = title.replace('\\', "\\\\").replace('"', "\\\"");
+                        filter == format!("title eq \"{escaped}\"")
+                    });
+                    let listed: Vec<Value> = found
+                        .map(|(title, _)| json!({ "id": "item-1", "title": title }))
+                        .into_iter()
+                        .collect();
+                    (200, Value::from(listed).to_string())
+                }
+                ("POST", "/v1/vaults/vault%201/items")
+                | ("PUT", "/v1/vaults/vault%201/items/item-1") => {
+                    let body: Value = serde_json::from_str(&request.body).unwrap();
+                    *item = Some((body["title"].as_str().unwrap().to_string(), body));
+                    (200, "{}".into())
+                }
+                ("GET", "/v1/vaults/vault%201/items/item-1") => match item.as_ref() {
+                    Some((_, body)) => (200, body.to_string()),
+                    None => (404, "{}".into()),
+                },
+                ("DELETE", "/v1/vaults/vault%201/items/item-1") => {
+                    *item = None;
+                    (204, String::new())
+                }
+                _ => (500, "unexpected request".into()),
+            }
+        });
+        let tmp = TempDir::new().unwrap();
+        let config: ExternalVaultConfig = serde_json::from_value(json!({
+            "backend": "one_password_connect",
+            "host": host,
+            "vault_id": "vault 1",
+            "token_secret_id": "op_token"
+        }))
+        .unwrap();
+        let vault =
+            open_external_vault(&config, &bootstrap(&tmp, &["op_token"]), "profile-a").unwrap();
+
+        exercise(vault.as_ref(), "say \"hi\" \\ bye");
+        let requests = requests.lock();
+        assert_eq!(
+            percent_decode(requests[0].target.split_once("filter=").unwrap().1),
+            r#"title eq "zeroclaw:profile a:say \"hi\" \\ bye""#
+        );
+        assert!(requests.iter().any(|request| request.method == "PUT"));
+        assert!(requests
+            .iter()
+            .all(|request| request.headers["authorization"] == "Bearer credential"));
+        drop(requests);
+
+        vault.set_secret("profile a", "key", "value").unwrap();
+        assert!(vault.get_secret("profile a", "other").unwrap().is_none());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::integrations::IntegrationPermissionContract;
+use crate::recovery::{read_json_or_recover, write_json_with_backup};
 use anyhow::{Context, Result};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
@@ -49,28 +50,11 @@ impl SkillsRegistryStore {
     }
 
     pub fn load(&self) -> Result<SkillsRegistry> {
-        if !self.path.exists() {
-            return Ok(SkillsRegistry::default());
-        }
-
-        let body = fs::read_to_string(&self.path)
-            .with_context(|| format!("failed to read {}", self.path.display()))?;
-        serde_json::from_str(&body).context("failed to parse skills registry")
+        read_json_or_recover(&self.path, "skills registry")
     }
 
     fn save(&self, registry: &SkillsRegistry) -> Result<()> {
-        if let Some(parent) = self.path.parent() {
-            fs::create_dir_all(parent)
-                .with_context(|| format!("failed to create {}", parent.display()))?;
-        }
-
-        let body = serde_json::to_string_pretty(registry)
-            .context("failed to serialize skills registry")?;
-        let tmp = self.path.with_extension("json.tmp");
-        fs::write(&tmp, body).with_context(|| format!("failed to write {}", tmp.display()))?;
-        fs::rename(&tmp, &self.path)
-            .with_context(|| format!("failed to replace {}", self.path.display()))?;
-        Ok(())
+        write_json_with_backup(&self.path, registry, "skills registry")
     }
 
     pub fn install(&self, request: SkillInstallRequest) -> Result<SkillRecord> {
@@ -0,0 +1,198 @@
+use crate::recovery::{read_json_or_recover, write_json_with_backup};
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum TaggedEntityKind {
+    Receipt,
+    Outcome,
+    Skill,
+    Integration,
+    McpConnector,
+    WorkflowTask,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TagAssignment {
+    pub kind: TaggedEntityKind,
+    pub entity_id: String,
+    pub tags: BTreeSet<String>,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TagIndex {
+    pub assignments: Vec<TagAssignment>,
+}
+
+/// Workspace-wide labels for any entity the shell shows, so one search can
+/// pull together receipts, skills, integrations and tasks for a review.
+#[derive(Debug, Clone)]
+pub struct TagStore {
+    path: PathBuf,
+}
+
+impl TagStore {
+    pub fn for_workspace(workspace_dir: &Path) -> Self {
+        Self {
+            path: workspace_dir.join("tags.json"),
+        }
+    }
+
+    pub fn load(&self) -> Result<TagIndex> {
+        read_json_or_recover(&self.path, "tag index")
+    }
+
+    fn save(&self, index: &TagIndex) -> Result<()> {
+        write_json_with_backup(&self.path, index, "tag index")
+    }
+
+    pub fn add_tags(
+        &self,
+        kind: TaggedEntityKind,
+        entity_id: &str,
+        tags: &[String],
+    ) -> Result<TagAssignment> {
+        if entity_id.trim().is_empty() {
+            anyhow::bail!("entity_id must not be empty");
+        }
+        let tags = tags
+            .iter()
+            .map(|tag| normalize_tag(tag))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut index = self.load()?;
+        let now = Utc::now().to_rfc3339();
+        let assignment = if let Some(existing) = index
+            .assignments
+            .iter_mut()
+            .find(|assignment| assignment.kind == kind && assignment.entity_id == entity_id)
+        {
+            existing.tags.extend(tags);
+            existing.updated_at = now;
+            existing.clone()
+        } else {
+            let assignment = TagAssignment {
+                kind,
+                entity_id: entity_id.to_string(),
+                tags: tags.into_iter().collect(),
+                updated_at: now,
+            };
+            index.assignments.push(assignment.clone());
+            assignment
+        };
+
+        self.save(&index)?;
+        Ok(assignment)
+    }
+
+    pub fn remove_tag(&self, kind: TaggedEntityKind, entity_id: &str, tag: &str) -> Result<()> {
+        let tag = normalize_tag(tag)?;
+        let mut index = self.load()?;
+        for assignment in &mut index.assignments {
+            if assignment.kind == kind && assignment.entity_id == entity_id {
+                assignment.tags.remove(&tag);
+                assignment.updated_at = Utc::now().to_rfc3339();
+            }
+        }
+        index
+            .assignments
+            .retain(|assignment| !assignment.tags.is_empty());
+        self.save(&index)
+    }
+
+    pub fn tags_for(&self, kind: TaggedEntityKind, entity_id: &str) -> Result<BTreeSet<String>> {
+        Ok(self
+            .load()?
+            .assignments
+            .into_iter()
+            .find(|assignment| assignment.kind == kind && assignment.entity_id == entity_id)
+            .map(|assignment| assignment.tags)
+            .unwrap_or_default())
+    }
+
+    /// Returns every entity carrying `tag`, optionally narrowed to one kind,
+    /// grouped by kind.
+    pub fn search(&self, tag: &str, kind: Option<TaggedEntityKind>) -> Result<Vec<TagAssignment>> {
+        let tag = normalize_tag(tag)?;
+        let mut hits: Vec<TagAssignment> = self
+            .load()?
+            .assignments
+            .into_iter()
+            .filter(|assignment| kind.is_none_or(|kind| assignment.kind == kind))
+            .filter(|assignment| assignment.tags.contains(&tag))
+            .collect();
+        hits.sort_by(|a, b| (a.kind, &a.entity_id).cmp(&(b.kind, &b.entity_id)));
+        Ok(hits)
+    }
+}
+
+fn normalize_tag(tag: &str) -> Result<String> {
+    let tag = tag.trim().to_ascii_lowercase();
+    if tag.is_empty() {
+        anyhow::bail!("tag must not be empty");
+    }
+    if tag
+        .chars()
+        .any(|ch| !(ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | ':' | '/' | '.')))
+    {
+        anyhow::bail!("tag '{tag}' contains invalid characters");
+    }
+    Ok(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn search_spans_entity_kinds() {
+        let tmp = TempDir::new().unwrap();
+        let store = TagStore::for_workspace(tmp.path());
+
+        store
+            .add_tags(
+                TaggedEntityKind::Skill,
+                "invoice-parser",
+                &["Customer-X".into()],
+            )
+            .unwrap();
+        store
+            .add_tags(
+                TaggedEntityKind::Receipt,
+                "rcpt-1",
+                &["customer-x".into(), "q3".into()],
+            )
+            .unwrap();
+        store
+            .add_tags(TaggedEntityKind::Integration, "slack", &["internal".into()])
+            .unwrap();
+        assert!(store
+            .add_tags(TaggedEntityKind::Skill, "x", &["bad tag".into()])
+            .is_err());
+
+        let hits = store.search("customer-x", None).unwrap();
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].kind, TaggedEntityKind::Receipt);
+        assert_eq!(hits[1].entity_id, "invoice-parser");
+
+        let skills = store
+            .search("customer-x", Some(TaggedEntityKind::Skill))
+            .unwrap();
+        assert_eq!(skills.len(), 1);
+
+        store
+            .remove_tag(TaggedEntityKind::Skill, "invoice-parser", "customer-x")
+            .unwrap();
+        assert_eq!(store.search("customer-x", None).unwrap().len(), 1);
+        assert!(store
+            .tags_for(TaggedEntityKind::Skill, "invoice-parser")
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,333 @@
+use crate::control_plane::{ActionPolicyDecision, ActionPolicyRequest, ControlPlaneStore};
+use crate::recovery::{read_json_or_recover, write_json_with_backup};
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum GrantCategory {
+    Network,
+    Filesystem,
+    Provider,
+}
+
+impl GrantCategory {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Network => "network",
+            Self::Filesystem => "filesystem",
+            Self::Provider => "provider",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum GrantOwnerKind {
+    Skill,
+    McpConnector,
+}
+
+impl GrantOwnerKind {
+    fn grant_action(self) -> &'static str {
+        match self {
+            Self::Skill => "skills.grant",
+            Self::McpConnector => "mcp.grant",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ToolGrant {
+    pub owner_kind: GrantOwnerKind,
+    pub owner_id: String,
+    pub category: GrantCategory,
+    pub granted_by: String,
+    pub granted_at: String,
+    pub receipt_id: String,
+    #[serde(default)]
+    pub revoked_at: Option<String>,
+}
+
+impl ToolGrant {
+    fn is_active(&self) -> bool {
+        self.revoked_at.is_none()
+    }
+
+    fn covers(&self, kind: GrantOwnerKind, owner_id: &str, category: GrantCategory) -> bool {
+        self.owner_kind == kind && self.owner_id == owner_id && self.category == category
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ToolGrantRequest {
+    pub owner_kind: GrantOwnerKind,
+    pub owner_id: String,
+    pub category: GrantCategory,
+    pub actor_id: String,
+    pub actor_role: String,
+    #[serde(default)]
+    pub approval_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolGrantCheck {
+    Granted,
+    /// First use of this category: the user has to be asked before the
+    /// skill or connector may proceed.
+    PromptRequired,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ToolGrantRegistry {
+    pub grants: Vec<ToolGrant>,
+}
+
+/// Per-skill and per-connector consent for each destination category, asked
+/// for at first use instead of granting everything when the skill is enabled.
+#[derive(Debug, Clone)]
+pub struct ToolGrantStore {
+    path: PathBuf,
+}
+
+impl ToolGrantStore {
+    pub fn for_workspace(workspace_dir: &Path) -> Self {
+        Self {
+            path: workspace_dir.join("tool_grants.json"),
+        }
+    }
+
+    pub fn load(&self) -> Result<ToolGrantRegistry> {
+        read_json_or_recover(&self.path, "tool grants")
+    }
+
+    fn save(&self, registry: &ToolGrantRegistry) -> Result<()> {
+        write_json_with_backup(&self.path, registry, "tool grants")
+    }
+
+    pub fn check(
+        &self,
+        kind: GrantOwnerKind,
+        owner_id: &str,
+        category: GrantCategory,
+    ) -> Result<ToolGrantCheck> {
+        let granted = self
+            .load()?
+            .grants
+            .iter()
+            .any(|grant| grant.is_active() && grant.covers(kind, owner_id, category));
+        Ok(if granted {
+            ToolGrantCheck::Granted
+        } else {
+            ToolGrantCheck::PromptRequired
+        })
+    }
+
+    /// Records the user's answer to a first-use prompt. The grant itself is
+    /// policy-gated, so roles that may not approve it get a denial or an
+    /// approval request back and nothing is stored.
+    pub fn grant(
+        &self,
+        control_plane: &ControlPlaneStore,
+        request: ToolGrantRequest,
+    ) -> Result<ActionPolicyDecision> {
+        if request.owner_id.trim().is_empty() {
+            anyhow::bail!("owner_id must not be empty");
+        }
+
+        let mut context = BTreeMap::new();
+        context.insert(
+            "category".into(),
+            Value::String(request.category.as_str().into()),
+        );
+        let decision = control_plane.evaluate_action(ActionPolicyRequest {
+            actor_id: request.actor_id.clone(),
+            actor_role: request.actor_role.clone(),
+            action: request.owner_kind.grant_action().into(),
+            resource: request.owner_id.clone(),
+            destination: request.category.as_str().into(),
+            approval_id: request.approval_id.clone(),
+            occurred_at: None,
+            authenticated_at: None,
+            on_behalf_of: None,
+            context,
+        })?;
+        if !decision.allowed {
+            return Ok(decision);
+        }
+
+        let mut registry = self.load()?;
+        let now = Utc::now().to_rfc3339();
+        if let Some(existing) = registry.grants.iter_mut().find(|grant| {
+            grant.is_active()
+                && grant.covers(request.owner_kind, &request.owner_id, request.category)
+        }) {
+            existing.granted_by = request.actor_id;
+            existing.granted_at = now;
+            existing.receipt_id.clone_from(&decision.receipt_id);
+        } else {
+            registry.grants.push(ToolGrant {
+                owner_kind: request.owner_kind,
+                owner_id: request.owner_id,
+                category: request.category,
+                granted_by: request.actor_id,
+                granted_at: now,
+                receipt_id: decision.receipt_id.clone(),
+                revoked_at: None,
+            });
+        }
+        self.save(&registry)?;
+        Ok(decision)
+    }
+
+    /// Revokes the grants of one skill or connector, either for a single
+    /// category or all of them. Returns how many grants were revoked.
+    pub fn revoke(
+        &self,
+        kind: GrantOwnerKind,
+        owner_id: &str,
+        category: Option<GrantCategory>,
+    ) -> Result<usize> {
+        let mut registry = self.load()?;
+        let now = Utc::now().to_rfc3339();
+        let mut revoked = 0;
+        for grant in &mut registry.grants {
+            if grant.is_active()
+                && grant.owner_kind == kind
+                && grant.owner_id == owner_id
+                && category.is_none_or(|category| grant.category == category)
+            {
+                grant.revoked_at = Some(now.clone());
+                revoked += 1;
+            }
+        }
+        if revoked > 0 {
+            self.save(&registry)?;
+        }
+        Ok(revoked)
+    }
+
+    pub fn list(&self, include_revoked: bool) -> Result<Vec<ToolGrant>> {
+        let mut grants: Vec<ToolGrant> = self
+            .load()?
+            .grants
+            .into_iter()
+            .filter(|grant| include_revoked || grant.is_active())
+            .collect();
+        grants.sort_by(|a, b| {
+            (a.owner_kind, &a.owner_id, a.category).cmp(&(b.owner_kind, &b.owner_id, b.category))
+        });
+        Ok(grants)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn grant_request(role: &str, category: GrantCategory) -> ToolGrantRequest {
+        ToolGrantRequest {
+            owner_kind: GrantOwnerKind::Skill,
+            owner_id: "invoice-parser".into(),
+            category,
+            actor_id: format!("{role}-a"),
+            actor_role: role.into(),
+            approval_id: None,
+        }
+    }
+
+    #[test]
+    fn first_use_grants_are_policy_gated_and_revocable() {
+        let tmp = TempDir::new().unwrap();
+        let control_plane = ControlPlaneStore::for_workspace(tmp.path());
+        let grants = ToolGrantStore::for_workspace(tmp.path());
+
+        assert_eq!(
+            grants
+                .check(
+                    GrantOwnerKind::Skill,
+                    "invoice-parser",
+                    GrantCategory::Network
+                )
+                .unwrap(),
+            ToolGrantCheck::PromptRequired
+        );
+
+        let viewer = grants
+            .grant(
+                &control_plane,
+                grant_request("viewer", GrantCategory::Network),
+            )
+            .unwrap();
+        assert!(!viewer.allowed);
+        let operator = grants
+            .grant(
+                &control_plane,
+                grant_request("operator", GrantCategory::Network),
+            )
+            .unwrap();
+        assert!(operator.requires_approval);
+        assert!(grants.list(true).unwrap().is_empty());
+
+        let owner = grants
+            .grant(
+                &control_plane,
+                grant_request("owner", GrantCategory::Network),
+            )
+            .unwrap();
+        assert!(owner.allowed);
+        grants
+            .grant(
+                &control_plane,
+                grant_request("owner", GrantCategory::Filesystem),
+            )
+            .unwrap();
+        assert_eq!(
+            grants
+                .check(
+                    GrantOwnerKind::Skill,
+                    "invoice-parser",
+                    GrantCategory::Network
+                )
+                .unwrap(),
+            ToolGrantCheck::Granted
+        );
+        assert_eq!(
+            grants
+                .check(
+                    GrantOwnerKind::Skill,
+                    "invoice-parser",
+                    GrantCategory::Provider
+                )
+                .unwrap(),
+            ToolGrantCheck::PromptRequired
+        );
+
+        let revoked = grants
+            .revoke(
+                GrantOwnerKind::Skill,
+                "invoice-parser",
+                Some(GrantCategory::Network),
+            )
+            .unwrap();
+        assert_eq!(revoked, 1);
+        assert_eq!(grants.list(false).unwrap().len(), 1);
+        assert_eq!(grants.list(true).unwrap().len(), 2);
+        assert_eq!(
+            grants
+                .check(
+                    GrantOwnerKind::Skill,
+                    "invoice-parser",
+                    GrantCategory::Network
+                )
+                .unwrap(),
+            ToolGrantCheck::PromptRequired
+        );
+    }
+}
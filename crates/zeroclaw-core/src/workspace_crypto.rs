@@ -0,0 +1,366 @@
+use crate::control_plane::RECEIPT_JOURNAL_EXTENSION;
+use crate::secrets::SecretVault;
+use anyhow::{Context, Result};
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng, Payload};
+use chacha20poly1305::{AeadCore, ChaCha20Poly1305, Key, Nonce};
+use parking_lot::Mutex;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Vault entry holding the per-profile workspace data key.
+pub const WORKSPACE_DATA_KEY_ID: &str = "workspace_data_key";
+
+const SEALED_PREFIX: &str = "zcws1:";
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+/// Encrypts workspace state files at rest with a per-profile data key.
+///
+/// The data key never touches the workspace: it is generated on first use and
+/// kept in the [`SecretVault`], so copying the workspace directory alone does
+/// not expose its contents. Each sealed file is bound to its file name, which
+/// stops an attacker from swapping one encrypted state file for another.
+pub struct WorkspaceCipher {
+    cipher: ChaCha20Poly1305,
+    accept_plaintext: bool,
+}
+
+impl std::fmt::Debug for WorkspaceCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorkspaceCipher").finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EncryptionMigrationReport {
+    pub encrypted: Vec<String>,
+    pub already_encrypted: Vec<String>,
+    pub missing: Vec<String>,
+}
+
+impl WorkspaceCipher {
+    /// Loads the profile data key from the vault, creating it on first use.
+    /// Creation is serialized and the key is read back from the vault, so
+    /// concurrent first uses all end up with the key that was stored.
+    pub fn from_vault(vault: &dyn SecretVault, profile_id: &str) -> Result<Self> {
+        let encoded = if let Some(encoded) = vault.get_secret(profile_id, WORKSPACE_DATA_KEY_ID)? {
+            encoded
+        } else {
+            create_data_key(vault, profile_id)?
+        };
+        let key = base64::engine::general_purpose::STANDARD
+            .decode(encoded.trim())
+            .context("workspace data key is not valid base64")?;
+
+        if key.len() != KEY_LEN {
+            anyhow::bail!("workspace data key has invalid length {}", key.len());
+        }
+        Ok(Self::from_key(&key))
+    }
+
+    pub(crate) fn from_key(key: &[u8]) -> Self {
+        Self {
+            cipher: ChaCha20Poly1305::new(Key::from_slice(key)),
+            accept_plaintext: false,
+        }
+    }
+
+    /// Lets [`WorkspaceCipher::read_to_string`] pass unsealed files through
+    /// while a workspace is being migrated. Without it a plaintext file is
+    /// rejected, so it cannot be swapped in for a sealed one.
+    #[must_use]
+    pub fn accepting_plaintext(mut self) -> Self {
+        self.accept_plaintext = true;
+        self
+    }
+
+    pub fn is_sealed(body: &str) -> bool {
+        body.starts_with(SEALED_PREFIX)
+    }
+
+    /// Encrypts `plaintext` for the state file called `label`.
+    pub fn seal(&self, label: &str, plaintext: &[u8]) -> Result<String> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad: label.as_bytes(),
+                },
+            )
+            .map_err(|error| anyhow::anyhow!("workspace encryption failed: {error}"))?;
+
+        let mut blob = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        blob.extend_from_slice(&nonce);
+        blob.extend_from_slice(&ciphertext);
+        Ok(format!(
+            "{SEALED_PREFIX}{}",
+            base64::engine::general_purpose::STANDARD.encode(blob)
+        ))
+    }
+
+    /// Decrypts a body produced by [`WorkspaceCipher::seal`] for `label`.
+    pub fn open(&self, label: &str, body: &str) -> Result<Vec<u8>> {
+        let Some(encoded) = body.trim().strip_prefix(SEALED_PREFIX) else {
+            anyhow::bail!("'{label}' is not an encrypted workspace file");
+        };
+        let blob = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .with_context(|| format!("'{label}' has a corrupt encrypted payload"))?;
+        if blob.len() <= NONCE_LEN {
+            anyhow::bail!("'{label}' encrypted payload is truncated");
+        }
+
+        let (nonce, ciphertext) = blob.split_at(NONCE_LEN);
+        self.cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: label.as_bytes(),
+                },
+            )
+            .map_err(|_| anyhow::anyhow!("failed to decrypt '{label}': wrong key or tampered data"))
+    }
+
+    /// Reads and decrypts a sealed state file. Plaintext is an error unless
+    /// the cipher was built with [`WorkspaceCipher::accepting_plaintext`].
+    pub fn read_to_string(&self, path: &Path) -> Result<String> {
+        let body = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        self.open_to_string(&file_label(path), body)
+            .with_context(|| format!("failed to open {}", path.display()))
+    }
+
+    /// Decrypts a sealed text body for `label`, passing plaintext through
+    /// only when the cipher accepts it.
+    pub fn open_to_string(&self, label: &str, body: String) -> Result<String> {
+        if !Self::is_sealed(&body) {
+            if self.accept_plaintext {
+                return Ok(body);
+            }
+            anyhow::bail!("'{label}' is not encrypted; migrate the workspace before opening it");
+        }
+        let plaintext = self.open(label, &body)?;
+        String::from_utf8(plaintext)
+            .with_context(|| format!("'{label}' decrypted to invalid UTF-8"))
+    }
+
+    /// Seals `contents` and atomically replaces `path`.
+    pub fn write(&self, path: &Path, contents: &str) -> Result<()> {
+        let sealed = self.seal(&file_label(path), contents.as_bytes())?;
+        let tmp = path.with_extension("enc.tmp");
+        fs::write(&tmp, sealed).with_context(|| format!("failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, path).with_context(|| format!("failed to replace {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Encrypts the named plaintext state files of an existing workspace in
+    /// place. Files that are already sealed or absent are reported and skipped.
+    /// A file's receipt journal is sealed line by line along with it, so
+    /// receipts not yet folded into the state survive the migration.
+    pub fn migrate_workspace(
+        &self,
+        workspace_dir: &Path,
+        file_names: &[&str],
+    ) -> Result<EncryptionMigrationReport> {
+        let mut report = EncryptionMigrationReport::default();
+        for name in file_names {
+            let path = workspace_dir.join(name);
+            let journal = path.with_extension(RECEIPT_JOURNAL_EXTENSION);
+            if journal.exists() && self.seal_lines(&journal)? {
+                report.encrypted.push(file_label(&journal));
+            }
+            if !path.exists() {
+                report.missing.push((*name).to_string());
+                continue;
+            }
+
+            let body = fs::read_to_string(&path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            if Self::is_sealed(&body) {
+                report.already_encrypted.push((*name).to_string());
+                continue;
+            }
+
+            self.write(&path, &body)?;
+            report.encrypted.push((*name).to_string());
+        }
+        Ok(report)
+    }
+
+    /// Seals every plaintext line of an append-only journal with the
+    /// journal's own label. Returns whether anything changed.
+    fn seal_lines(&self, path: &Path) -> Result<bool> {
+        let body = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let label = file_label(path);
+        let mut sealed = String::with_capacity(body.len());
+        let mut changed = false;
+        for line in body.lines().filter(|line| !line.trim().is_empty()) {
+            if Self::is_sealed(line) {
+                sealed.push_str(line);
+            } else {
+                sealed.push_str(&self.seal(&label, line.as_bytes())?);
+                changed = true;
+            }
+            sealed.push('\n');
+        }
+        if changed {
+            let tmp = path.with_extension("jsonl.tmp");
+            fs::write(&tmp, sealed)
+                .with_context(|| format!("failed to write {}", tmp.display()))?;
+            fs::rename(&tmp, path)
+                .with_context(|| format!("failed to replace {}", path.display()))?;
+        }
+        Ok(changed)
+    }
+}
+
+fn create_data_key(vault: &dyn SecretVault, profile_id: &str) -> Result<String> {
+    static KEY_CREATION: OnceLock<Mutex<()>> = OnceLock::new();
+    let _guard = KEY_CREATION.get_or_init(Mutex::default).lock();
+    if vault
+        .get_secret(profile_id, WORKSPACE_DATA_KEY_ID)?
+        .is_none()
+    {
+        let mut key = vec![0_u8; KEY_LEN];
+        rand::rng().fill_bytes(&mut key);
+        let encoded = base64::engine::general_purpose::STANDARD.encode(&key);
+        vault
+            .set_secret(profile_id, WORKSPACE_DATA_KEY_ID, &encoded)
+            .context("failed to store workspace data key")?;
+    }
+    vault
+        .get_secret(profile_id, WORKSPACE_DATA_KEY_ID)?
+        .context("workspace data key was not stored")
+}
+
+pub(crate) fn file_label(path: &Path) -> String {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control_plane::tests::request;
+    use crate::control_plane::{ControlPlaneStore, JsonFileControlPlaneBackend};
+    use crate::secrets::EncryptedFileSecretVault;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    #[test]
+    fn encrypted_control_plane_roundtrip_and_migration() {
+        let tmp = TempDir::new().unwrap();
+        let vault = EncryptedFileSecretVault::new(tmp.path().join("secrets"), true).unwrap();
+        let workspace = tmp.path().join("workspace");
+
+        let plain = ControlPlaneStore::for_workspace(&workspace);
+        plain.start_trial().unwrap();
+        let state_path = workspace.join("control_plane.json");
+        assert!(fs::read_to_string(&state_path)
+            .unwrap()
+            .contains("owner-full-access"));
+
+        let cipher = Arc::new(WorkspaceCipher::from_vault(&vault, "profile-a").unwrap());
+        let report = cipher
+            .migrate_workspace(&workspace, &["control_plane.json", "rbac.json"])
+            .unwrap();
+        assert_eq!(report.encrypted, vec!["control_plane.json".to_string()]);
+        assert_eq!(report.missing, vec!["rbac.json".to_string()]);
+
+        let on_disk = fs::read_to_string(&state_path).unwrap();
+        assert!(WorkspaceCipher::is_sealed(&on_disk));
+        assert!(!on_disk.contains("owner-full-access"));
+
+        let reopened = WorkspaceCipher::from_vault(&vault, "profile-a").unwrap();
+        let store = ControlPlaneStore::with_backend(Arc::new(
+            JsonFileControlPlaneBackend::for_workspace(&workspace).with_cipher(Arc::new(reopened)),
+        ));
+        assert!(!store.get_state().unwrap().policy_rules.is_empty());
+        assert!(plain.get_state().is_err());
+
+        let forged = workspace.join("forged.json");
+        fs::write(&forged, "{}").unwrap();
+        let cipher = WorkspaceCipher::from_vault(&vault, "profile-a").unwrap();
+        assert!(cipher.read_to_string(&forged).is_err());
+        assert_eq!(
+            cipher
+                .accepting_plaintext()
+                .read_to_string(&forged)
+                .unwrap(),
+            "{}"
+        );
+    }
+
+    #[test]
+    fn migration_keeps_journaled_receipts() {
+        let tmp = TempDir::new().unwrap();
+        let vault = EncryptedFileSecretVault::new(tmp.path().join("secrets"), true).unwrap();
+        let workspace = tmp.path().join("workspace");
+        let store = ControlPlaneStore::for_workspace(&workspace)
+            .with_decision_cache(std::time::Duration::from_secs(30));
+        for _ in 0..3 {
+            store
+                .evaluate_action(request("operator", "runtime.start", "agent", "local"))
+                .unwrap();
+        }
+
+        let cipher = Arc::new(WorkspaceCipher::from_vault(&vault, "profile-a").unwrap());
+        let report = cipher
+            .migrate_workspace(&workspace, &["control_plane.json"])
+            .unwrap();
+        assert_eq!(
+            report.encrypted,
+            vec!["control_plane.receipts.jsonl", "control_plane.json"]
+        );
+        let journal = fs::read_to_string(workspace.join("control_plane.receipts.jsonl")).unwrap();
+        assert!(journal.lines().all(WorkspaceCipher::is_sealed));
+
+        let reopened = ControlPlaneStore::with_backend(Arc::new(
+            JsonFileControlPlaneBackend::for_workspace(&workspace).with_cipher(cipher),
+        ));
+        assert_eq!(reopened.list_receipts(10).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn concurrent_first_use_shares_one_data_key() {
+        let tmp = TempDir::new().unwrap();
+        let vault = Arc::new(EncryptedFileSecretVault::new(tmp.path(), true).unwrap());
+        let ciphers: Vec<WorkspaceCipher> = (0..4)
+            .map(|_| {
+                let vault = Arc::clone(&vault);
+                std::thread::spawn(move || {
+                    WorkspaceCipher::from_vault(vault.as_ref(), "profile-a").unwrap()
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|worker| worker.join().unwrap())
+            .collect();
+        let sealed = ciphers[0].seal("control_plane.json", b"{}").unwrap();
+        for cipher in &ciphers {
+            assert!(cipher.open("control_plane.json", &sealed).is_ok());
+        }
+    }
+
+    #[test]
+    fn sealed_payload_is_bound_to_file_name() {
+        let tmp = TempDir::new().unwrap();
+        let vault = EncryptedFileSecretVault::new(tmp.path(), true).unwrap();
+        let cipher = WorkspaceCipher::from_vault(&vault, "profile-a").unwrap();
+
+        let sealed = cipher.seal("control_plane.json", b"{}").unwrap();
+        assert_eq!(cipher.open("control_plane.json", &sealed).unwrap(), b"{}");
+        assert!(cipher.open("billing.json", &sealed).is_err());
+    }
+}
@@ -5,7 +5,7 @@ use crate::agent::memory_loader::{DefaultMemoryLoader, MemoryLoader};
 use crate::agent::prompt::{PromptContext, SystemPromptBuilder};
 use crate::config::Config;
 use crate::memory::{self, Memory, MemoryCategory};
-use crate::observability::{self, Observer, ObserverEvent};
+use crate::observability::{self, MultiObserver, Observer, ObserverEvent};
 use crate::providers::{self, ChatMessage, ChatRequest, ConversationMessage, Provider};
 use crate::runtime;
 use crate::security::SecurityPolicy;
@@ -230,6 +230,30 @@ impl Agent {
         self.history.clear();
     }
 
+    /// Updates the sampling temperature for subsequent turns.
+    pub fn set_temperature(&mut self, temperature: f64) {
+        self.temperature = temperature;
+    }
+
+    /// Replaces the loop limits read on every turn. The tool dispatcher was
+    /// chosen at build time and is not swapped by this call.
+    pub fn set_agent_config(&mut self, config: crate::config::AgentConfig) {
+        self.config = config;
+    }
+
+    pub fn set_skills_prompt_mode(&mut self, mode: crate::config::SkillsPromptInjectionMode) {
+        self.skills_prompt_mode = mode;
+    }
+
+    /// Fans events out to `observer` in addition to the configured backend.
+    pub fn add_observer(&mut self, observer: Arc<dyn Observer>) {
+        let current = Arc::clone(&self.observer);
+        self.observer = Arc::new(MultiObserver::new(vec![
+            Box::new(current),
+            Box::new(observer),
+        ]));
+    }
+
     pub fn from_config(config: &Config) -> Result<Self> {
         let observer: Arc<dyn Observer> =
             Arc::from(observability::create_observer(&config.observability));
@@ -0,0 +1,254 @@
+//! Declarative channel provisioning.
+//!
+//! A manifest lists the channels a deployment should have. Applying it diffs
+//! the list against `[channels_config]` and adds, updates or (with `prune`)
+//! removes channels, reporting the outcome per channel.
+
+use crate::config::{ChannelsConfig, Config};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// `[channels_config]` keys a manifest may provision.
+const MANIFEST_CHANNEL_KEYS: &[&str] = &[
+    "telegram",
+    "discord",
+    "slack",
+    "mattermost",
+    "webhook",
+    "imessage",
+    "matrix",
+    "signal",
+    "whatsapp",
+    "linq",
+    "nextcloud_talk",
+    "email",
+    "irc",
+    "lark",
+    "dingtalk",
+    "qq",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChannelManifest {
+    /// Remove configured channels that the manifest does not list.
+    #[serde(default)]
+    pub prune: bool,
+    #[serde(default)]
+    pub channels: Vec<ChannelManifestEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelManifestEntry {
+    /// Channel type, e.g. `telegram` or `nextcloud-talk`.
+    #[serde(rename = "type")]
+    pub channel_type: String,
+    /// The channel's config table, as in `[channels_config.<type>]`.
+    #[serde(default)]
+    pub config: Value,
+    /// Identities bound to the channel's allowlist; replaces `allowed_users`.
+    #[serde(default)]
+    pub allowed_users: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelChange {
+    Add,
+    Update,
+    Remove,
+    Unchanged,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ChannelApplyResult {
+    pub channel: String,
+    pub change: ChannelChange,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Reads a manifest from a `.toml` file, or JSON otherwise.
+pub fn load_channel_manifest(path: &Path) -> Result<ChannelManifest> {
+    let body = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    if path.extension().is_some_and(|ext| ext == "toml") {
+        toml::from_str(&body).context("failed to parse channel manifest")
+    } else {
+        serde_json::from_str(&body).context("failed to parse channel manifest")
+    }
+}
+
+/// Computes the channel set `manifest` asks for. Entries that fail
+/// validation are reported as `Failed` and leave their channel untouched.
+pub fn plan_channel_manifest(
+    channels: &ChannelsConfig,
+    manifest: &ChannelManifest,
+) -> Result<(ChannelsConfig, Vec<ChannelApplyResult>)> {
+    let current = serde_json::to_value(channels).context("failed to serialize channels")?;
+    let mut next = current.clone();
+    let mut seen = BTreeSet::new();
+    let mut results = Vec::new();
+
+    for entry in &manifest.channels {
+        let key = entry
+            .channel_type
+            .trim()
+            .to_ascii_lowercase()
+            .replace('-', "_");
+        let outcome = if !MANIFEST_CHANNEL_KEYS.contains(&key.as_str()) {
+            Err(format!("unknown channel type '{}'", entry.channel_type))
+        } else if !seen.insert(key.clone()) {
+            Err("channel is listed more than once".to_string())
+        } else {
+            desired_channel(&next, &key, entry)
+        };
+
+        results.push(match outcome {
+            Ok(desired) => {
+                let change = if current[&key].is_null() {
+                    ChannelChange::Add
+                } else if current[&key] == desired {
+                    ChannelChange::Unchanged
+                } else {
+                    ChannelChange::Update
+                };
+                next[&key] = desired;
+                ChannelApplyResult {
+                    channel: key,
+                    change,
+                    error: None,
+                }
+            }
+            Err(error) => ChannelApplyResult {
+                channel: key,
+                change: ChannelChange::Failed,
+                error: Some(error),
+            },
+        });
+    }
+
+    if manifest.prune {
+        for key in MANIFEST_CHANNEL_KEYS {
+            if !seen.contains(*key) && !current[*key].is_null() {
+                next[*key] = Value::Null;
+                results.push(ChannelApplyResult {
+                    channel: (*key).to_string(),
+                    change: ChannelChange::Remove,
+                    error: None,
+                });
+            }
+        }
+    }
+
+    let planned = serde_json::from_value(next).context("failed to build planned channels")?;
+    Ok((planned, results))
+}
+
+/// Validates one entry against its channel schema and returns the config as
+/// it would be stored, so it can be compared with the current one.
+fn desired_channel(
+    channels: &Value,
+    key: &str,
+    entry: &ChannelManifestEntry,
+) -> std::result::Result<Value, String> {
+    let mut config = match &entry.config {
+        Value::Object(_) => entry.config.clone(),
+        Value::Null => Value::Object(serde_json::Map::new()),
+        _ => return Err("config must be a table".into()),
+    };
+    if let Some(users) = &entry.allowed_users {
+        config["allowed_users"] = Value::from(users.clone());
+    }
+
+    let mut candidate = channels.clone();
+    candidate[key] = config;
+    let parsed: ChannelsConfig = serde_json::from_value(candidate).map_err(|e| e.to_string())?;
+    let stored = serde_json::to_value(parsed).map_err(|e| e.to_string())?;
+    if entry.allowed_users.is_some() && stored[key].get("allowed_users").is_none() {
+        return Err(format!("channel '{key}' has no identity allowlist"));
+    }
+    Ok(stored[key].clone())
+}
+
+/// Applies `manifest` to the saved config unless `dry_run` is set. Returns
+/// the per-channel results either way.
+pub async fn apply_channel_manifest(
+    config: &Config,
+    manifest: &ChannelManifest,
+    dry_run: bool,
+) -> Result<Vec<ChannelApplyResult>> {
+    let (planned, results) = plan_channel_manifest(&config.channels_config, manifest)?;
+    let changed = results.iter().any(|result| {
+        matches!(
+            result.change,
+            ChannelChange::Add | ChannelChange::Update | ChannelChange::Remove
+        )
+    });
+    if changed && !dry_run {
+        let mut updated = config.clone();
+        updated.channels_config = planned;
+        updated.save().await?;
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn telegram(token: &str) -> ChannelManifestEntry {
+        ChannelManifestEntry {
+            channel_type: "telegram".into(),
+            config: serde_json::json!({ "bot_token": token }),
+            allowed_users: Some(vec!["alice".into()]),
+        }
+    }
+
+    #[test]
+    fn manifest_plan_reports_each_channel() {
+        let manifest = ChannelManifest {
+            prune: false,
+            channels: vec![
+                telegram("token-a"),
+                ChannelManifestEntry {
+                    channel_type: "fax".into(),
+                    config: Value::Null,
+                    allowed_users: None,
+                },
+            ],
+        };
+        let (planned, results) =
+            plan_channel_manifest(&ChannelsConfig::default(), &manifest).unwrap();
+        assert_eq!(results[0].change, ChannelChange::Add);
+        assert_eq!(results[1].change, ChannelChange::Failed);
+        let bot = planned.telegram.as_ref().unwrap();
+        assert_eq!(bot.allowed_users, vec!["alice".to_string()]);
+
+        let (_, results) = plan_channel_manifest(&planned, &manifest).unwrap();
+        assert_eq!(results[0].change, ChannelChange::Unchanged);
+
+        let manifest = ChannelManifest {
+            prune: true,
+            channels: vec![ChannelManifestEntry {
+                channel_type: "telegram".into(),
+                config: serde_json::json!({}),
+                allowed_users: None,
+            }],
+        };
+        let (unchanged, results) = plan_channel_manifest(&planned, &manifest).unwrap();
+        assert_eq!(results[0].change, ChannelChange::Failed);
+        assert!(unchanged.telegram.is_some());
+
+        let manifest = ChannelManifest {
+            prune: true,
+            channels: Vec::new(),
+        };
+        let (pruned, results) = plan_channel_manifest(&planned, &manifest).unwrap();
+        assert_eq!(results[0].change, ChannelChange::Remove);
+        assert!(pruned.telegram.is_none());
+    }
+}
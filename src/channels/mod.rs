@@ -23,6 +23,7 @@ pub mod irc;
 #[cfg(feature = "channel-lark")]
 pub mod lark;
 pub mod linq;
+pub mod manifest;
 #[cfg(feature = "channel-matrix")]
 pub mod matrix;
 pub mod mattermost;
@@ -212,6 +213,7 @@ struct ChannelRuntimeContext {
     message_timeout_secs: u64,
     interrupt_on_new_message: bool,
     multimodal: crate::config::MultimodalConfig,
+    translation: crate::config::ChannelTranslationConfig,
 }
 
 #[derive(Clone)]
@@ -1309,6 +1311,73 @@ fn spawn_scoped_typing_task(
     handle
 }
 
+/// Translates an inbound message into the configured working language and
+/// returns the original text when it changed. Any failure falls back to the
+/// original message.
+async fn translate_inbound_message(
+    ctx: &ChannelRuntimeContext,
+    conversation_provider: &Arc<dyn Provider>,
+    conversation_model: &str,
+    mut msg: traits::ChannelMessage,
+) -> (traits::ChannelMessage, Option<String>) {
+    let settings = &ctx.translation;
+    let provider = match settings.provider.as_deref() {
+        Some(name) => match get_or_create_provider(ctx, name).await {
+            Ok(provider) => provider,
+            Err(err) => {
+                tracing::warn!("Translation provider `{name}` unavailable: {err}");
+                return (msg, None);
+            }
+        },
+        None => Arc::clone(conversation_provider),
+    };
+    let model = settings.model.as_deref().unwrap_or(conversation_model);
+    let system_prompt = format!(
+        "Translate the user's message into {lang}. Reply with the translation only. \
+         If the message is already in {lang}, reply with it unchanged.",
+        lang = settings.target_language
+    );
+
+    match provider
+        .chat_with_system(Some(&system_prompt), &msg.content, model, 0.0)
+        .await
+    {
+        Ok(translated) => {
+            let translated = translated.trim();
+            if !translated.is_empty() && translated != msg.content.trim() {
+                tracing::info!(
+                    channel = %msg.channel,
+                    sender = %msg.sender,
+                    message_id = %msg.id,
+                    target_language = %settings.target_language,
+                    model = %model,
+                    original_chars = msg.content.chars().count(),
+                    translated_chars = translated.chars().count(),
+                    "Translated inbound channel message"
+                );
+                let original = std::mem::replace(&mut msg.content, translated.to_string());
+                return (msg, Some(original));
+            }
+        }
+        Err(err) => {
+            tracing::warn!(
+                channel = %msg.channel,
+                message_id = %msg.id,
+                "Inbound translation failed, using original text: {}",
+                providers::sanitize_api_error(&err.to_string())
+            );
+        }
+    }
+    (msg, None)
+}
+
+/// History turn for a translated message. The original text is kept next to
+/// the translation so the conversation transcript records what was sent,
+/// whether or not memory autosave is on.
+fn translated_user_turn(translated: &str, original: &str) -> String {
+    format!("{translated}\n\n[Original message: {original}]")
+}
+
 async fn process_channel_message(
     ctx: Arc<ChannelRuntimeContext>,
     msg: traits::ChannelMessage,
@@ -1368,6 +1437,16 @@ async fn process_channel_message(
             .await;
     }
 
+    let (msg, original_text) = if ctx.translation.enabled {
+        translate_inbound_message(ctx.as_ref(), &active_provider, &route.model, msg).await
+    } else {
+        (msg, None)
+    };
+    let user_turn = original_text.as_deref().map_or_else(
+        || msg.content.clone(),
+        |original| translated_user_turn(&msg.content, original),
+    );
+
     println!("  ⏳ Processing message...");
     let started_at = Instant::now();
 
@@ -1379,7 +1458,7 @@ async fn process_channel_message(
         .is_some_and(|turns| !turns.is_empty());
 
     // Preserve user turn before the LLM call so interrupted requests keep context.
-    append_sender_turn(ctx.as_ref(), &history_key, ChatMessage::user(&msg.content));
+    append_sender_turn(ctx.as_ref(), &history_key, ChatMessage::user(&user_turn));
 
     // Build history from per-sender conversation cache.
     let prior_turns_raw = ctx
@@ -1398,7 +1477,7 @@ async fn process_channel_message(
             build_memory_context(ctx.memory.as_ref(), &msg.content, ctx.min_relevance_score).await;
         if let Some(last_turn) = prior_turns.last_mut() {
             if last_turn.role == "user" && !memory_context.is_empty() {
-                last_turn.content = format!("{memory_context}{}", msg.content);
+                last_turn.content = format!("{memory_context}{user_turn}");
             }
         }
     }
@@ -2247,6 +2326,20 @@ pub async fn handle_command(command: crate::ChannelCommands, config: &Config) ->
         crate::ChannelCommands::BindTelegram { identity } => {
             bind_telegram_identity(config, &identity).await
         }
+        crate::ChannelCommands::Apply { manifest, dry_run } => {
+            let manifest = manifest::load_channel_manifest(&manifest)?;
+            let results = manifest::apply_channel_manifest(config, &manifest, dry_run).await?;
+            for result in &results {
+                match &result.error {
+                    Some(error) => println!("  ❌ {}: {error}", result.channel),
+                    None => println!("  {:?} {}", result.change, result.channel),
+                }
+            }
+            if dry_run {
+                println!("\nDry run: nothing was saved.");
+            }
+            Ok(())
+        }
     }
 }
 
@@ -2975,6 +3068,7 @@ pub async fn start_channels(config: Config) -> Result<()> {
         message_timeout_secs,
         interrupt_on_new_message,
         multimodal: config.multimodal.clone(),
+        translation: config.channels_config.translation.clone(),
     });
 
     run_message_dispatch_loop(rx, runtime_ctx, max_in_flight_messages).await;
@@ -3147,6 +3241,7 @@ mod tests {
             reliability: Arc::new(crate::config::ReliabilityConfig::default()),
             interrupt_on_new_message: false,
             multimodal: crate::config::MultimodalConfig::default(),
+            translation: crate::config::ChannelTranslationConfig::default(),
             provider_runtime_options: providers::ProviderRuntimeOptions::default(),
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
@@ -3597,6 +3692,7 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             multimodal: crate::config::MultimodalConfig::default(),
+            translation: crate::config::ChannelTranslationConfig::default(),
         });
 
         process_channel_message(
@@ -3654,6 +3750,7 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             multimodal: crate::config::MultimodalConfig::default(),
+            translation: crate::config::ChannelTranslationConfig::default(),
         });
 
         process_channel_message(
@@ -3711,6 +3808,7 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             multimodal: crate::config::MultimodalConfig::default(),
+            translation: crate::config::ChannelTranslationConfig::default(),
         });
 
         process_channel_message(
@@ -3777,6 +3875,7 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             multimodal: crate::config::MultimodalConfig::default(),
+            translation: crate::config::ChannelTranslationConfig::default(),
         });
 
         process_channel_message(
@@ -3864,6 +3963,7 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             multimodal: crate::config::MultimodalConfig::default(),
+            translation: crate::config::ChannelTranslationConfig::default(),
         });
 
         process_channel_message(
@@ -3933,6 +4033,7 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             multimodal: crate::config::MultimodalConfig::default(),
+            translation: crate::config::ChannelTranslationConfig::default(),
         });
 
         process_channel_message(
@@ -4017,6 +4118,7 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             multimodal: crate::config::MultimodalConfig::default(),
+            translation: crate::config::ChannelTranslationConfig::default(),
         });
 
         process_channel_message(
@@ -4086,6 +4188,7 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             multimodal: crate::config::MultimodalConfig::default(),
+            translation: crate::config::ChannelTranslationConfig::default(),
         });
 
         process_channel_message(
@@ -4144,6 +4247,7 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             multimodal: crate::config::MultimodalConfig::default(),
+            translation: crate::config::ChannelTranslationConfig::default(),
         });
 
         process_channel_message(
@@ -4313,6 +4417,7 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             multimodal: crate::config::MultimodalConfig::default(),
+            translation: crate::config::ChannelTranslationConfig::default(),
         });
 
         let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(4);
@@ -4391,6 +4496,7 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: true,
             multimodal: crate::config::MultimodalConfig::default(),
+            translation: crate::config::ChannelTranslationConfig::default(),
         });
 
         let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(8);
@@ -4481,6 +4587,7 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: true,
             multimodal: crate::config::MultimodalConfig::default(),
+            translation: crate::config::ChannelTranslationConfig::default(),
         });
 
         let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(8);
@@ -4553,6 +4660,7 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             multimodal: crate::config::MultimodalConfig::default(),
+            translation: crate::config::ChannelTranslationConfig::default(),
         });
 
         process_channel_message(
@@ -5055,6 +5163,7 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             multimodal: crate::config::MultimodalConfig::default(),
+            translation: crate::config::ChannelTranslationConfig::default(),
         });
 
         process_channel_message(
@@ -5105,6 +5214,85 @@ BTC is currently around $65,000 based on latest tool output."#
         assert!(calls[1][3].1.contains("follow up"));
     }
 
+    #[tokio::test]
+    async fn process_channel_message_translates_inbound_text_when_enabled() {
+        let channel_impl = Arc::new(RecordingChannel::default());
+        let channel: Arc<dyn Channel> = channel_impl.clone();
+
+        let mut channels_by_name = HashMap::new();
+        channels_by_name.insert(channel.name().to_string(), channel);
+
+        // HistoryCaptureProvider answers one-shot prompts with `fallback`,
+        // which stands in for the translated text here.
+        let provider_impl = Arc::new(HistoryCaptureProvider::default());
+
+        let runtime_ctx = Arc::new(ChannelRuntimeContext {
+            channels_by_name: Arc::new(channels_by_name),
+            provider: provider_impl.clone(),
+            default_provider: Arc::new("test-provider".to_string()),
+            memory: Arc::new(NoopMemory),
+            tools_registry: Arc::new(vec![]),
+            observer: Arc::new(NoopObserver),
+            system_prompt: Arc::new("test-system-prompt".to_string()),
+            model: Arc::new("test-model".to_string()),
+            temperature: 0.0,
+            auto_save_memory: false,
+            max_tool_iterations: 5,
+            min_relevance_score: 0.0,
+            conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            provider_cache: Arc::new(Mutex::new(HashMap::new())),
+            route_overrides: Arc::new(Mutex::new(HashMap::new())),
+            api_key: None,
+            api_url: None,
+            reliability: Arc::new(crate::config::ReliabilityConfig::default()),
+            provider_runtime_options: providers::ProviderRuntimeOptions::default(),
+            workspace_dir: Arc::new(std::env::temp_dir()),
+            message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
+            interrupt_on_new_message: false,
+            multimodal: crate::config::MultimodalConfig::default(),
+            translation: crate::config::ChannelTranslationConfig {
+                enabled: true,
+                ..crate::config::ChannelTranslationConfig::default()
+            },
+        });
+
+        process_channel_message(
+            Arc::clone(&runtime_ctx),
+            traits::ChannelMessage {
+                id: "msg-es".to_string(),
+                sender: "alice".to_string(),
+                reply_target: "chat-1".to_string(),
+                content: "hola, ¿qué hora es?".to_string(),
+                channel: "test-channel".to_string(),
+                timestamp: 1,
+                thread_ts: None,
+            },
+            CancellationToken::new(),
+        )
+        .await;
+
+        let calls = provider_impl
+            .calls
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0][1].0, "user");
+        assert!(calls[0][1].1.starts_with("fallback"));
+
+        // Memory autosave is off, so the history is the only transcript and
+        // must still hold what the sender wrote.
+        let histories = runtime_ctx
+            .conversation_histories
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let turns = histories.values().next().unwrap();
+        assert_eq!(turns[0].role, "user");
+        assert_eq!(
+            turns[0].content,
+            translated_user_turn("fallback", "hola, ¿qué hora es?")
+        );
+    }
+
     #[tokio::test]
     async fn process_channel_message_enriches_current_turn_without_persisting_context() {
         let channel_impl = Arc::new(RecordingChannel::default());
@@ -5138,6 +5326,7 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             multimodal: crate::config::MultimodalConfig::default(),
+            translation: crate::config::ChannelTranslationConfig::default(),
         });
 
         process_channel_message(
@@ -5221,6 +5410,7 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             multimodal: crate::config::MultimodalConfig::default(),
+            translation: crate::config::ChannelTranslationConfig::default(),
         });
 
         process_channel_message(
@@ -64,6 +64,10 @@ pub struct Config {
     pub api_key: Option<String>,
     /// Base URL override for provider API (e.g. "http://10.0.0.1:11434" for remote Ollama)
     pub api_url: Option<String>,
+    /// Region the default provider is pinned to (e.g. `"eu"`). Checked against the
+    /// workspace's allowed provider regions and recorded on provider-bound receipts.
+    #[serde(default)]
+    pub provider_region: Option<String>,
     /// Default provider ID or alias (e.g. `"openrouter"`, `"ollama"`, `"anthropic"`). Default: `"openrouter"`.
     pub default_provider: Option<String>,
     /// Default model routed through the selected provider (e.g. `"anthropic/claude-sonnet-4-6"`).
@@ -218,6 +222,9 @@ pub struct DelegateAgentConfig {
     /// Maximum tool-call iterations in agentic mode.
     #[serde(default = "default_max_tool_iterations")]
     pub max_iterations: usize,
+    /// Region this agent's provider is pinned to. Falls back to `provider_region`.
+    #[serde(default)]
+    pub region: Option<String>,
 }
 
 fn default_max_depth() -> u32 {
@@ -1277,6 +1284,17 @@ fn service_selector_matches(selector: &str, service_key: &str) -> bool {
     false
 }
 
+fn validate_region(field: &str, region: &str) -> Result<()> {
+    if region.is_empty()
+        || !region
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    {
+        anyhow::bail!("{field} must be a lowercase region id such as \"eu\" or \"us-east\"");
+    }
+    Ok(())
+}
+
 fn validate_proxy_url(field: &str, url: &str) -> Result<()> {
     let parsed = reqwest::Url::parse(url)
         .with_context(|| format!("Invalid {field} URL: '{url}' is not a valid URL"))?;
@@ -2270,12 +2288,52 @@ pub struct ChannelsConfig {
     /// Default: 300s for on-device LLMs (Ollama) which are slower than cloud APIs.
     #[serde(default = "default_channel_message_timeout_secs")]
     pub message_timeout_secs: u64,
+    /// Inbound message translation (`[channels_config.translation]`).
+    #[serde(default)]
+    pub translation: ChannelTranslationConfig,
 }
 
 fn default_channel_message_timeout_secs() -> u64 {
     300
 }
 
+/// Inbound message translation (`[channels_config.translation]` section).
+///
+/// When enabled, each inbound channel message is translated into
+/// `target_language` before it reaches the agent. Memory autosave keeps the
+/// original text.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChannelTranslationConfig {
+    /// Translate inbound channel messages. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Language the agent works in. Default: `"English"`.
+    #[serde(default = "default_translation_target_language")]
+    pub target_language: String,
+    /// Provider used for translation. Defaults to the conversation's provider;
+    /// set to `ollama` to keep translation on-device.
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Model used for translation. Defaults to the conversation's model.
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+fn default_translation_target_language() -> String {
+    "English".into()
+}
+
+impl Default for ChannelTranslationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_language: default_translation_target_language(),
+            provider: None,
+            model: None,
+        }
+    }
+}
+
 impl Default for ChannelsConfig {
     fn default() -> Self {
         Self {
@@ -2297,6 +2355,7 @@ impl Default for ChannelsConfig {
             dingtalk: None,
             qq: None,
             message_timeout_secs: default_channel_message_timeout_secs(),
+            translation: ChannelTranslationConfig::default(),
         }
     }
 }
@@ -2817,6 +2876,7 @@ impl Default for Config {
             config_path: zeroclaw_dir.join("config.toml"),
             api_key: None,
             api_url: None,
+            provider_region: None,
             default_provider: Some("openrouter".to_string()),
             default_model: Some("anthropic/claude-sonnet-4.6".to_string()),
             default_temperature: 0.7,
@@ -3322,6 +3382,16 @@ impl Config {
             }
         }
 
+        // Provider regions
+        if let Some(region) = &self.provider_region {
+            validate_region("provider_region", region)?;
+        }
+        for (name, agent) in &self.agents {
+            if let Some(region) = &agent.region {
+                validate_region(&format!("agents.{name}.region"), region)?;
+            }
+        }
+
         // Proxy (delegate to existing validation)
         self.proxy.validate()?;
 
@@ -3610,6 +3680,12 @@ impl Config {
     }
 
     pub async fn save(&self) -> Result<()> {
+        crate::tools::delegate::warn_unknown_allowed_tools(
+            &self.agents,
+            crate::tools::DELEGATABLE_TOOL_NAMES,
+            "config_save",
+        );
+
         // Encrypt secrets before serialization
         let mut config_to_save = self.clone();
         let zeroclaw_dir = self
@@ -3922,6 +3998,7 @@ default_temperature = 0.7
             config_path: PathBuf::from("/tmp/test/config.toml"),
             api_key: Some("sk-test-key".into()),
             api_url: None,
+            provider_region: None,
             default_provider: Some("openrouter".into()),
             default_model: Some("gpt-4o".into()),
             default_temperature: 0.5,
@@ -3982,6 +4059,7 @@ default_temperature = 0.7
                 dingtalk: None,
                 qq: None,
                 message_timeout_secs: 300,
+                translation: ChannelTranslationConfig::default(),
             },
             memory: MemoryConfig::default(),
             storage: StorageConfig::default(),
@@ -4137,6 +4215,7 @@ tool_dispatcher = "xml"
             config_path: config_path.clone(),
             api_key: Some("sk-roundtrip".into()),
             api_url: None,
+            provider_region: None,
             default_provider: Some("openrouter".into()),
             default_model: Some("test-model".into()),
             default_temperature: 0.9,
@@ -4218,6 +4297,7 @@ tool_dispatcher = "xml"
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                region: None,
             },
         );
 
@@ -4526,6 +4606,7 @@ allowed_users = ["@ops:matrix.org"]
             dingtalk: None,
             qq: None,
             message_timeout_secs: 300,
+            translation: ChannelTranslationConfig::default(),
         };
         let toml_str = toml::to_string_pretty(&c).unwrap();
         let parsed: ChannelsConfig = toml::from_str(&toml_str).unwrap();
@@ -4736,6 +4817,7 @@ channel_id = "C123"
             dingtalk: None,
             qq: None,
             message_timeout_secs: 300,
+            translation: ChannelTranslationConfig::default(),
         };
         let toml_str = toml::to_string_pretty(&c).unwrap();
         let parsed: ChannelsConfig = toml::from_str(&toml_str).unwrap();
@@ -5293,6 +5375,33 @@ default_temperature = 0.7
         assert!(result.is_ok(), "expected validation to pass: {result:?}");
     }
 
+    #[test]
+    async fn validate_rejects_malformed_provider_regions() {
+        let mut config = Config {
+            provider_region: Some("eu".to_string()),
+            ..Config::default()
+        };
+        assert!(config.validate().is_ok());
+
+        config.agents.insert(
+            "researcher".to_string(),
+            DelegateAgentConfig {
+                provider: "openrouter".to_string(),
+                model: "model-test".to_string(),
+                system_prompt: None,
+                api_key: None,
+                temperature: None,
+                max_depth: 3,
+                agentic: false,
+                allowed_tools: Vec::new(),
+                max_iterations: 10,
+                region: Some("EU West".to_string()),
+            },
+        );
+        let error = config.validate().expect_err("expected validation to fail");
+        assert!(error.to_string().contains("agents.researcher.region"));
+    }
+
     #[test]
     async fn env_override_model_fallback() {
         let _env_guard = env_override_lock().await;
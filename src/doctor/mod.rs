@@ -63,7 +63,9 @@ pub fn run(config: &Config) -> Result<()> {
 
     check_config_semantics(config, &mut items);
     check_workspace(config, &mut items);
+    check_state_recovery(&config.workspace_dir, &mut items);
     check_daemon_state(config, &mut items);
+    check_service_definition(config, &mut items);
     check_environment(&mut items);
 
     // Print report
@@ -540,6 +542,53 @@ fn check_workspace(config: &Config, items: &mut Vec<DiagItem>) {
     check_file_exists(ws, "AGENTS.md", false, cat, items);
 }
 
+/// Reports state files that were found corrupt and replaced automatically
+/// during the last week (see `state_recovery.jsonl` in the workspace).
+fn check_state_recovery(workspace_dir: &Path, items: &mut Vec<DiagItem>) {
+    let cat = "workspace";
+    let Ok(body) = std::fs::read_to_string(workspace_dir.join("state_recovery.jsonl")) else {
+        return;
+    };
+
+    let cutoff = Utc::now() - chrono::Duration::days(7);
+    let recent: Vec<serde_json::Value> = body
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|incident| {
+            incident
+                .get("occurred_at")
+                .and_then(serde_json::Value::as_str)
+                .and_then(parse_rfc3339)
+                .is_some_and(|at| at >= cutoff)
+        })
+        .collect();
+    let Some(latest) = recent.last() else {
+        items.push(DiagItem::ok(
+            cat,
+            "no corrupt state files recovered this week",
+        ));
+        return;
+    };
+
+    let field = |key: &str| {
+        latest
+            .get(key)
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or("unknown")
+            .to_string()
+    };
+    items.push(DiagItem::warn(
+        cat,
+        format!(
+            "{} corrupt state file(s) recovered this week; latest {} was restored from {} (original kept at {})",
+            recent.len(),
+            field("file"),
+            field("restored_from"),
+            field("quarantined_to"),
+        ),
+    ));
+}
+
 fn check_file_exists(
     base: &Path,
     name: &str,
@@ -723,6 +772,17 @@ fn check_daemon_state(config: &Config, items: &mut Vec<DiagItem>) {
 
 // ── Environment checks ───────────────────────────────────────────
 
+fn check_service_definition(config: &Config, items: &mut Vec<DiagItem>) {
+    let cat = "service";
+    for check in crate::service::verify_definition(config) {
+        if check.healthy {
+            items.push(DiagItem::ok(cat, check.message));
+        } else {
+            items.push(DiagItem::warn(cat, check.message));
+        }
+    }
+}
+
 fn check_environment(items: &mut Vec<DiagItem>) {
     let cat = "environment";
 
@@ -1076,6 +1136,7 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                region: None,
             },
         );
         config.agents.insert(
@@ -1090,6 +1151,7 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                region: None,
             },
         );
 
@@ -1106,4 +1168,29 @@ mod tests {
         assert!(agent_messages[0].contains("agent \"alpha\""));
         assert!(agent_messages[1].contains("agent \"zeta\""));
     }
+
+    #[test]
+    fn state_recovery_incidents_surface_as_warnings() {
+        let tmp = TempDir::new().unwrap();
+        let mut items = Vec::new();
+        check_state_recovery(tmp.path(), &mut items);
+        assert!(items.is_empty());
+
+        let incident = serde_json::json!({
+            "occurred_at": Utc::now().to_rfc3339(),
+            "file": "tags.json",
+            "error": "failed to parse tag index",
+            "quarantined_to": "tags.json.corrupt-1",
+            "restored_from": "backup"
+        });
+        std::fs::write(
+            tmp.path().join("state_recovery.jsonl"),
+            format!("{incident}\n"),
+        )
+        .unwrap();
+        check_state_recovery(tmp.path(), &mut items);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].severity, Severity::Warn);
+        assert!(items[0].message.contains("tags.json"));
+    }
 }
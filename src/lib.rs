@@ -75,7 +75,12 @@ pub use config::Config;
 #[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub(crate) enum ServiceCommands {
     /// Install daemon service unit for auto-start and restart
-    Install,
+    Install {
+        /// macOS only: `agent` runs at login for this user, `daemon` runs
+        /// system-wide at boot and needs sudo
+        #[arg(long, value_parser = ["agent", "daemon"])]
+        scope: Option<String>,
+    },
     /// Start daemon service
     Start,
     /// Stop daemon service
@@ -135,6 +140,25 @@ Examples:
         /// Telegram identity to allow (username without '@' or numeric user ID)
         identity: String,
     },
+    /// Add, update or remove channels to match a manifest
+    #[command(long_about = "\
+Add, update or remove channels to match a manifest.
+
+The manifest (TOML, or JSON for other extensions) lists channels by \
+type with their config table and optional `allowed_users`. With \
+`prune = true`, configured channels missing from the manifest are \
+removed. Results are printed per channel.
+
+Examples:
+  zeroclaw channel apply channels.toml --dry-run
+  zeroclaw channel apply channels.json")]
+    Apply {
+        /// Path to the channel manifest
+        manifest: std::path::PathBuf,
+        /// Show the changes without saving them
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 /// Skills management subcommands
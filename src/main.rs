@@ -103,7 +103,12 @@ struct Cli {
 #[derive(Subcommand, Debug)]
 enum ServiceCommands {
     /// Install daemon service unit for auto-start and restart
-    Install,
+    Install {
+        /// macOS only: `agent` runs at login for this user, `daemon` runs
+        /// system-wide at boot and needs sudo
+        #[arg(long, value_parser = ["agent", "daemon"])]
+        scope: Option<String>,
+    },
     /// Start daemon service
     Start,
     /// Stop daemon service
@@ -242,6 +247,10 @@ Examples:
         /// Host to bind to; defaults to config gateway.host
         #[arg(long)]
         host: Option<String>,
+
+        /// Run under the Windows service control manager (set by `service install`)
+        #[arg(long, hide = true)]
+        windows_service: bool,
     },
 
     /// Manage OS service lifecycle (launchd/systemd user service)
@@ -653,6 +662,14 @@ enum ChannelCommands {
         /// Telegram identity to allow (username without '@' or numeric user ID)
         identity: String,
     },
+    /// Add, update or remove channels to match a manifest
+    Apply {
+        /// Path to the channel manifest (TOML or JSON)
+        manifest: std::path::PathBuf,
+        /// Show the changes without saving them
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -829,9 +846,16 @@ async fn main() -> Result<()> {
             gateway::run_gateway(&host, port, config).await
         }
 
-        Commands::Daemon { port, host } => {
+        Commands::Daemon {
+            port,
+            host,
+            windows_service,
+        } => {
             let port = port.unwrap_or(config.gateway.port);
             let host = host.unwrap_or_else(|| config.gateway.host.clone());
+            if windows_service {
+                return service::run_windows_service(config, host, port);
+            }
             if port == 0 {
                 info!("🧠 Starting ZeroClaw Daemon on {host} (random port)");
             } else {
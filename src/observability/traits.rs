@@ -124,6 +124,28 @@ pub trait Observer: Send + Sync + 'static {
     fn as_any(&self) -> &dyn std::any::Any;
 }
 
+impl<T: Observer + ?Sized> Observer for std::sync::Arc<T> {
+    fn record_event(&self, event: &ObserverEvent) {
+        (**self).record_event(event);
+    }
+
+    fn record_metric(&self, metric: &ObserverMetric) {
+        (**self).record_metric(metric);
+    }
+
+    fn flush(&self) {
+        (**self).flush();
+    }
+
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        (**self).as_any()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
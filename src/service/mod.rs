@@ -5,8 +5,90 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::str::FromStr;
 
+#[cfg(windows)]
+mod windows_scm;
+
+#[cfg(windows)]
+pub use windows_scm::run_as_service as run_windows_service;
+
 const SERVICE_LABEL: &str = "com.zeroclaw.daemon";
-const WINDOWS_TASK_NAME: &str = "ZeroClaw Daemon";
+const WINDOWS_SERVICE_NAME: &str = "ZeroClaw";
+const WINDOWS_SERVICE_DISPLAY_NAME: &str = "ZeroClaw Daemon";
+/// Scheduled task registered by older releases; removed on install/uninstall.
+const LEGACY_WINDOWS_TASK_NAME: &str = "ZeroClaw Daemon";
+/// SCM recovery: restart three times, a minute apart, and forget failures
+/// after a day.
+const WINDOWS_FAILURE_ACTIONS: &str = "restart/60000/restart/60000/restart/60000";
+/// Records which launchd scope `service install` chose, next to the config.
+const LAUNCHD_SCOPE_FILE: &str = "launchd-scope";
+
+/// Sandboxing applied to the systemd user unit. Namespace-based options
+/// (ProtectSystem, PrivateTmp, ...) are left out: user managers without
+/// unprivileged user namespaces refuse to start units that request them.
+const SYSTEMD_HARDENING: &[&str] = &[
+    "NoNewPrivileges=true",
+    "RestrictSUIDSGID=true",
+    "RestrictRealtime=true",
+    "LockPersonality=true",
+    "UMask=0077",
+];
+
+/// Whether launchd runs the daemon per user or system-wide. Chosen with
+/// `service install --scope` and saved, so later commands act on the
+/// installed definition whoever runs them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LaunchdScope {
+    /// `~/Library/LaunchAgents`, runs while the user is logged in
+    #[default]
+    Agent,
+    /// `/Library/LaunchDaemons`, runs at boot; managing it needs root
+    Daemon,
+}
+
+impl FromStr for LaunchdScope {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "agent" => Ok(Self::Agent),
+            "daemon" => Ok(Self::Daemon),
+            other => bail!("Unknown launchd scope: '{other}'. Supported: agent, daemon"),
+        }
+    }
+}
+
+impl LaunchdScope {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Agent => "agent",
+            Self::Daemon => "daemon",
+        }
+    }
+}
+
+/// One finding from comparing an installed service definition with what
+/// `zeroclaw service install` generates today.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceDefinitionCheck {
+    pub healthy: bool,
+    pub message: String,
+}
+
+impl ServiceDefinitionCheck {
+    fn ok(message: impl Into<String>) -> Self {
+        Self {
+            healthy: true,
+            message: message.into(),
+        }
+    }
+
+    fn issue(message: impl Into<String>) -> Self {
+        Self {
+            healthy: false,
+            message: message.into(),
+        }
+    }
+}
 
 /// Supported init systems for service management
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -85,17 +167,13 @@ fn detect_init_system() -> Result<InitSystem> {
     );
 }
 
-fn windows_task_name() -> &'static str {
-    WINDOWS_TASK_NAME
-}
-
 pub fn handle_command(
     command: &crate::ServiceCommands,
     config: &Config,
     init_system: InitSystem,
 ) -> Result<()> {
     match command {
-        crate::ServiceCommands::Install => install(config, init_system),
+        crate::ServiceCommands::Install { scope } => install(config, init_system, scope.as_deref()),
         crate::ServiceCommands::Start => start(config, init_system),
         crate::ServiceCommands::Stop => stop(config, init_system),
         crate::ServiceCommands::Restart => restart(config, init_system),
@@ -104,9 +182,13 @@ pub fn handle_command(
     }
 }
 
-fn install(config: &Config, init_system: InitSystem) -> Result<()> {
+fn install(config: &Config, init_system: InitSystem, scope: Option<&str>) -> Result<()> {
+    if scope.is_some() && !cfg!(target_os = "macos") {
+        bail!("--scope only applies to macOS launchd services");
+    }
     if cfg!(target_os = "macos") {
-        install_macos(config)
+        let scope = scope.map(str::parse).transpose()?.unwrap_or_default();
+        install_macos(config, scope)
     } else if cfg!(target_os = "linux") {
         let resolved = init_system.resolve()?;
         install_linux(config, resolved)
@@ -119,7 +201,8 @@ fn install(config: &Config, init_system: InitSystem) -> Result<()> {
 
 fn start(config: &Config, init_system: InitSystem) -> Result<()> {
     if cfg!(target_os = "macos") {
-        let plist = macos_service_file()?;
+        let scope = require_launchd_privileges(config, "start")?;
+        let plist = macos_service_file(scope)?;
         run_checked(Command::new("launchctl").arg("load").arg("-w").arg(&plist))?;
         run_checked(Command::new("launchctl").arg("start").arg(SERVICE_LABEL))?;
         println!("✅ Service started");
@@ -129,7 +212,7 @@ fn start(config: &Config, init_system: InitSystem) -> Result<()> {
         start_linux(resolved)
     } else if cfg!(target_os = "windows") {
         let _ = config;
-        run_checked(Command::new("schtasks").args(["/Run", "/TN", windows_task_name()]))?;
+        run_checked(Command::new("sc").args(["start", WINDOWS_SERVICE_NAME]))?;
         println!("✅ Service started");
         Ok(())
     } else {
@@ -155,7 +238,8 @@ fn start_linux(init_system: InitSystem) -> Result<()> {
 
 fn stop(config: &Config, init_system: InitSystem) -> Result<()> {
     if cfg!(target_os = "macos") {
-        let plist = macos_service_file()?;
+        let scope = require_launchd_privileges(config, "stop")?;
+        let plist = macos_service_file(scope)?;
         let _ = run_checked(Command::new("launchctl").arg("stop").arg(SERVICE_LABEL));
         let _ = run_checked(
             Command::new("launchctl")
@@ -170,8 +254,7 @@ fn stop(config: &Config, init_system: InitSystem) -> Result<()> {
         stop_linux(resolved)
     } else if cfg!(target_os = "windows") {
         let _ = config;
-        let task_name = windows_task_name();
-        let _ = run_checked(Command::new("schtasks").args(["/End", "/TN", task_name]));
+        let _ = run_checked(Command::new("sc").args(["stop", WINDOWS_SERVICE_NAME]));
         println!("✅ Service stopped");
         Ok(())
     } else {
@@ -237,8 +320,17 @@ fn restart_linux(init_system: InitSystem) -> Result<()> {
 
 fn status(config: &Config, init_system: InitSystem) -> Result<()> {
     if cfg!(target_os = "macos") {
-        let out = run_capture(Command::new("launchctl").arg("list"))?;
-        let running = out.lines().any(|line| line.contains(SERVICE_LABEL));
+        let scope = installed_launchd_scope(config);
+        let running = match scope {
+            LaunchdScope::Agent => run_capture(Command::new("launchctl").arg("list"))?
+                .lines()
+                .any(|line| line.contains(SERVICE_LABEL)),
+            LaunchdScope::Daemon => Command::new("launchctl")
+                .arg("print")
+                .arg(format!("system/{SERVICE_LABEL}"))
+                .output()
+                .is_ok_and(|output| output.status.success()),
+        };
         println!(
             "Service: {}",
             if running {
@@ -247,7 +339,8 @@ fn status(config: &Config, init_system: InitSystem) -> Result<()> {
                 "❌ not loaded"
             }
         );
-        println!("Unit: {}", macos_service_file()?.display());
+        println!("Scope: {}", scope.as_str());
+        println!("Unit: {}", macos_service_file(scope)?.display());
         return Ok(());
     }
 
@@ -258,12 +351,10 @@ fn status(config: &Config, init_system: InitSystem) -> Result<()> {
 
     if cfg!(target_os = "windows") {
         let _ = config;
-        let task_name = windows_task_name();
-        let out =
-            run_capture(Command::new("schtasks").args(["/Query", "/TN", task_name, "/FO", "LIST"]));
+        let out = run_capture(Command::new("sc").args(["query", WINDOWS_SERVICE_NAME]));
         match out {
-            Ok(text) => {
-                let running = text.contains("Running");
+            Ok(text) if !windows_service_missing(&text) => {
+                let running = text.contains("RUNNING");
                 println!(
                     "Service: {}",
                     if running {
@@ -272,9 +363,9 @@ fn status(config: &Config, init_system: InitSystem) -> Result<()> {
                         "❌ not running"
                     }
                 );
-                println!("Task: {}", task_name);
+                println!("Service name: {WINDOWS_SERVICE_NAME}");
             }
-            Err(_) => {
+            _ => {
                 println!("Service: ❌ not installed");
             }
         }
@@ -311,11 +402,12 @@ fn uninstall(config: &Config, init_system: InitSystem) -> Result<()> {
     stop(config, init_system)?;
 
     if cfg!(target_os = "macos") {
-        let file = macos_service_file()?;
+        let file = macos_service_file(installed_launchd_scope(config))?;
         if file.exists() {
             fs::remove_file(&file)
                 .with_context(|| format!("Failed to remove {}", file.display()))?;
         }
+        let _ = fs::remove_file(service_config_dir(config).join(LAUNCHD_SCOPE_FILE));
         println!("✅ Service uninstalled ({})", file.display());
         return Ok(());
     }
@@ -326,18 +418,8 @@ fn uninstall(config: &Config, init_system: InitSystem) -> Result<()> {
     }
 
     if cfg!(target_os = "windows") {
-        let task_name = windows_task_name();
-        let _ = run_checked(Command::new("schtasks").args(["/Delete", "/TN", task_name, "/F"]));
-        // Remove the wrapper script
-        let wrapper = config
-            .config_path
-            .parent()
-            .map_or_else(|| PathBuf::from("."), PathBuf::from)
-            .join("logs")
-            .join("zeroclaw-daemon.cmd");
-        if wrapper.exists() {
-            fs::remove_file(&wrapper).ok();
-        }
+        let _ = run_checked(Command::new("sc").args(["delete", WINDOWS_SERVICE_NAME]));
+        remove_legacy_windows_task(config);
         println!("✅ Service uninstalled");
         return Ok(());
     }
@@ -376,24 +458,81 @@ fn uninstall_linux(config: &Config, init_system: InitSystem) -> Result<()> {
     Ok(())
 }
 
-fn install_macos(config: &Config) -> Result<()> {
-    let file = macos_service_file()?;
+fn install_macos(config: &Config, scope: LaunchdScope) -> Result<()> {
+    if scope == LaunchdScope::Daemon && !is_root() {
+        bail!("Installing a launchd daemon needs root: run `sudo zeroclaw service install --scope daemon`");
+    }
+    let file = macos_service_file(scope)?;
     if let Some(parent) = file.parent() {
         fs::create_dir_all(parent)?;
     }
 
     let exe = std::env::current_exe().context("Failed to resolve current executable")?;
-    let logs_dir = config
-        .config_path
-        .parent()
-        .map_or_else(|| PathBuf::from("."), PathBuf::from)
-        .join("logs");
+    let logs_dir = service_config_dir(config).join("logs");
     fs::create_dir_all(&logs_dir)?;
 
     let stdout = logs_dir.join("daemon.stdout.log");
     let stderr = logs_dir.join("daemon.stderr.log");
 
-    let plist = format!(
+    let run_as = match scope {
+        LaunchdScope::Daemon => std::env::var("SUDO_USER").ok(),
+        LaunchdScope::Agent => None,
+    };
+    let plist = generate_launchd_plist(&exe, &stdout, &stderr, run_as.as_deref());
+
+    fs::write(&file, plist)?;
+    let marker = service_config_dir(config).join(LAUNCHD_SCOPE_FILE);
+    fs::write(&marker, scope.as_str())
+        .with_context(|| format!("Failed to record launchd scope in {}", marker.display()))?;
+    match scope {
+        LaunchdScope::Agent => println!("✅ Installed launchd agent: {}", file.display()),
+        LaunchdScope::Daemon => println!("✅ Installed launchd daemon: {}", file.display()),
+    }
+    match scope {
+        LaunchdScope::Agent => println!("   Start with: zeroclaw service start"),
+        LaunchdScope::Daemon => println!("   Start with: sudo zeroclaw service start"),
+    }
+    Ok(())
+}
+
+/// Scope saved by `install`. Definitions from before the scope was recorded
+/// are found by looking for the system daemon plist.
+fn installed_launchd_scope(config: &Config) -> LaunchdScope {
+    fs::read_to_string(service_config_dir(config).join(LAUNCHD_SCOPE_FILE))
+        .ok()
+        .and_then(|raw| raw.parse().ok())
+        .unwrap_or_else(|| {
+            let daemon = macos_service_file(LaunchdScope::Daemon);
+            if daemon.is_ok_and(|file| file.exists()) {
+                LaunchdScope::Daemon
+            } else {
+                LaunchdScope::Agent
+            }
+        })
+}
+
+/// System daemons can only be loaded and unloaded by root.
+fn require_launchd_privileges(config: &Config, action: &str) -> Result<LaunchdScope> {
+    let scope = installed_launchd_scope(config);
+    if scope == LaunchdScope::Daemon && !is_root() {
+        bail!("The launchd daemon is system-wide: run `sudo zeroclaw service {action}`");
+    }
+    Ok(scope)
+}
+
+fn generate_launchd_plist(
+    exe: &Path,
+    stdout: &Path,
+    stderr: &Path,
+    run_as: Option<&str>,
+) -> String {
+    let user_name = run_as.map_or_else(String::new, |user| {
+        format!(
+            "  <key>UserName</key>\n  <string>{}</string>\n",
+            xml_escape(user)
+        )
+    });
+    format!(
         r#"<?xml version=\"1.0\" encoding=\"UTF-8\"?>
 <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">
 <plist version=\"1.0\">
@@ -405,10 +544,14 @@ fn install_macos(config: &Config) -> Result<()> {
     <string>{exe}</string>
     <string>daemon</string>
   </array>
-  <key>RunAtLoad</key>
+{user_name}  <key>RunAtLoad</key>
   <true/>
   <key>KeepAlive</key>
   <true/>
+  <key>ThrottleInterval</key>
+  <integer>10</integer>
+  <key>ProcessType</key>
+  <string>Background</string>
   <key>StandardOutPath</key>
   <string>{stdout}</string>
   <key>StandardErrorPath</key>
@@ -420,12 +563,15 @@ fn install_macos(config: &Config) -> Result<()> {
         exe = xml_escape(&exe.display().to_string()),
         stdout = xml_escape(&stdout.display().to_string()),
         stderr = xml_escape(&stderr.display().to_string())
-    );
+    )
+}
 
-    fs::write(&file, plist)?;
-    println!("✅ Installed launchd service: {}", file.display());
-    println!("   Start with: zeroclaw service start");
-    Ok(())
+fn launchd_plist_issues(plist: &str) -> Vec<String> {
+    ["KeepAlive", "ThrottleInterval", "StandardErrorPath"]
+        .iter()
+        .filter(|key| !plist.contains(&format!("<key>{key}</key>")))
+        .map(|key| format!("launchd plist is missing {key}"))
+        .collect()
 }
 
 fn install_linux(config: &Config, init_system: InitSystem) -> Result<()> {
@@ -443,12 +589,7 @@ fn install_linux_systemd(config: &Config) -> Result<()> {
     }
 
     let exe = std::env::current_exe().context("Failed to resolve current executable")?;
-    let unit = format!(
-        "[Unit]\nDescription=ZeroClaw daemon\nAfter=network.target\n\n[Service]\nType=simple\nExecStart={} daemon\nRestart=always\nRestartSec=3\n\n[Install]\nWantedBy=default.target\n",
-        exe.display()
-    );
-
-    fs::write(&file, unit)?;
+    fs::write(&file, generate_systemd_unit(&exe))?;
     let _ = run_checked(Command::new("systemctl").args(["--user", "daemon-reload"]));
     let _ = run_checked(Command::new("systemctl").args(["--user", "enable", "zeroclaw.service"]));
     println!("✅ Installed systemd user service: {}", file.display());
@@ -456,6 +597,27 @@ fn install_linux_systemd(config: &Config) -> Result<()> {
     Ok(())
 }
 
+fn generate_systemd_unit(exe: &Path) -> String {
+    let mut unit = format!(
+        "[Unit]\nDescription=ZeroClaw daemon\nAfter=network.target\n\n[Service]\nType=simple\nExecStart={} daemon\nRestart=always\nRestartSec=3\n",
+        exe.display()
+    );
+    for directive in SYSTEMD_HARDENING {
+        unit.push_str(directive);
+        unit.push('\n');
+    }
+    unit.push_str("\n[Install]\nWantedBy=default.target\n");
+    unit
+}
+
+fn missing_systemd_hardening(unit: &str) -> Vec<&'static str> {
+    SYSTEMD_HARDENING
+        .iter()
+        .copied()
+        .filter(|directive| !unit.lines().any(|line| line.trim() == *directive))
+        .collect()
+}
+
 /// Check if the current process is running as root (Unix only)
 #[cfg(unix)]
 fn is_root() -> bool {
@@ -979,54 +1141,187 @@ fn install_linux_openrc(config: &Config) -> Result<()> {
 
 fn install_windows(config: &Config) -> Result<()> {
     let exe = std::env::current_exe().context("Failed to resolve current executable")?;
-    let logs_dir = config
-        .config_path
-        .parent()
-        .map_or_else(|| PathBuf::from("."), PathBuf::from)
-        .join("logs");
-    fs::create_dir_all(&logs_dir)?;
+    let config_dir = service_config_dir(config);
+    remove_legacy_windows_task(config);
+
+    // Reconfigure an existing service in place: deleting a running service
+    // only marks it for deletion, and creating it again then fails with
+    // error 1072. The new binary path takes effect on the next start.
+    let installed = run_capture(Command::new("sc").args(["query", WINDOWS_SERVICE_NAME]))
+        .is_ok_and(|text| !windows_service_missing(&text));
+    run_checked(Command::new("sc").args([
+        windows_install_verb(installed),
+        WINDOWS_SERVICE_NAME,
+        "binPath=",
+        &windows_service_command(&exe, &config_dir),
+        "start=",
+        "auto",
+        "DisplayName=",
+        WINDOWS_SERVICE_DISPLAY_NAME,
+    ]))
+    .context("Failed to register the Windows service (run from an elevated prompt)")?;
+    run_checked(Command::new("sc").args(["description", WINDOWS_SERVICE_NAME, "ZeroClaw daemon"]))?;
+    run_checked(Command::new("sc").args([
+        "failure",
+        WINDOWS_SERVICE_NAME,
+        "reset=",
+        "86400",
+        "actions=",
+        WINDOWS_FAILURE_ACTIONS,
+    ]))?;
+    // Also restart when the daemon stops with an error, not only on crashes.
+    run_checked(Command::new("sc").args(["failureflag", WINDOWS_SERVICE_NAME, "1"]))?;
+
+    println!("✅ Installed Windows service: {WINDOWS_SERVICE_NAME}");
+    println!("   Config: {}", config_dir.display());
+    if installed {
+        println!("   Apply with: zeroclaw service restart");
+    } else {
+        println!("   Start with: zeroclaw service start");
+    }
+    Ok(())
+}
 
-    // Create a wrapper script that redirects output to log files
-    let wrapper = logs_dir.join("zeroclaw-daemon.cmd");
-    let stdout_log = logs_dir.join("daemon.stdout.log");
-    let stderr_log = logs_dir.join("daemon.stderr.log");
+/// `sc` subcommand that writes the service definition.
+fn windows_install_verb(installed: bool) -> &'static str {
+    if installed {
+        "config"
+    } else {
+        "create"
+    }
+}
 
-    let wrapper_content = format!(
-        "@echo off\r\n\"{}\" daemon >>\"{}\" 2>>\"{}\"",
+/// Command line SCM runs. The config directory is passed explicitly because
+/// the service account does not share the installing user's profile.
+fn windows_service_command(exe: &Path, config_dir: &Path) -> String {
+    format!(
+        "\"{}\" --config-dir \"{}\" daemon --windows-service",
         exe.display(),
-        stdout_log.display(),
-        stderr_log.display()
-    );
-    fs::write(&wrapper, &wrapper_content)?;
-
-    let task_name = windows_task_name();
+        config_dir.display()
+    )
+}
 
-    // Remove any existing task first (ignore errors if it doesn't exist)
+/// Drops the scheduled task and wrapper script older releases installed.
+fn remove_legacy_windows_task(config: &Config) {
     let _ = Command::new("schtasks")
-        .args(["/Delete", "/TN", task_name, "/F"])
+        .args(["/Delete", "/TN", LEGACY_WINDOWS_TASK_NAME, "/F"])
         .output();
+    let logs_dir = service_config_dir(config).join("logs");
+    for legacy in ["zeroclaw-daemon.cmd", "zeroclaw-task.xml"] {
+        let _ = fs::remove_file(logs_dir.join(legacy));
+    }
+}
 
-    run_checked(Command::new("schtasks").args([
-        "/Create",
-        "/TN",
-        task_name,
-        "/SC",
-        "ONLOGON",
-        "/TR",
-        &format!("\"{}\"", wrapper.display()),
-        "/RL",
-        "HIGHEST",
-        "/F",
-    ]))?;
+#[cfg(not(windows))]
+pub fn run_windows_service(_config: Config, _host: String, _port: u16) -> Result<()> {
+    bail!("--windows-service is only used when the Windows service manager starts the daemon");
+}
 
-    println!("✅ Installed Windows scheduled task: {}", task_name);
-    println!("   Wrapper: {}", wrapper.display());
-    println!("   Logs: {}", logs_dir.display());
-    println!("   Start with: zeroclaw service start");
-    Ok(())
+fn windows_service_missing(sc_output: &str) -> bool {
+    sc_output.contains("FAILED 1060")
+}
+
+/// Findings from `sc qc`, `sc qfailure` and `sc qfailureflag` output.
+fn windows_service_issues(config: &str, failure: &str, failure_flag: &str) -> Vec<String> {
+    let mut issues = Vec::new();
+    if !config.contains("AUTO_START") {
+        issues.push("Windows service does not start automatically".to_string());
+    }
+    if !config.contains("--windows-service") {
+        issues.push("Windows service does not run the daemon in service mode".to_string());
+    }
+    if !failure.contains("RESTART") {
+        issues.push("Windows service has no restart-on-failure recovery actions".to_string());
+    }
+    if !failure_flag.contains("TRUE") {
+        issues.push("Windows service only recovers from crashes, not daemon errors".to_string());
+    }
+    issues
+}
+
+/// Compares the installed service definition for this platform with the one
+/// `install` would write now. Used by `zeroclaw doctor`.
+pub fn verify_definition(config: &Config) -> Vec<ServiceDefinitionCheck> {
+    let reinstall = "; reinstall with `zeroclaw service install`";
+    if cfg!(target_os = "macos") {
+        let Ok(file) = macos_service_file(installed_launchd_scope(config)) else {
+            return vec![ServiceDefinitionCheck::issue(
+                "could not resolve launchd plist path",
+            )];
+        };
+        let Ok(plist) = fs::read_to_string(&file) else {
+            return vec![ServiceDefinitionCheck::ok("launchd service not installed")];
+        };
+        let issues = launchd_plist_issues(&plist);
+        if issues.is_empty() {
+            return vec![ServiceDefinitionCheck::ok(format!(
+                "launchd definition up to date ({})",
+                file.display()
+            ))];
+        }
+        return issues
+            .into_iter()
+            .map(|issue| ServiceDefinitionCheck::issue(format!("{issue}{reinstall}")))
+            .collect();
+    }
+
+    if cfg!(target_os = "linux") {
+        if matches!(InitSystem::Auto.resolve(), Ok(InitSystem::Openrc)) {
+            return vec![ServiceDefinitionCheck::ok(
+                "OpenRC service hardening is set by the init script",
+            )];
+        }
+        let Ok(file) = linux_service_file(config) else {
+            return vec![ServiceDefinitionCheck::issue(
+                "could not resolve systemd unit path",
+            )];
+        };
+        let Ok(unit) = fs::read_to_string(&file) else {
+            return vec![ServiceDefinitionCheck::ok("systemd service not installed")];
+        };
+        let missing = missing_systemd_hardening(&unit);
+        if missing.is_empty() {
+            return vec![ServiceDefinitionCheck::ok(format!(
+                "systemd unit hardened ({})",
+                file.display()
+            ))];
+        }
+        return vec![ServiceDefinitionCheck::issue(format!(
+            "systemd unit lacks {}{reinstall}",
+            missing.join(", ")
+        ))];
+    }
+
+    if cfg!(target_os = "windows") {
+        let query = |action: &str| {
+            run_capture(Command::new("sc").args([action, WINDOWS_SERVICE_NAME])).unwrap_or_default()
+        };
+        let service_config = query("qc");
+        if service_config.is_empty() || windows_service_missing(&service_config) {
+            return vec![ServiceDefinitionCheck::ok("Windows service not installed")];
+        }
+        let issues =
+            windows_service_issues(&service_config, &query("qfailure"), &query("qfailureflag"));
+        if issues.is_empty() {
+            return vec![ServiceDefinitionCheck::ok(
+                "Windows service restarts on failure",
+            )];
+        }
+        return issues
+            .into_iter()
+            .map(|issue| ServiceDefinitionCheck::issue(format!("{issue}{reinstall}")))
+            .collect();
+    }
+
+    vec![ServiceDefinitionCheck::ok(
+        "service management not supported on this platform",
+    )]
 }
 
-fn macos_service_file() -> Result<PathBuf> {
+fn macos_service_file(scope: LaunchdScope) -> Result<PathBuf> {
+    if scope == LaunchdScope::Daemon {
+        return Ok(PathBuf::from("/Library/LaunchDaemons").join(format!("{SERVICE_LABEL}.plist")));
+    }
     let home = directories::UserDirs::new()
         .map(|u| u.home_dir().to_path_buf())
         .context("Could not find home directory")?;
@@ -1036,6 +1331,13 @@ fn macos_service_file() -> Result<PathBuf> {
         .join(format!("{SERVICE_LABEL}.plist")))
 }
 
+fn service_config_dir(config: &Config) -> PathBuf {
+    config
+        .config_path
+        .parent()
+        .map_or_else(|| PathBuf::from("."), PathBuf::from)
+}
+
 fn linux_service_file(config: &Config) -> Result<PathBuf> {
     let home = directories::UserDirs::new()
         .map(|u| u.home_dir().to_path_buf())
@@ -1117,8 +1419,8 @@ mod tests {
     }
 
     #[test]
-    fn windows_task_name_is_constant() {
-        assert_eq!(windows_task_name(), "ZeroClaw Daemon");
+    fn windows_service_name_is_constant() {
+        assert_eq!(WINDOWS_SERVICE_NAME, "ZeroClaw");
     }
 
     #[cfg(target_os = "windows")]
@@ -1259,4 +1561,75 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn systemd_unit_includes_hardening_directives() {
+        let unit = generate_systemd_unit(Path::new("/usr/local/bin/zeroclaw"));
+        assert!(unit.contains("ExecStart=/usr/local/bin/zeroclaw daemon"));
+        assert!(missing_systemd_hardening(&unit).is_empty());
+
+        let legacy = "[Service]\nExecStart=/usr/local/bin/zeroclaw daemon\nRestart=always\n";
+        assert_eq!(
+            missing_systemd_hardening(legacy).len(),
+            SYSTEMD_HARDENING.len()
+        );
+    }
+
+    #[test]
+    fn launchd_plist_sets_user_only_for_daemons() {
+        let exe = Path::new("/usr/local/bin/zeroclaw");
+        let out = Path::new("/tmp/out.log");
+        let err = Path::new("/tmp/err.log");
+
+        let agent = generate_launchd_plist(exe, out, err, None);
+        assert!(!agent.contains("<key>UserName</key>"));
+        assert!(launchd_plist_issues(&agent).is_empty());
+
+        let daemon = generate_launchd_plist(exe, out, err, Some("alice"));
+        assert!(daemon.contains("<key>UserName</key>\n  <string>alice</string>"));
+    }
+
+    #[test]
+    fn launchd_scope_is_saved_and_read_back() {
+        assert_eq!(
+            "Daemon".parse::<LaunchdScope>().unwrap(),
+            LaunchdScope::Daemon
+        );
+        assert!("system".parse::<LaunchdScope>().is_err());
+
+        let tmp = tempfile::tempdir().unwrap();
+        let config = Config {
+            config_path: tmp.path().join("config.toml"),
+            ..Config::default()
+        };
+        fs::write(tmp.path().join(LAUNCHD_SCOPE_FILE), "daemon").unwrap();
+        assert_eq!(installed_launchd_scope(&config), LaunchdScope::Daemon);
+        assert!(macos_service_file(LaunchdScope::Daemon)
+            .unwrap()
+            .starts_with("/Library/LaunchDaemons"));
+    }
+
+    #[test]
+    fn windows_service_runs_daemon_in_service_mode_with_recovery() {
+        let command = windows_service_command(
+            Path::new("C:\\Program Files\\zeroclaw.exe"),
+            Path::new("C:\\Users\\dana\\.zeroclaw"),
+        );
+        assert_eq!(
+            command,
+            "\"C:\\Program Files\\zeroclaw.exe\" --config-dir \"C:\\Users\\dana\\.zeroclaw\" daemon --windows-service"
+        );
+        assert_eq!(WINDOWS_FAILURE_ACTIONS.matches("restart/").count(), 3);
+
+        let qc = format!("START_TYPE : 2 AUTO_START\nBINARY_PATH_NAME : {command}");
+        let qfailure = "FAILURE_ACTIONS : RESTART -- Delay = 60000 milliseconds.";
+        let qflag = "FAILURE_ACTIONS_ON_NONCRASH_FAILURES: TRUE";
+        assert!(windows_service_issues(&qc, qfailure, qflag).is_empty());
+        assert_eq!(windows_service_issues("", "", "").len(), 4);
+        assert!(windows_service_missing(
+            "[SC] OpenService FAILED 1060:\n\nThe specified service does not exist."
+        ));
+        assert_eq!(windows_install_verb(false), "create");
+        assert_eq!(windows_install_verb(true), "config");
+    }
 }
@@ -0,0 +1,91 @@
+//! Runs the daemon under the Windows Service Control Manager.
+//!
+//! `zeroclaw service install` registers `zeroclaw daemon --windows-service`
+//! with SCM; that entry point hands the process to the service dispatcher,
+//! which calls back into [`service_main`] on its own thread.
+
+use crate::config::Config;
+use anyhow::{Context, Result};
+use std::ffi::OsString;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use windows_service::service::{
+    ServiceControl, ServiceControlAccept, ServiceExitCode, ServiceState, ServiceStatus, ServiceType,
+};
+use windows_service::service_control_handler::{self, ServiceControlHandlerResult};
+use windows_service::{define_windows_service, service_dispatcher};
+
+static DAEMON_ARGS: OnceLock<(Config, String, u16)> = OnceLock::new();
+
+define_windows_service!(ffi_service_main, service_main);
+
+/// Blocks until SCM stops the service.
+pub fn run_as_service(config: Config, host: String, port: u16) -> Result<()> {
+    let _ = DAEMON_ARGS.set((config, host, port));
+    service_dispatcher::start(super::WINDOWS_SERVICE_NAME, ffi_service_main)
+        .context("Failed to connect to the Windows service control manager")
+}
+
+fn service_main(_arguments: Vec<OsString>) {
+    if let Err(err) = run_service() {
+        tracing::error!("Windows service failed: {err:#}");
+    }
+}
+
+fn run_service() -> Result<()> {
+    let (config, host, port) = DAEMON_ARGS
+        .get()
+        .cloned()
+        .context("daemon arguments were not set before dispatch")?;
+
+    let shutdown = Arc::new(tokio::sync::Notify::new());
+    let handler_shutdown = Arc::clone(&shutdown);
+    let status_handle = service_control_handler::register(
+        super::WINDOWS_SERVICE_NAME,
+        move |control| match control {
+            ServiceControl::Stop | ServiceControl::Shutdown => {
+                handler_shutdown.notify_one();
+                ServiceControlHandlerResult::NoError
+            }
+            ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+            _ => ServiceControlHandlerResult::NotImplemented,
+        },
+    )?;
+    let report = |state: ServiceState, exit_code: ServiceExitCode| {
+        status_handle.set_service_status(ServiceStatus {
+            service_type: ServiceType::OWN_PROCESS,
+            current_state: state,
+            controls_accepted: if state == ServiceState::Running {
+                ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN
+            } else {
+                ServiceControlAccept::empty()
+            },
+            exit_code,
+            checkpoint: 0,
+            wait_hint: Duration::default(),
+            process_id: None,
+        })
+    };
+    report(ServiceState::Running, ServiceExitCode::Win32(0))?;
+
+    let outcome = tokio::runtime::Runtime::new()
+        .context("Failed to start the daemon runtime")
+        .and_then(|runtime| {
+            runtime.block_on(async {
+                tokio::select! {
+                    result = crate::daemon::run(config, host, port) => result,
+                    () = shutdown.notified() => Ok(()),
+                }
+            })
+        });
+
+    // A service-specific exit code is what makes SCM apply the recovery
+    // actions set with `sc failure` / `sc failureflag`.
+    let exit_code = if outcome.is_err() {
+        ServiceExitCode::ServiceSpecific(1)
+    } else {
+        ServiceExitCode::Win32(0)
+    };
+    report(ServiceState::Stopped, exit_code)?;
+    outcome
+}
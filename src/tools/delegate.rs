@@ -492,6 +492,103 @@ impl Observer for NoopObserver {
     }
 }
 
+/// Allowlist entries of one delegate agent that match no registered tool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownAllowedTools {
+    pub agent: String,
+    pub unknown: Vec<String>,
+    /// `(entry, closest registered name)` pairs for likely misspellings.
+    pub suggestions: Vec<(String, String)>,
+}
+
+/// Checks every agent's `allowed_tools` against `registered`. Entries naming
+/// `delegate` are reported too, since sub-agents never receive it.
+pub fn find_unknown_allowed_tools(
+    agents: &HashMap<String, DelegateAgentConfig>,
+    registered: &[&str],
+) -> Vec<UnknownAllowedTools> {
+    let mut findings: Vec<UnknownAllowedTools> = agents
+        .iter()
+        .filter_map(|(agent, config)| {
+            let unknown: Vec<String> = config
+                .allowed_tools
+                .iter()
+                .map(|name| name.trim())
+                .filter(|name| !name.is_empty())
+                .filter(|name| *name == "delegate" || !registered.contains(name))
+                .map(str::to_string)
+                .collect();
+            if unknown.is_empty() {
+                return None;
+            }
+            let suggestions = unknown
+                .iter()
+                .filter_map(|name| {
+                    closest_tool_name(name, registered)
+                        .map(|closest| (name.clone(), closest.to_string()))
+                })
+                .collect();
+            Some(UnknownAllowedTools {
+                agent: agent.clone(),
+                unknown,
+                suggestions,
+            })
+        })
+        .collect();
+    findings.sort_by(|a, b| a.agent.cmp(&b.agent));
+    findings
+}
+
+/// Logs one structured warning per agent whose allowlist names unknown tools.
+/// `stage` says where the check ran, e.g. `config_save` or `runtime_start`.
+pub fn warn_unknown_allowed_tools(
+    agents: &HashMap<String, DelegateAgentConfig>,
+    registered: &[&str],
+    stage: &str,
+) -> Vec<UnknownAllowedTools> {
+    let findings = find_unknown_allowed_tools(agents, registered);
+    for finding in &findings {
+        let suggestions = finding
+            .suggestions
+            .iter()
+            .map(|(entry, closest)| format!("{entry} -> {closest}"))
+            .collect::<Vec<_>>();
+        tracing::warn!(
+            stage,
+            agent = %finding.agent,
+            unknown = ?finding.unknown,
+            suggestions = ?suggestions,
+            "Delegate agent allowed_tools lists tools that are not registered; they will never be called"
+        );
+    }
+    findings
+}
+
+fn closest_tool_name<'a>(name: &str, registered: &[&'a str]) -> Option<&'a str> {
+    let name = name.to_ascii_lowercase();
+    registered
+        .iter()
+        .filter(|candidate| **candidate != "delegate")
+        .map(|candidate| (edit_distance(&name, candidate), *candidate))
+        .filter(|(distance, candidate)| *distance <= (candidate.len() / 3).max(1))
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -517,6 +614,7 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                region: None,
             },
         );
         agents.insert(
@@ -531,6 +629,7 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                region: None,
             },
         );
         agents
@@ -678,9 +777,41 @@ mod tests {
             agentic: true,
             allowed_tools,
             max_iterations,
+            region: None,
         }
     }
 
+    #[test]
+    fn unknown_allowed_tools_are_reported_with_suggestions() {
+        let mut agents = HashMap::new();
+        agents.insert(
+            "coder".to_string(),
+            agentic_config(
+                vec![
+                    "file_read".into(),
+                    "shel".into(),
+                    "delegate".into(),
+                    "teleport".into(),
+                ],
+                5,
+            ),
+        );
+        agents.insert(
+            "reader".to_string(),
+            agentic_config(vec!["file_read".into()], 5),
+        );
+
+        let findings =
+            find_unknown_allowed_tools(&agents, &["shell", "file_read", "file_write", "delegate"]);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].agent, "coder");
+        assert_eq!(findings[0].unknown, vec!["shel", "delegate", "teleport"]);
+        assert_eq!(
+            findings[0].suggestions,
+            vec![("shel".to_string(), "shell".to_string())]
+        );
+    }
+
     #[test]
     fn name_and_schema() {
         let tool = DelegateTool::new(sample_agents(), None, test_security());
@@ -786,6 +917,7 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                region: None,
             },
         );
         let tool = DelegateTool::new(agents, None, test_security());
@@ -892,6 +1024,7 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                region: None,
             },
         );
         let tool = DelegateTool::new(agents, None, test_security());
@@ -927,6 +1060,7 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                region: None,
             },
         );
         let tool = DelegateTool::new(agents, None, test_security());
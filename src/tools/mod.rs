@@ -90,6 +90,37 @@ use async_trait::async_trait;
 use std::collections::HashMap;
 use std::sync::Arc;
 
+/// Every tool name [`all_tools_with_runtime`] can register for delegate
+/// sub-agents, whatever the config. Used to validate `allowed_tools` when the
+/// config is saved, before a registry exists.
+pub const DELEGATABLE_TOOL_NAMES: &[&str] = &[
+    "shell",
+    "file_read",
+    "file_write",
+    "glob_search",
+    "cron_add",
+    "cron_list",
+    "cron_remove",
+    "cron_update",
+    "cron_run",
+    "cron_runs",
+    "memory_store",
+    "memory_recall",
+    "memory_forget",
+    "schedule",
+    "proxy_config",
+    "git_operations",
+    "pushover",
+    "browser_open",
+    "browser",
+    "http_request",
+    "web_search_tool",
+    "pdf_read",
+    "screenshot",
+    "image_info",
+    "composio",
+];
+
 #[derive(Clone)]
 struct ArcDelegatingTool {
     inner: Arc<dyn Tool>,
@@ -288,6 +319,8 @@ pub fn all_tools_with_runtime(
             let trimmed_value = value.trim();
             (!trimmed_value.is_empty()).then(|| trimmed_value.to_owned())
         });
+        let registered: Vec<&str> = tool_arcs.iter().map(|tool| tool.name()).collect();
+        delegate::warn_unknown_allowed_tools(&delegate_agents, &registered, "runtime_start");
         let parent_tools = Arc::new(tool_arcs.clone());
         let delegate_tool = DelegateTool::new_with_options(
             delegate_agents,
@@ -411,6 +444,51 @@ mod tests {
         assert!(names.contains(&"proxy_config"));
     }
 
+    #[test]
+    fn delegatable_tool_names_cover_full_registry() {
+        let tmp = TempDir::new().unwrap();
+        let security = Arc::new(SecurityPolicy::default());
+        let mem_cfg = MemoryConfig {
+            backend: "markdown".into(),
+            ..MemoryConfig::default()
+        };
+        let mem: Arc<dyn Memory> =
+            Arc::from(crate::memory::create_memory(&mem_cfg, tmp.path(), None).unwrap());
+
+        let browser = BrowserConfig {
+            enabled: true,
+            allowed_domains: vec!["example.com".into()],
+            ..BrowserConfig::default()
+        };
+        let http = crate::config::HttpRequestConfig {
+            enabled: true,
+            ..crate::config::HttpRequestConfig::default()
+        };
+        let mut cfg = test_config(&tmp);
+        cfg.web_search.enabled = true;
+
+        let tools = all_tools(
+            Arc::new(Config::default()),
+            &security,
+            mem,
+            Some("composio-test-key"),
+            None,
+            &browser,
+            &http,
+            tmp.path(),
+            &HashMap::new(),
+            None,
+            &cfg,
+        );
+        for tool in &tools {
+            assert!(
+                DELEGATABLE_TOOL_NAMES.contains(&tool.name()),
+                "{} missing from DELEGATABLE_TOOL_NAMES",
+                tool.name()
+            );
+        }
+    }
+
     #[test]
     fn default_tools_names() {
         let security = Arc::new(SecurityPolicy::default());
@@ -534,6 +612,7 @@ mod tests {
                 agentic: false,
                 allowed_tools: Vec::new(),
                 max_iterations: 10,
+                region: None,
             },
         );
 